@@ -1,8 +1,16 @@
+pub mod arrangement;
+pub mod art_gallery;
+pub mod boolean;
 pub mod convex_hull;
 pub mod intersection;
+pub mod minkowski;
+pub mod point_location;
 pub mod polygonization;
+pub mod rotating_calipers;
+pub mod shortest_path;
 pub mod triangulation;
 pub mod visibility;
+pub mod voronoi;
 pub mod zhash;
 
 #[doc(inline)]
@@ -0,0 +1,224 @@
+//! Arrangements of line segments.
+//!
+//! Segments are split at every point where they touch another segment and the
+//! pieces form a planar subdivision. Intersections are found with exact
+//! rational arithmetic so the topology is always correct. Intersection points
+//! are rounded with [`PolygonScalar::from_rational`].
+use crate::data::dcel::Dcel;
+use crate::data::{EndPoint, ILineSegment, LineSegment, LineSegmentView, Point};
+use crate::utils::exact::{line_intersection, to_rational};
+use crate::{Intersects, PolygonScalar};
+
+use num::BigRational;
+use std::collections::BTreeSet;
+
+use super::intersection::bentley_ottmann;
+
+type Rational = Point<BigRational, 2>;
+
+struct Segment {
+  id: usize,
+  min: Rational,
+  max: Rational,
+}
+
+impl<'a> From<&'a Segment> for LineSegmentView<'a, BigRational, 2> {
+  fn from(segment: &'a Segment) -> LineSegmentView<'a, BigRational, 2> {
+    LineSegmentView::new(
+      EndPoint::Inclusive(&segment.min),
+      EndPoint::Inclusive(&segment.max),
+    )
+  }
+}
+
+/// Planar subdivision formed by a set of line segments. Every intersection
+/// between two segments becomes a vertex and overlapping segments are merged.
+/// Segments are treated as closed and zero-length segments are ignored.
+///
+/// # Time complexity
+/// $O((n+k) \log n + c (n+k))$ where $k$ is the number of intersections and $c$
+/// is the number of connected components.
+pub fn arrangement<T>(segments: &[LineSegment<T>]) -> Dcel<T>
+where
+  T: PolygonScalar,
+{
+  let segments: Vec<Segment> = segments
+    .iter()
+    .map(|segment| {
+      (
+        to_rational(segment.min.inner()),
+        to_rational(segment.max.inner()),
+      )
+    })
+    .filter(|(min, max)| min != max)
+    .enumerate()
+    .map(|(id, (min, max))| Segment { id, min, max })
+    .collect();
+
+  let mut splits: Vec<Vec<Rational>> = vec![Vec::new(); segments.len()];
+  for (a, b) in bentley_ottmann::segment_intersections(&segments) {
+    match LineSegmentView::from(a).intersect(LineSegmentView::from(b)) {
+      Some(ILineSegment::Crossing) => {
+        let pt = line_intersection(&a.min, &a.max, &b.min, &b.max);
+        splits[a.id].push(pt.clone());
+        splits[b.id].push(pt);
+      }
+      Some(ILineSegment::Overlap(overlap)) => {
+        for id in [a.id, b.id] {
+          splits[id].push((*overlap.min.inner()).clone());
+          splits[id].push((*overlap.max.inner()).clone());
+        }
+      }
+      None => {}
+    }
+  }
+
+  let mut pieces: BTreeSet<(Rational, Rational)> = BTreeSet::new();
+  for (segment, mut points) in segments.iter().zip(splits) {
+    points.retain(|pt| pt > &segment.min && pt < &segment.max);
+    points.push(segment.min.clone());
+    points.push(segment.max.clone());
+    points.sort();
+    points.dedup();
+    for piece in points.windows(2) {
+      pieces.insert((piece[0].clone(), piece[1].clone()));
+    }
+  }
+  let pieces: Vec<LineSegment<BigRational>> = pieces
+    .into_iter()
+    .map(|(min, max)| LineSegment::from(min..=max))
+    .collect();
+  Dcel::from_segments_unchecked(&pieces).map(|v| T::from_rational(&v))
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  use proptest::collection::vec;
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  fn rational_segments(coords: &[((i32, i32), (i32, i32))]) -> Vec<LineSegment<BigRational>> {
+    let pt = |(x, y): (i32, i32)| {
+      Point::new([
+        BigRational::from_integer(x.into()),
+        BigRational::from_integer(y.into()),
+      ])
+    };
+    coords
+      .iter()
+      .map(|&(a, b)| LineSegment::from(pt(a)..=pt(b)))
+      .collect()
+  }
+
+  // V - E + F = 1 + C where C is the number of connected components.
+  fn check_euler<T: PolygonScalar>(dcel: &Dcel<T>) {
+    let components: usize = dcel
+      .iter_faces()
+      .map(|f| dcel.inner_components(f).len())
+      .sum();
+    let edges = dcel.half_edge_count() / 2;
+    assert_eq!(
+      dcel.vertex_count() + dcel.face_count(),
+      edges + 1 + components
+    );
+  }
+
+  #[test]
+  fn grid() {
+    // 3 horizontal and 4 vertical segments.
+    let mut coords = Vec::new();
+    for y in 0..3 {
+      coords.push(((-1, y), (4, y)));
+    }
+    for x in 0..4 {
+      coords.push(((x, -1), (x, 3)));
+    }
+    let dcel = arrangement(&rational_segments(&coords));
+    assert_eq!(dcel.vertex_count(), 3 * 4 + 2 * 3 + 2 * 4);
+    assert_eq!(dcel.face_count(), 2 * 3 + 1);
+    assert!(dcel.validate().is_ok());
+    check_euler(&dcel);
+  }
+
+  #[test]
+  fn star() {
+    let coords = [
+      ((-2, 0), (2, 0)),
+      ((0, -2), (0, 2)),
+      ((-2, -2), (2, 2)),
+      ((-2, 2), (2, -2)),
+    ];
+    let dcel = arrangement(&rational_segments(&coords));
+    assert_eq!(dcel.vertex_count(), 9);
+    assert_eq!(dcel.half_edge_count(), 16);
+    assert_eq!(dcel.face_count(), 1);
+  }
+
+  #[test]
+  fn triangle_with_exact_vertices() {
+    // Three lines whose pairwise intersections are not integer points.
+    let coords = [((0, 0), (3, 1)), ((3, 0), (0, 2)), ((1, -1), (1, 3))];
+    let dcel = arrangement(&rational_segments(&coords));
+    assert_eq!(dcel.face_count(), 2);
+    let inside = dcel
+      .iter_faces()
+      .find(|&f| dcel.outer_component(f).is_some())
+      .unwrap();
+    let corners: Vec<&Point<BigRational>> = dcel
+      .iter_boundary(inside)
+      .map(|e| dcel.point(dcel.origin(e)))
+      .collect();
+    assert_eq!(corners.len(), 3);
+    let pt = |x: (i32, i32), y: (i32, i32)| {
+      Point::new([
+        BigRational::new(x.0.into(), x.1.into()),
+        BigRational::new(y.0.into(), y.1.into()),
+      ])
+    };
+    assert!(corners.contains(&&pt((1, 1), (1, 3))));
+    assert!(corners.contains(&&pt((1, 1), (4, 3))));
+    assert!(corners.contains(&&pt((2, 1), (2, 3))));
+  }
+
+  #[test]
+  fn overlapping() {
+    let coords = [((0, 0), (4, 0)), ((2, 0), (6, 0)), ((1, 0), (3, 0))];
+    let dcel = arrangement(&rational_segments(&coords));
+    // Split at 0, 1, 2, 3, 4 and 6.
+    assert_eq!(dcel.vertex_count(), 6);
+    assert_eq!(dcel.half_edge_count(), 10);
+    assert_eq!(dcel.face_count(), 1);
+  }
+
+  #[test]
+  fn integer_input() {
+    let segments = vec![
+      LineSegment::from((0, 0)..=(4, 4)),
+      LineSegment::from((0, 4)..=(4, 0)),
+    ];
+    let dcel = arrangement(&segments);
+    assert_eq!(dcel.vertex_count(), 5);
+    assert!(dcel
+      .iter_vertices()
+      .any(|v| dcel.point(v) == &Point::new([2, 2])));
+  }
+
+  #[proptest]
+  fn arrangement_prop(
+    #[strategy(vec(any::<((i8, i8), (i8, i8))>(), 0..8))] coords: Vec<((i8, i8), (i8, i8))>,
+  ) {
+    let coords: Vec<_> = coords
+      .into_iter()
+      .map(|((a, b), (c, d))| ((a.into(), b.into()), (c.into(), d.into())))
+      .collect();
+    let dcel = arrangement(&rational_segments(&coords));
+    prop_assert!(dcel.validate().is_ok());
+    check_euler(&dcel);
+  }
+}
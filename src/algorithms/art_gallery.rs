@@ -0,0 +1,248 @@
+//! Art gallery problem: place guards at the vertices of a simple polygon so
+//! that every point of the polygon is seen by at least one guard.
+//!
+//! The vertices of a triangulated polygon can be colored with three colors so
+//! that every triangle has one corner of each color. Every triangle (and so
+//! the whole polygon) is seen by the vertices of any single color, and the
+//! least used color has at most $\lfloor n/3 \rfloor$ vertices.
+use crate::data::{LineSegment, Point, PointId, Polygon};
+use crate::utils::exact::{signed_area_2x, to_rational};
+use crate::PolygonScalar;
+
+use num::BigRational;
+
+use super::boolean;
+use super::triangulation::Triangulation;
+use super::visibility::weak::get_weak_visibility_polygon;
+
+/// Vertex guards that see the whole polygon. At most $\lfloor n/3 \rfloor$
+/// guards are returned, sorted by their index.
+///
+/// # Time complexity
+/// $O(n^2)$
+///
+/// # Panics
+///
+/// Panics if the polygon has holes.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::art_gallery::{guards, is_guarded};
+/// # use rgeometry::data::{Point, Polygon};
+/// # use num::BigRational;
+/// let polygon = Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([6, 0]),
+///   Point::new([6, 6]),
+///   Point::new([4, 6]),
+///   Point::new([4, 2]),
+///   Point::new([2, 2]),
+///   Point::new([2, 6]),
+///   Point::new([0, 6]),
+/// ])
+/// .unwrap()
+/// .map(|v| BigRational::from_integer(v.into()));
+/// let guards = guards(&polygon);
+/// assert!(guards.len() <= 8 / 3);
+/// assert!(is_guarded(&polygon, &guards));
+/// assert!(!is_guarded(&polygon, &[]));
+/// ```
+pub fn guards<T>(polygon: &Polygon<T>) -> Vec<PointId>
+where
+  T: PolygonScalar,
+{
+  assert_eq!(
+    polygon.rings.len(),
+    1,
+    "Guards can only be placed in polygons without holes."
+  );
+  let colors = three_coloring(polygon);
+  let mut classes = vec![Vec::new(); 3];
+  for cursor in polygon.iter_boundary() {
+    let pid = cursor.point_id();
+    classes[colors[pid.usize()]].push(pid);
+  }
+  let mut guards = classes.into_iter().min_by_key(|class| class.len()).unwrap();
+  guards.sort_unstable();
+  guards
+}
+
+/// Check that the guards see the whole polygon. The visibility polygons of
+/// the guards are merged and their area is compared with the area of the
+/// polygon. Everything is computed with exact arithmetic, so the answer is
+/// exact for fixed-width scalars as well.
+///
+/// # Time complexity
+/// $O(g n^3 + (m+k) \cdot m)$ where $g$ is the number of guards, $m$ is the
+/// total number of vertices of their visibility polygons and $k$ is the
+/// number of intersections between them.
+///
+/// # Panics
+///
+/// Panics if the polygon has holes.
+pub fn is_guarded<T>(polygon: &Polygon<T>, guards: &[PointId]) -> bool
+where
+  T: PolygonScalar,
+{
+  // A guard sees what is weakly visible from the segment that starts and ends
+  // at its vertex. The visibility polygons are exact.
+  let visible: Vec<Polygon<BigRational>> = guards
+    .iter()
+    .map(|&pid| {
+      let point = polygon.point(pid);
+      let segment = LineSegment::from(point.clone()..=point.clone());
+      get_weak_visibility_polygon(&segment, polygon).expect("Guards are vertices of the polygon")
+    })
+    .collect();
+  if visible.is_empty() {
+    return false;
+  }
+  let covered = boolean::union_all(&visible);
+  let boundary: Vec<Point<BigRational>> = polygon
+    .iter_boundary()
+    .map(|cursor| to_rational(cursor.point()))
+    .collect();
+  covered.signed_area_2x::<BigRational>() == signed_area_2x(&boundary)
+}
+
+// Color the vertices so that the corners of every triangle have different
+// colors. The dual graph of the triangulation is a tree so the colors are
+// forced once the first triangle is colored.
+fn three_coloring<T>(polygon: &Polygon<T>) -> Vec<usize>
+where
+  T: PolygonScalar,
+{
+  let triangulation = Triangulation::from_triangles(
+    polygon
+      .triangulate()
+      .map(|(a, b, c)| [a.point_id(), b.point_id(), c.point_id()])
+      .collect(),
+  );
+  let mut colors: Vec<Option<usize>> = vec![None; polygon.points.len()];
+  let mut visited = vec![false; triangulation.len()];
+  let mut stack = vec![0];
+  visited[0] = true;
+  while let Some(idx) = stack.pop() {
+    let corners = triangulation.triangles[idx];
+    // Only the first triangle has more than one uncolored corner.
+    for corner in corners {
+      if colors[corner.usize()].is_none() {
+        let used: Vec<usize> = corners
+          .iter()
+          .filter_map(|other| colors[other.usize()])
+          .collect();
+        colors[corner.usize()] = (0..3).find(|color| !used.contains(color));
+      }
+    }
+    for next in triangulation.neighbours(idx).iter().flatten() {
+      if !visited[*next] {
+        visited[*next] = true;
+        stack.push(*next);
+      }
+    }
+  }
+  colors
+    .into_iter()
+    .map(|color| color.expect("Every vertex is a corner of a triangle"))
+    .collect()
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::testing::rational;
+
+  use test_strategy::proptest;
+
+  #[proptest]
+  fn proper_coloring(#[any((3..50, ()))] polygon: Polygon<i8>) {
+    let colors = three_coloring(&polygon);
+    for (a, b, c) in polygon.triangulate() {
+      let (a, b, c) = (
+        colors[a.point_id().usize()],
+        colors[b.point_id().usize()],
+        colors[c.point_id().usize()],
+      );
+      assert!(a != b && b != c && c != a);
+    }
+  }
+
+  #[proptest]
+  fn guards_see_everything(#[any((3..15, ()))] polygon: Polygon<i8>) {
+    let polygon = rational(polygon);
+    let guards = guards(&polygon);
+    assert!(guards.len() <= polygon.points.len() / 3);
+    assert!(is_guarded(&polygon, &guards));
+  }
+
+  #[proptest]
+  fn guards_see_everything_i8(#[any((3..15, ()))] polygon: Polygon<i8>) {
+    let guards = guards(&polygon);
+    assert!(is_guarded(&polygon, &guards));
+    for guard in &guards {
+      let rest: Vec<PointId> = guards.iter().copied().filter(|g| g != guard).collect();
+      assert_eq!(
+        is_guarded(&polygon, &rest),
+        is_guarded(&rational(polygon.clone()), &rest)
+      );
+    }
+  }
+
+  #[test]
+  fn large_i8() {
+    // The window from (120, -100) past (10, 0) ends at (-101.1, 101). Rounding
+    // it to (-101, 101) would hide the sliver of the polygon behind it.
+    let polygon = Polygon::new(vec![
+      Point::new([-120i8, -120]),
+      Point::new([120, -120]),
+      Point::new([120, -100]),
+      Point::new([120, 0]),
+      Point::new([10, 0]),
+      Point::new([-101, 101]),
+      Point::new([-120, 101]),
+    ])
+    .unwrap();
+    let guard = |x, y| {
+      polygon
+        .iter_boundary()
+        .find(|cursor| cursor.point() == &Point::new([x, y]))
+        .unwrap()
+        .point_id()
+    };
+    assert!(!is_guarded(&polygon, &[guard(120, -100)]));
+    assert!(is_guarded(&polygon, &[guard(10, 0)]));
+    assert!(is_guarded(&polygon, &[guard(120, -100), guard(-120, 101)]));
+  }
+
+  #[test]
+  fn comb() {
+    let polygon = rational(
+      Polygon::new(vec![
+        Point::new([0, 0]),
+        Point::new([9, 0]),
+        Point::new([9, 4]),
+        Point::new([8, 4]),
+        Point::new([7, 1]),
+        Point::new([6, 4]),
+        Point::new([5, 4]),
+        Point::new([4, 1]),
+        Point::new([3, 4]),
+        Point::new([2, 4]),
+        Point::new([1, 1]),
+        Point::new([0, 4]),
+      ])
+      .unwrap(),
+    );
+    let guards = guards(&polygon);
+    assert!(guards.len() <= 4);
+    assert!(is_guarded(&polygon, &guards));
+    // The corner can't see into the teeth on the right.
+    let corner = polygon.iter_boundary().next().unwrap().point_id();
+    assert!(!is_guarded(&polygon, &[corner]));
+  }
+}
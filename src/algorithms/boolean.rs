@@ -0,0 +1,597 @@
+//! Boolean set operations on polygons.
+//!
+//! Edges of both polygons are split at every intersection and each piece is
+//! classified as being inside or outside of the other polygon. All of this is
+//! done with exact rational arithmetic so the topology of the result is always
+//! correct, even for floating point coordinates. New vertices (intersections
+//! between edges) are rounded with [`PolygonScalar::from_rational`].
+use crate::data::{EndPoint, LineSegmentView, MultiPolygon, Point, Polygon};
+use crate::utils::exact::{cross, dot, line_intersection, signed_area_2x, sub, to_rational, Vec2};
+use crate::PolygonScalar;
+
+use num::{BigRational, Signed, Zero};
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::intersection::bentley_ottmann;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+  Union,
+  Intersection,
+  Difference,
+  Xor,
+}
+
+impl BooleanOp {
+  fn apply(self, in_a: bool, in_b: bool) -> bool {
+    match self {
+      BooleanOp::Union => in_a || in_b,
+      BooleanOp::Intersection => in_a && in_b,
+      BooleanOp::Difference => in_a && !in_b,
+      BooleanOp::Xor => in_a != in_b,
+    }
+  }
+}
+
+/// Area covered by either `a` or `b`.
+///
+/// # Time complexity
+/// $O((n+k) \cdot n)$ where $n$ is the total number of vertices and $k$ is the
+/// number of edge intersections.
+pub fn union<T: PolygonScalar>(a: &Polygon<T>, b: &Polygon<T>) -> MultiPolygon<T> {
+  boolean_op(a, b, BooleanOp::Union)
+}
+
+/// Area covered by both `a` and `b`.
+///
+/// # Time complexity
+/// $O((n+k) \cdot n)$ where $n$ is the total number of vertices and $k$ is the
+/// number of edge intersections.
+pub fn intersection<T: PolygonScalar>(a: &Polygon<T>, b: &Polygon<T>) -> MultiPolygon<T> {
+  boolean_op(a, b, BooleanOp::Intersection)
+}
+
+/// Area covered by `a` but not by `b`.
+///
+/// # Time complexity
+/// $O((n+k) \cdot n)$ where $n$ is the total number of vertices and $k$ is the
+/// number of edge intersections.
+pub fn difference<T: PolygonScalar>(a: &Polygon<T>, b: &Polygon<T>) -> MultiPolygon<T> {
+  boolean_op(a, b, BooleanOp::Difference)
+}
+
+/// Area covered by exactly one of `a` and `b`.
+///
+/// # Time complexity
+/// $O((n+k) \cdot n)$ where $n$ is the total number of vertices and $k$ is the
+/// number of edge intersections.
+pub fn xor<T: PolygonScalar>(a: &Polygon<T>, b: &Polygon<T>) -> MultiPolygon<T> {
+  boolean_op(a, b, BooleanOp::Xor)
+}
+
+/// Combine two polygons (with or without holes). The components of the result
+/// may touch at vertices. Edges are split at their intersections with a sweep
+/// but each piece that isn't part of a polygon's boundary is classified with a
+/// linear point-in-polygon test.
+///
+/// # Time complexity
+/// $O((n+k) \cdot n)$ where $n$ is the total number of vertices and $k$ is the
+/// number of edge intersections.
+pub fn boolean_op<T: PolygonScalar>(
+  a: &Polygon<T>,
+  b: &Polygon<T>,
+  op: BooleanOp,
+) -> MultiPolygon<T> {
+  let rings_a = rational_rings(a);
+  let rings_b = rational_rings(b);
+  let originals: BTreeSet<&Rational> = rings_a.iter().chain(&rings_b).flatten().collect();
+
+  let mut edges = Vec::new();
+  add_edges(&mut edges, &rings_a, 0);
+  add_edges(&mut edges, &rings_b, 1);
+
+  let mut boundary = Vec::new();
+  for ((p, q), sides) in split_edges(&edges) {
+    let mid = midpoint(&p, &q);
+    let (a_left, a_right) = classify(&sides, 0, &rings_a, &mid);
+    let (b_left, b_right) = classify(&sides, 1, &rings_b, &mid);
+    let left = op.apply(a_left, b_left);
+    let right = op.apply(a_right, b_right);
+    if left && !right {
+      boundary.push((p, q));
+    } else if right && !left {
+      boundary.push((q, p));
+    }
+  }
+  build(&boundary, &originals)
+}
+
+/// Area covered by any of the polygons. Colinear vertices are removed even if
+/// they are vertices of the input.
+///
+/// # Time complexity
+/// $O((n+k) \cdot n)$ where $n$ is the total number of vertices and $k$ is the
+/// number of edge intersections.
+pub(crate) fn union_all<T: PolygonScalar>(polys: &[Polygon<T>]) -> MultiPolygon<T> {
+  let rings: Vec<Vec<Vec<Rational>>> = polys.iter().map(rational_rings).collect();
+
+  let mut edges = Vec::new();
+  for (source, rings) in rings.iter().enumerate() {
+    add_edges(&mut edges, rings, source);
+  }
+
+  let mut boundary = Vec::new();
+  for ((p, q), sides) in split_edges(&edges) {
+    let mid = midpoint(&p, &q);
+    let (mut left, mut right) = (false, false);
+    for (source, rings) in rings.iter().enumerate() {
+      let (inside_left, inside_right) = classify(&sides, source, rings, &mid);
+      left |= inside_left;
+      right |= inside_right;
+    }
+    if left && !right {
+      boundary.push((p, q));
+    } else if right && !left {
+      boundary.push((q, p));
+    }
+  }
+  build(&boundary, &BTreeSet::new())
+}
+
+// Turn the directed boundary edges of the result into polygons.
+fn build<T: PolygonScalar>(
+  boundary: &[(Rational, Rational)],
+  originals: &BTreeSet<&Rational>,
+) -> MultiPolygon<T> {
+  // Vertices where rings touch must be kept in every ring that uses them.
+  let mut degree: BTreeMap<&Rational, usize> = BTreeMap::new();
+  for (src, _dst) in boundary {
+    *degree.entry(src).or_default() += 1;
+  }
+  let keep: BTreeSet<&Rational> = originals
+    .iter()
+    .copied()
+    .chain(degree.into_iter().filter(|&(_, n)| n > 1).map(|(pt, _)| pt))
+    .collect();
+  let rings = trace_rings(boundary)
+    .into_iter()
+    .map(|ring| simplify(ring, &keep))
+    .filter(|ring| ring.len() >= 3)
+    .collect();
+  assemble(rings)
+}
+
+type Rational = Point<BigRational, 2>;
+
+// Directed edge with the interior of its polygon on the left.
+struct Edge {
+  id: usize,
+  src: Rational,
+  dst: Rational,
+  // Index of the polygon the edge came from.
+  source: usize,
+}
+
+impl<'a> From<&'a Edge> for LineSegmentView<'a, BigRational, 2> {
+  fn from(edge: &'a Edge) -> LineSegmentView<'a, BigRational, 2> {
+    LineSegmentView::new(
+      EndPoint::Inclusive(&edge.src),
+      EndPoint::Inclusive(&edge.dst),
+    )
+  }
+}
+
+// For an undirected piece of an edge: Whether the interior of each polygon is
+// on the left side when walking from the smallest to the largest endpoint.
+// Polygons are missing if the piece is not part of their boundary.
+type Sides = BTreeMap<usize, bool>;
+
+fn rational_rings<T: PolygonScalar>(poly: &Polygon<T>) -> Vec<Vec<Rational>> {
+  poly
+    .rings
+    .iter()
+    .map(|ring| {
+      ring
+        .iter()
+        .map(|&pid| to_rational(poly.point(pid)))
+        .collect()
+    })
+    .collect()
+}
+
+fn add_edges(edges: &mut Vec<Edge>, rings: &[Vec<Rational>], source: usize) {
+  for ring in rings {
+    for (i, src) in ring.iter().enumerate() {
+      let dst = &ring[(i + 1) % ring.len()];
+      if src != dst {
+        edges.push(Edge {
+          id: edges.len(),
+          src: src.clone(),
+          dst: dst.clone(),
+          source,
+        });
+      }
+    }
+  }
+}
+
+// Split edges at every point where they touch another edge. Overlapping pieces
+// are merged.
+fn split_edges(edges: &[Edge]) -> BTreeMap<(Rational, Rational), Sides> {
+  let mut splits: Vec<Vec<Rational>> = vec![Vec::new(); edges.len()];
+  for (e, f) in bentley_ottmann::segment_intersections(edges) {
+    let o1 = Point::orient(&e.src, &e.dst, &f.src);
+    let o2 = Point::orient(&e.src, &e.dst, &f.dst);
+    if o1.is_colinear() && o2.is_colinear() {
+      splits[e.id].extend([f.src.clone(), f.dst.clone()]);
+      splits[f.id].extend([e.src.clone(), e.dst.clone()]);
+    } else {
+      let pt = line_intersection(&e.src, &e.dst, &f.src, &f.dst);
+      splits[e.id].push(pt.clone());
+      splits[f.id].push(pt);
+    }
+  }
+
+  let mut pieces: BTreeMap<(Rational, Rational), Sides> = BTreeMap::new();
+  for (edge, mut points) in edges.iter().zip(splits) {
+    let (min, max) = if edge.src < edge.dst {
+      (&edge.src, &edge.dst)
+    } else {
+      (&edge.dst, &edge.src)
+    };
+    points.retain(|pt| pt > min && pt < max);
+    points.push(min.clone());
+    points.push(max.clone());
+    points.sort();
+    points.dedup();
+    // Interior is on the left when walking from 'min' to 'max'.
+    let left = &edge.src == min;
+    for piece in points.windows(2) {
+      pieces
+        .entry((piece[0].clone(), piece[1].clone()))
+        .or_default()
+        .insert(edge.source, left);
+    }
+  }
+  pieces
+}
+
+// (inside left, inside right) of a piece of an edge for a single polygon.
+fn classify(sides: &Sides, source: usize, rings: &[Vec<Rational>], mid: &Rational) -> (bool, bool) {
+  match sides.get(&source) {
+    Some(&left) => (left, !left),
+    None => {
+      let inside = contains(rings, mid);
+      (inside, inside)
+    }
+  }
+}
+
+fn midpoint(p: &Rational, q: &Rational) -> Rational {
+  Point::new([
+    (&p.array[0] + &q.array[0]) / BigRational::from_integer(2.into()),
+    (&p.array[1] + &q.array[1]) / BigRational::from_integer(2.into()),
+  ])
+}
+
+// Even-odd test. The point must not lie on the boundary.
+fn contains(rings: &[Vec<Rational>], pt: &Rational) -> bool {
+  rings.iter().filter(|ring| odd_crossings(ring, pt)).count() % 2 == 1
+}
+
+// Does a ray going right from 'pt' cross the ring an odd number of times?
+fn odd_crossings(ring: &[Rational], pt: &Rational) -> bool {
+  let mut odd = false;
+  for (i, p) in ring.iter().enumerate() {
+    let q = &ring[(i + 1) % ring.len()];
+    let (lo, hi) = if p.array[1] <= q.array[1] {
+      (p, q)
+    } else {
+      (q, p)
+    };
+    if lo.array[1] <= pt.array[1] && pt.array[1] < hi.array[1] && Point::orient(lo, hi, pt).is_ccw()
+    {
+      odd = !odd;
+    }
+  }
+  odd
+}
+
+// Link directed edges into closed rings. When several edges leave the same
+// vertex, take the first one clockwise from the incoming edge. This keeps the
+// interior on the left and splits rings that touch at a vertex. A ring can
+// still pass through a vertex twice if it encloses a hole that touches the
+// boundary, so such rings are split in two.
+fn trace_rings(edges: &[(Rational, Rational)]) -> Vec<Vec<Rational>> {
+  let mut outgoing: BTreeMap<&Rational, Vec<usize>> = BTreeMap::new();
+  for (idx, (src, _dst)) in edges.iter().enumerate() {
+    outgoing.entry(src).or_default().push(idx);
+  }
+  let mut used = vec![false; edges.len()];
+  let mut rings = Vec::new();
+  for start in 0..edges.len() {
+    if used[start] {
+      continue;
+    }
+    let mut ring = Vec::new();
+    let mut current = start;
+    loop {
+      used[current] = true;
+      let (src, dst) = &edges[current];
+      ring.push(src.clone());
+      let incoming = sub(src, dst);
+      let next = outgoing
+        .get(dst)
+        .into_iter()
+        .flatten()
+        .copied()
+        .filter(|&idx| !used[idx] || idx == start)
+        .min_by(|&x, &y| cmp_clockwise(&incoming, &sub(&edges[x].1, dst), &sub(&edges[y].1, dst)));
+      match next {
+        Some(next) if next != start => current = next,
+        _ => break,
+      }
+    }
+    split_ring(ring, &mut rings);
+  }
+  rings
+}
+
+fn split_ring(mut ring: Vec<Rational>, rings: &mut Vec<Vec<Rational>>) {
+  let repeated = (1..ring.len()).find_map(|j| (0..j).find(|&i| ring[i] == ring[j]).map(|i| (i, j)));
+  match repeated {
+    Some((i, j)) => {
+      let inner: Vec<Rational> = ring.drain(i..j).collect();
+      split_ring(inner, rings);
+      split_ring(ring, rings);
+    }
+    None => rings.push(ring),
+  }
+}
+
+// Order directions by the clockwise angle from 'start', in the range (0, 2pi].
+fn cmp_clockwise(start: &Vec2, a: &Vec2, b: &Vec2) -> Ordering {
+  let quadrant = |v: &Vec2| {
+    let c = cross(start, v);
+    if c.is_negative() {
+      0
+    } else if c.is_zero() && dot(start, v).is_negative() {
+      1
+    } else if c.is_positive() {
+      2
+    } else {
+      3
+    }
+  };
+  quadrant(a).cmp(&quadrant(b)).then_with(|| {
+    let c = cross(a, b);
+    if c.is_negative() {
+      Ordering::Less
+    } else if c.is_positive() {
+      Ordering::Greater
+    } else {
+      Ordering::Equal
+    }
+  })
+}
+
+// Remove vertices that were introduced by splitting edges and which turned out
+// to be in the middle of a straight line.
+fn simplify(mut ring: Vec<Rational>, originals: &BTreeSet<&Rational>) -> Vec<Rational> {
+  loop {
+    let n = ring.len();
+    let redundant = (0..n).find(|&i| {
+      let prev = &ring[(i + n - 1) % n];
+      let next = &ring[(i + 1) % n];
+      !originals.contains(&ring[i]) && Point::orient(prev, &ring[i], next).is_colinear()
+    });
+    match redundant {
+      Some(i) if n > 3 => {
+        ring.remove(i);
+      }
+      _ => return ring,
+    }
+  }
+}
+
+// Locate a point relative to a ring. 'None' if the point is on the boundary.
+fn ring_contains(ring: &[Rational], pt: &Rational) -> Option<bool> {
+  for (i, p) in ring.iter().enumerate() {
+    let q = &ring[(i + 1) % ring.len()];
+    if LineSegmentView::from(p..q).contains(pt) || pt == q {
+      return None;
+    }
+  }
+  Some(odd_crossings(ring, pt))
+}
+
+// Match holes (cw rings) with the smallest boundary (ccw ring) that contains them.
+fn assemble<T: PolygonScalar>(rings: Vec<Vec<Rational>>) -> MultiPolygon<T> {
+  let mut boundaries: Vec<(BigRational, Vec<Vec<Rational>>)> = Vec::new();
+  let mut holes = Vec::new();
+  for ring in rings {
+    let area = signed_area_2x(&ring);
+    if area.is_positive() {
+      boundaries.push((area, vec![ring]));
+    } else if area.is_negative() {
+      holes.push(ring);
+    }
+  }
+  for hole in holes {
+    let owner = boundaries
+      .iter()
+      .enumerate()
+      .filter(|(_, (_, rings))| {
+        hole
+          .iter()
+          .find_map(|pt| ring_contains(&rings[0], pt))
+          .unwrap_or(false)
+      })
+      .min_by(|(_, (a, _)), (_, (b, _))| a.cmp(b))
+      .map(|(idx, _)| idx)
+      .expect("every hole must be inside a boundary");
+    boundaries[owner].1.push(hole);
+  }
+  let components = boundaries
+    .into_iter()
+    .map(|(_, rings)| {
+      Polygon::new_rings_unchecked(
+        rings
+          .into_iter()
+          .map(|ring| {
+            ring
+              .iter()
+              .map(|pt| {
+                Point::new([
+                  T::from_rational(&pt.array[0]),
+                  T::from_rational(&pt.array[1]),
+                ])
+              })
+              .collect()
+          })
+          .collect(),
+      )
+    })
+    .collect();
+  MultiPolygon::new_unchecked(components)
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::testing::{rational, square};
+
+  use ordered_float::OrderedFloat;
+  use test_strategy::proptest;
+
+  // Area of all components, with holes subtracted.
+  fn area(polys: &[Polygon<BigRational>]) -> BigRational {
+    polys
+      .iter()
+      .flat_map(|poly| {
+        poly.rings.iter().map(move |ring| {
+          let pts: Vec<Rational> = ring.iter().map(|&pid| poly.point(pid).clone()).collect();
+          signed_area_2x(&pts)
+        })
+      })
+      .sum::<BigRational>()
+      / BigRational::from_integer(2.into())
+  }
+
+  fn int(val: i32) -> BigRational {
+    BigRational::from_integer(val.into())
+  }
+
+  #[test]
+  fn overlapping_squares() {
+    let a = rational(square(0, 0, 2));
+    let b = rational(square(1, 1, 2));
+    assert_eq!(area(&union(&a, &b)), int(7));
+    assert_eq!(area(&intersection(&a, &b)), int(1));
+    assert_eq!(area(&difference(&a, &b)), int(3));
+    assert_eq!(area(&xor(&a, &b)), int(6));
+    assert_eq!(union(&a, &b).len(), 1);
+    assert_eq!(union(&a, &b)[0].iter_boundary().count(), 8);
+    assert_eq!(xor(&a, &b).len(), 2);
+  }
+
+  #[test]
+  fn disjoint_squares() {
+    let a = rational(square(0, 0, 2));
+    let b = rational(square(3, 3, 2));
+    assert_eq!(union(&a, &b).len(), 2);
+    assert!(intersection(&a, &b).is_empty());
+    assert_eq!(area(&difference(&a, &b)), int(4));
+  }
+
+  #[test]
+  fn shared_edge() {
+    let a = rational(square(0, 0, 2));
+    let b = rational(square(2, 0, 2));
+    let u = union(&a, &b);
+    assert_eq!(u.len(), 1);
+    assert_eq!(area(&u), int(8));
+    assert!(intersection(&a, &b).is_empty());
+  }
+
+  #[test]
+  fn identical() {
+    let a = rational(square(0, 0, 2));
+    assert_eq!(area(&union(&a, &a)), int(4));
+    assert_eq!(area(&intersection(&a, &a)), int(4));
+    assert!(difference(&a, &a).is_empty());
+    assert!(xor(&a, &a).is_empty());
+  }
+
+  #[test]
+  fn hole() {
+    let a = rational(square(0, 0, 6));
+    let b = rational(square(2, 2, 2));
+    let diff = difference(&a, &b);
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff[0].rings.len(), 2);
+    assert_eq!(area(&diff), int(32));
+    // Filling the hole gives back the original square.
+    let filled = union(&diff[0], &b);
+    assert_eq!(filled.len(), 1);
+    assert_eq!(filled[0].rings.len(), 1);
+    assert_eq!(area(&filled), int(36));
+    // Nothing of 'b' is left in the hole.
+    assert!(intersection(&diff[0], &b).is_empty());
+  }
+
+  #[test]
+  fn hole_touching_boundary() {
+    let a = rational(square(0, 0, 6));
+    let b = Polygon::new(vec![
+      Point::new([0, 3]),
+      Point::new([3, 2]),
+      Point::new([3, 4]),
+    ])
+    .unwrap();
+    let diff = difference(&a, &rational(b));
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff[0].rings.len(), 2);
+    assert_eq!(diff[0].validate(), Ok(()));
+    assert_eq!(area(&diff), int(33));
+  }
+
+  #[test]
+  fn touching_corners() {
+    let a = rational(square(0, 0, 2));
+    let b = rational(square(2, 2, 2));
+    let u = union(&a, &b);
+    assert_eq!(u.len(), 2);
+    for poly in &u {
+      assert_eq!(poly.validate(), Ok(()));
+    }
+  }
+
+  #[test]
+  fn floating_point() {
+    let a = square(OrderedFloat(0.), OrderedFloat(0.), OrderedFloat(2.));
+    let b = a.clone().map(|v| v + OrderedFloat(0.5));
+    let i = intersection(&a, &b);
+    assert_eq!(i.len(), 1);
+    assert_eq!(i[0].signed_area::<OrderedFloat<f64>>(), OrderedFloat(2.25));
+  }
+
+  #[proptest]
+  fn area_identities(#[any((3..20, ()))] a: Polygon<i8>, #[any((3..20, ()))] b: Polygon<i8>) {
+    let (a, b) = (rational(a), rational(b));
+    let area_a = area(std::slice::from_ref(&a));
+    let area_b = area(std::slice::from_ref(&b));
+    let union = union(&a, &b);
+    let inter = intersection(&a, &b);
+    let diff = difference(&a, &b);
+    let xor = xor(&a, &b);
+    assert_eq!(area(&union) + area(&inter), &area_a + &area_b);
+    assert_eq!(area(&diff) + area(&inter), area_a);
+    assert_eq!(area(&xor), area(&union) - area(&inter));
+    for poly in union.iter().chain(&inter).chain(&diff).chain(&xor) {
+      assert_eq!(poly.validate(), Ok(()));
+    }
+  }
+}
@@ -0,0 +1,208 @@
+use crate::data::{Point, Polygon, PolygonConvex};
+use crate::{Error, Orientation, PolygonScalar, TotalOrd};
+
+use std::cmp::Ordering;
+
+use super::monotone_chain;
+
+// https://en.wikipedia.org/wiki/Chan%27s_algorithm
+
+/// Convex hull of a set of points.
+///
+/// [Chan's algorithm][wiki] splits the points into groups of size $m$, finds
+/// the convex hull of each group, and then wraps the groups with a Jarvis
+/// march that finds the next vertex of every group hull by binary search. The
+/// march gives up after $m$ steps and starts over with $m^2$ so the running
+/// time only depends on the number of vertices in the output.
+///
+/// # Errors
+/// Will return an error iff the input set contains less than three distinct points.
+///
+/// # Properties
+/// * No points from the input set will be outside the returned convex polygon.
+/// * All vertices in the convex polygon are from the input set.
+///
+/// # Time complexity
+/// $O(n \log h)$ where $h$ is the number of vertices in the convex hull.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::convex_hull::chan::convex_hull;
+/// # use rgeometry::data::Point;
+/// let points = vec![
+///   Point::new([0, 0]),
+///   Point::new([2, 0]),
+///   Point::new([1, 1]),
+///   Point::new([2, 2]),
+///   Point::new([0, 2]),
+/// ];
+/// let hull = convex_hull(points).unwrap();
+/// assert_eq!(hull.iter().count(), 4);
+/// ```
+///
+/// [wiki]: https://en.wikipedia.org/wiki/Chan%27s_algorithm
+pub fn convex_hull<T>(pts: Vec<Point<T>>) -> Result<PolygonConvex<T>, Error>
+where
+  T: PolygonScalar,
+{
+  let start = pts
+    .iter()
+    .min_by(|a, b| TotalOrd::total_cmp(&(a.y_coord(), a.x_coord()), &(b.y_coord(), b.x_coord())))
+    .ok_or(Error::InsufficientVertices)?
+    .clone();
+  let mut m: usize = 4;
+  loop {
+    let groups: Vec<Vec<Point<T>>> = pts
+      .chunks(m)
+      .map(|chunk| monotone_chain::hull_points(chunk.to_vec()))
+      .collect();
+    if let Some(hull) = wrap(&start, &groups, m) {
+      if hull.len() < 3 {
+        return Err(Error::InsufficientVertices);
+      }
+      return Ok(PolygonConvex::new_unchecked(Polygon::new_unchecked(hull)));
+    }
+    m = m.saturating_mul(m);
+  }
+}
+
+// Jarvis march around the group hulls. Gives up after 'limit' steps.
+fn wrap<T>(start: &Point<T>, groups: &[Vec<Point<T>>], limit: usize) -> Option<Vec<Point<T>>>
+where
+  T: PolygonScalar,
+{
+  let mut hull = vec![start.clone()];
+  for _ in 0..limit {
+    let current = &hull[hull.len() - 1];
+    let next = groups
+      .iter()
+      .filter(|group| !group.is_empty())
+      .map(|group| &group[tangent(current, group)])
+      .min_by(|a, b| cmp_turn(current, a, b))?;
+    if next == start || next == current {
+      return Some(hull);
+    }
+    hull.push(next.clone());
+  }
+  None
+}
+
+// Order points by how far they turn clockwise from the origin. Points in the
+// same direction are ordered from far to near, and the origin itself is last.
+fn cmp_turn<T>(origin: &Point<T>, a: &Point<T>, b: &Point<T>) -> Ordering
+where
+  T: PolygonScalar,
+{
+  match (a == origin, b == origin) {
+    (true, true) => Ordering::Equal,
+    (true, false) => Ordering::Greater,
+    (false, true) => Ordering::Less,
+    (false, false) => match Point::orient(origin, a, b) {
+      Orientation::CounterClockWise => Ordering::Less,
+      Orientation::ClockWise => Ordering::Greater,
+      Orientation::CoLinear => origin.cmp_distance_to(b, a),
+    },
+  }
+}
+
+// Index of the first vertex of a convex hull by 'cmp_turn'. Seen from a point
+// outside the hull, or from one of its vertices, the vertices go up and down
+// once around the hull so the smallest one can be found by binary search.
+fn tangent<T>(origin: &Point<T>, hull: &[Point<T>]) -> usize
+where
+  T: PolygonScalar,
+{
+  let n = hull.len();
+  let up = |i: usize| cmp_turn(origin, &hull[i], &hull[(i + 1) % n]) == Ordering::Less;
+  let below_first = |i: usize| cmp_turn(origin, &hull[i], &hull[0]) == Ordering::Less;
+  // The vertices after the first one are going up, then down, and then up
+  // again to the smallest vertex. Find the start of the last stretch.
+  let is_after = |i: usize| {
+    if up(0) {
+      up(i) && below_first(i)
+    } else {
+      up(i) || !below_first(i)
+    }
+  };
+  let (mut lower, mut upper) = (1, n);
+  while lower < upper {
+    let middle = (lower + upper) / 2;
+    if is_after(middle) {
+      upper = middle;
+    } else {
+      lower = middle + 1;
+    }
+  }
+  lower % n
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::algorithms::convex_hull::graham_scan;
+  use crate::testing::*;
+
+  use claims::assert_ok;
+  use num_bigint::BigInt;
+
+  use proptest::collection::*;
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  fn check<T: PolygonScalar>(pts: Vec<Point<T>>) {
+    match (convex_hull(pts.clone()), graham_scan::convex_hull(pts)) {
+      (Ok(a), Ok(b)) => {
+        assert_eq!(a.validate().err(), None);
+        assert!(a.equals(&b));
+      }
+      (a, b) => assert_eq!(a.err(), b.err()),
+    }
+  }
+
+  #[test]
+  fn convex_hull_circle() {
+    // Every point is on the hull so the march has to start over.
+    let points: Vec<Point<i64>> = (0..100)
+      .map(|i| {
+        let angle = i as f64 / 100.0 * std::f64::consts::TAU;
+        Point::new([
+          (angle.cos() * 1e6).round() as i64,
+          (angle.sin() * 1e6).round() as i64,
+        ])
+      })
+      .collect();
+    let poly = convex_hull(points).unwrap();
+    assert_ok!(poly.validate());
+    assert_eq!(poly.iter().count(), 100);
+  }
+
+  #[test]
+  fn convex_hull_insufficient() {
+    let dups = vec![Point::new([0, 0]), Point::new([2, 2])].repeat(3);
+    assert_eq!(convex_hull(dups).err(), Some(Error::InsufficientVertices));
+    let line: Vec<Point<i8>> = (0..20).map(|i| Point::new([i, 2 * i])).collect();
+    assert_eq!(convex_hull(line).err(), Some(Error::InsufficientVertices));
+  }
+
+  #[proptest]
+  fn matches_graham_scan(#[strategy(vec(any_r(), 0..100))] pts: Vec<Point<BigInt>>) {
+    check(pts);
+  }
+
+  #[proptest]
+  fn matches_graham_scan_i8(#[strategy(vec(any::<Point<i8>>(), 0..100))] pts: Vec<Point<i8>>) {
+    check(pts);
+  }
+
+  // Small coordinates give many duplicate and colinear points.
+  #[proptest]
+  fn matches_graham_scan_grid(
+    #[strategy(vec((-3i8..3, -3i8..3).prop_map(|(x, y)| Point::new([x, y])), 0..200))] pts: Vec<
+      Point<i8>,
+    >,
+  ) {
+    check(pts);
+  }
+}
@@ -0,0 +1,309 @@
+use crate::data::{Point, TriangleMesh};
+use crate::{Error, Orientation, PolygonScalar};
+
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+
+use super::monotone_chain;
+
+/// Convex hull of a set of points in three dimensions.
+///
+/// The points are sorted lexicographically and added to the hull one at a
+/// time. Each new point is outside the current hull so it can see the faces
+/// around the previously added point. The faces it can see, or whose plane it
+/// lies in, are replaced by a cone of new faces from the point to the horizon.
+/// All tests use the exact [`Point::orient3d`] predicate.
+///
+/// # Errors
+/// Will return an error iff all points in the input set are coplanar.
+///
+/// # Properties
+/// * No points from the input set will be outside the returned mesh.
+/// * All vertices in the mesh are from the input set and are corners of the
+///   hull.
+/// * Faces are oriented counter-clockwise when seen from outside. Planar
+///   facets of the hull may be split into several coplanar triangles.
+///
+/// # Time complexity
+/// $O(n^2)$
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::convex_hull::hull_3d::convex_hull;
+/// # use rgeometry::data::Point;
+/// let points = vec![
+///   Point::new([0, 0, 0]),
+///   Point::new([4, 0, 0]),
+///   Point::new([0, 4, 0]),
+///   Point::new([0, 0, 4]),
+///   Point::new([1, 1, 1]),
+/// ];
+/// let hull = convex_hull(points).unwrap();
+/// assert_eq!(hull.points.len(), 4);
+/// assert_eq!(hull.faces.len(), 4);
+/// ```
+pub fn convex_hull<T>(mut pts: Vec<Point<T, 3>>) -> Result<TriangleMesh<T>, Error>
+where
+  T: PolygonScalar,
+{
+  pts.sort_unstable();
+  pts.dedup();
+  // The points before the apex are coplanar.
+  let (base, apex) = initial_pyramid(&pts).ok_or(Error::InsufficientVertices)?;
+  let mut hull = Hull {
+    pts: &pts,
+    faces: Vec::new(),
+    edges: HashMap::new(),
+  };
+  for i in 1..base.len() - 1 {
+    hull.add_face([base[0], base[i], base[i + 1]]);
+  }
+  let mut cone: Vec<usize> = (0..base.len())
+    .map(|i| hull.add_face([base[(i + 1) % base.len()], base[i], apex]))
+    .collect();
+  for pt in apex + 1..pts.len() {
+    // The previous point is the largest vertex so 'pt' can see at least one of
+    // the faces around it.
+    let start = cone
+      .into_iter()
+      .find(|&face| hull.sees(face, pt))
+      .expect("point must see the previous vertex");
+    cone = hull.add_point(start, pt);
+  }
+  Ok(hull.into_mesh())
+}
+
+struct Face {
+  vertices: [usize; 3],
+  alive: bool,
+}
+
+struct Hull<'a, T> {
+  pts: &'a [Point<T, 3>],
+  faces: Vec<Face>,
+  // Face on the left of each directed edge.
+  edges: HashMap<(usize, usize), usize>,
+}
+
+impl<'a, T> Hull<'a, T>
+where
+  T: PolygonScalar,
+{
+  fn add_face(&mut self, vertices: [usize; 3]) -> usize {
+    let id = self.faces.len();
+    for i in 0..3 {
+      self.edges.insert((vertices[i], vertices[(i + 1) % 3]), id);
+    }
+    self.faces.push(Face {
+      vertices,
+      alive: true,
+    });
+    id
+  }
+
+  // Whether the point is outside the plane of the face or in it.
+  fn sees(&self, face: usize, pt: usize) -> bool {
+    let [a, b, c] = self.faces[face].vertices;
+    let pts = self.pts;
+    Point::orient3d(&pts[a], &pts[b], &pts[c], &pts[pt]) != Ordering::Greater
+  }
+
+  // Add a point that is outside the hull and can see 'start'. Returns the new
+  // faces.
+  fn add_point(&mut self, start: usize, pt: usize) -> Vec<usize> {
+    // The visible faces are connected so they can be found by a search from
+    // 'start'. Edges between a visible and a hidden face form the horizon.
+    let mut visible = vec![start];
+    let mut horizon = Vec::new();
+    self.faces[start].alive = false;
+    let mut i = 0;
+    while i < visible.len() {
+      let [a, b, c] = self.faces[visible[i]].vertices;
+      for (src, dst) in [(a, b), (b, c), (c, a)] {
+        let twin = self.edges[&(dst, src)];
+        if !self.faces[twin].alive {
+          continue;
+        }
+        if self.sees(twin, pt) {
+          self.faces[twin].alive = false;
+          visible.push(twin);
+        } else {
+          horizon.push((src, dst));
+        }
+      }
+      i += 1;
+    }
+    for &face in &visible {
+      let [a, b, c] = self.faces[face].vertices;
+      for edge in [(a, b), (b, c), (c, a)] {
+        self.edges.remove(&edge);
+      }
+    }
+    horizon
+      .into_iter()
+      .map(|(src, dst)| self.add_face([src, dst, pt]))
+      .collect()
+  }
+
+  // Keep only the points that are vertices of the hull.
+  fn into_mesh(self) -> TriangleMesh<T> {
+    let mut index = vec![None; self.pts.len()];
+    let mut points = Vec::new();
+    let mut faces = Vec::new();
+    for face in self.faces.iter().filter(|face| face.alive) {
+      faces.push(face.vertices.map(|v| {
+        *index[v].get_or_insert_with(|| {
+          points.push(self.pts[v].clone());
+          points.len() - 1
+        })
+      }));
+    }
+    TriangleMesh { points, faces }
+  }
+}
+
+// Pyramid over the coplanar points before the first point that is not in
+// their plane. Returns the vertices of the base, ordered such that the apex is
+// below them, and the apex.
+fn initial_pyramid<T>(pts: &[Point<T, 3>]) -> Option<(Vec<usize>, usize)>
+where
+  T: PolygonScalar,
+{
+  let c = (2..pts.len()).find(|&c| !colinear(&pts[0], &pts[1], &pts[c]))?;
+  let apex =
+    (c + 1..pts.len()).find(|&d| Point::orient3d(&pts[0], &pts[1], &pts[c], &pts[d]).is_ne())?;
+  // The base is the 2D convex hull of the coplanar points, projected onto an
+  // axis plane that the plane of the points isn't perpendicular to.
+  let (i, j) = [(0, 1), (0, 2), (1, 2)].iter().copied().find(|&(i, j)| {
+    let project = |pt: &Point<T, 3>| [pt[i].clone(), pt[j].clone()];
+    !Orientation::new(&project(&pts[0]), &project(&pts[1]), &project(&pts[c])).is_colinear()
+  })?;
+  let projected: BTreeMap<Point<T>, usize> = (0..apex)
+    .map(|k| (Point::new([pts[k][i].clone(), pts[k][j].clone()]), k))
+    .collect();
+  let mut base: Vec<usize> = monotone_chain::hull_points(projected.keys().cloned().collect())
+    .iter()
+    .map(|pt| projected[pt])
+    .collect();
+  let [a, b, c] = [base[0], base[1], base[2]];
+  if Point::orient3d(&pts[a], &pts[b], &pts[c], &pts[apex]) == Ordering::Less {
+    base.reverse();
+  }
+  Some((base, apex))
+}
+
+// Three points are colinear iff their projections onto all three axis planes
+// are colinear.
+fn colinear<T>(a: &Point<T, 3>, b: &Point<T, 3>, c: &Point<T, 3>) -> bool
+where
+  T: PolygonScalar,
+{
+  [(0, 1), (0, 2), (1, 2)].iter().all(|&(i, j)| {
+    let project = |pt: &Point<T, 3>| [pt[i].clone(), pt[j].clone()];
+    Orientation::new(&project(a), &project(b), &project(c)).is_colinear()
+  })
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  use std::collections::HashSet;
+
+  use proptest::collection::*;
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  fn check_hull<T: PolygonScalar>(pts: &[Point<T, 3>], hull: &TriangleMesh<T>) {
+    // Every directed edge has a twin so the mesh is closed.
+    let mut edges = HashSet::new();
+    for &[a, b, c] in &hull.faces {
+      for edge in [(a, b), (b, c), (c, a)] {
+        assert!(edges.insert(edge));
+      }
+    }
+    for &(a, b) in &edges {
+      assert!(edges.contains(&(b, a)));
+    }
+    // Euler's formula for a sphere.
+    let (v, e, f) = (hull.points.len(), edges.len() / 2, hull.faces.len());
+    assert_eq!(v + f, e + 2);
+    for [a, b, c] in hull.triangles() {
+      assert!(!colinear(a, b, c));
+      for pt in pts {
+        assert_ne!(Point::orient3d(a, b, c, pt), Ordering::Less);
+      }
+    }
+    for vertex in &hull.points {
+      assert!(pts.contains(vertex));
+    }
+    // Corners have faces in at least three different planes around them.
+    for v in 0..hull.points.len() {
+      let mut planes: Vec<[&Point<T, 3>; 3]> = Vec::new();
+      for tri in hull
+        .triangles()
+        .filter(|tri| tri.contains(&&hull.points[v]))
+      {
+        let coplanar = |plane: &[&Point<T, 3>; 3]| {
+          tri
+            .iter()
+            .all(|pt| Point::orient3d(plane[0], plane[1], plane[2], pt).is_eq())
+        };
+        if !planes.iter().any(coplanar) {
+          planes.push(tri);
+        }
+      }
+      assert!(planes.len() >= 3);
+    }
+  }
+
+  #[test]
+  fn cube() {
+    let mut pts = Vec::new();
+    for x in 0..3 {
+      for y in 0..3 {
+        for z in 0..3 {
+          pts.push(Point::new([x, y, z]));
+        }
+      }
+    }
+    let hull = convex_hull(pts.clone()).unwrap();
+    check_hull(&pts, &hull);
+    assert_eq!(hull.points.len(), 8);
+    assert_eq!(hull.faces.len(), 12);
+  }
+
+  #[test]
+  fn coplanar() {
+    let pts = vec![
+      Point::new([0, 0, 1]),
+      Point::new([1, 0, 1]),
+      Point::new([0, 1, 1]),
+      Point::new([1, 1, 1]),
+      Point::new([0, 0, 1]),
+    ];
+    assert_eq!(convex_hull(pts).err(), Some(Error::InsufficientVertices));
+    let pts: Vec<Point<i8, 3>> = (0..10).map(|i| Point::new([i, 2 * i, 3 * i])).collect();
+    assert_eq!(convex_hull(pts).err(), Some(Error::InsufficientVertices));
+  }
+
+  #[proptest]
+  fn valid_hull(#[strategy(vec(any::<Point<i8, 3>>(), 4..60))] pts: Vec<Point<i8, 3>>) {
+    if let Ok(hull) = convex_hull(pts.clone()) {
+      check_hull(&pts, &hull);
+    }
+  }
+
+  // Small coordinates give many duplicate and coplanar points.
+  #[proptest]
+  fn valid_hull_grid(
+    #[strategy(vec((0i8..3, 0i8..3, 0i8..3).prop_map(|(x, y, z)| Point::new([x, y, z])), 4..60))]
+    pts: Vec<Point<i8, 3>>,
+  ) {
+    if let Ok(hull) = convex_hull(pts.clone()) {
+      check_hull(&pts, &hull);
+    }
+  }
+}
@@ -0,0 +1,294 @@
+use crate::data::{Point, Polygon, PolygonConvex};
+use crate::{Error, Orientation, PolygonScalar};
+
+use std::collections::BTreeSet;
+use std::iter::FromIterator;
+use std::ops::Bound::{Excluded, Unbounded};
+
+/// Convex hull that is updated as points are inserted.
+///
+/// The lower and upper hull are kept in sorted sets. A new point is either
+/// inside the hull and ignored, or it is added to a hull and the vertices
+/// around it that are no longer convex are removed. Every point is removed at
+/// most once so insertions take amortized logarithmic time.
+///
+/// # Examples
+/// ```rust
+/// # use rgeometry::algorithms::convex_hull::incremental::IncrementalHull;
+/// # use rgeometry::data::Point;
+/// let mut hull = IncrementalHull::new();
+/// hull.insert(Point::new([0, 0]));
+/// hull.insert(Point::new([4, 0]));
+/// hull.insert(Point::new([0, 4]));
+/// assert_eq!(hull.convex_hull().unwrap().iter().count(), 3);
+/// // Inside the triangle.
+/// assert!(!hull.insert(Point::new([1, 1])));
+/// assert!(hull.insert(Point::new([4, 4])));
+/// assert_eq!(hull.convex_hull().unwrap().iter().count(), 4);
+/// ```
+#[derive(Debug, Clone)]
+pub struct IncrementalHull<T> {
+  lower: Chain<T>,
+  upper: Chain<T>,
+}
+
+impl<T> IncrementalHull<T>
+where
+  T: PolygonScalar,
+{
+  /// Empty hull.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn new() -> IncrementalHull<T> {
+    IncrementalHull {
+      lower: Chain::new(Orientation::CounterClockWise),
+      upper: Chain::new(Orientation::ClockWise),
+    }
+  }
+
+  /// Add a point to the hull. Returns `false` if the point is inside the hull
+  /// or on its boundary, in which case the hull doesn't change.
+  ///
+  /// # Time complexity
+  /// $O(\log n)$ amortized
+  pub fn insert(&mut self, pt: Point<T>) -> bool {
+    let lower = self.lower.insert(&pt);
+    let upper = self.upper.insert(&pt);
+    lower || upper
+  }
+
+  /// Check if a point is inside the hull or on its boundary.
+  ///
+  /// # Time complexity
+  /// $O(\log n)$
+  pub fn contains(&self, pt: &Point<T>) -> bool {
+    self.lower.covers(pt) && self.upper.covers(pt)
+  }
+
+  /// Number of vertices of the hull.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn len(&self) -> usize {
+    let shared = self.lower.points.len().min(2);
+    self.lower.points.len() + self.upper.points.len() - shared
+  }
+
+  /// # Time complexity
+  /// $O(1)$
+  pub fn is_empty(&self) -> bool {
+    self.lower.points.is_empty()
+  }
+
+  /// Freeze the hull into a convex polygon.
+  ///
+  /// # Errors
+  /// Will return an error iff less than three points that are not colinear
+  /// have been inserted.
+  ///
+  /// # Time complexity
+  /// $O(n)$
+  pub fn convex_hull(&self) -> Result<PolygonConvex<T>, Error> {
+    if self.len() < 3 {
+      return Err(Error::InsufficientVertices);
+    }
+    // The lower and upper hull share their first and last point.
+    let n = self.upper.points.len();
+    let vertices = self
+      .lower
+      .points
+      .iter()
+      .chain(self.upper.points.iter().rev().skip(1).take(n - 2))
+      .cloned()
+      .collect();
+    Ok(PolygonConvex::new_unchecked(Polygon::new_unchecked(
+      vertices,
+    )))
+  }
+}
+
+impl<T> Default for IncrementalHull<T>
+where
+  T: PolygonScalar,
+{
+  fn default() -> Self {
+    IncrementalHull::new()
+  }
+}
+
+impl<T> Extend<Point<T>> for IncrementalHull<T>
+where
+  T: PolygonScalar,
+{
+  fn extend<I: IntoIterator<Item = Point<T>>>(&mut self, iter: I) {
+    for pt in iter {
+      self.insert(pt);
+    }
+  }
+}
+
+impl<T> FromIterator<Point<T>> for IncrementalHull<T>
+where
+  T: PolygonScalar,
+{
+  fn from_iter<I: IntoIterator<Item = Point<T>>>(iter: I) -> Self {
+    let mut hull = IncrementalHull::new();
+    hull.extend(iter);
+    hull
+  }
+}
+
+// Lower or upper hull from the smallest to the largest point. Consecutive
+// vertices always turn in the same direction.
+#[derive(Debug, Clone)]
+struct Chain<T> {
+  points: BTreeSet<Point<T>>,
+  turn: Orientation,
+}
+
+impl<T> Chain<T>
+where
+  T: PolygonScalar,
+{
+  fn new(turn: Orientation) -> Chain<T> {
+    Chain {
+      points: BTreeSet::new(),
+      turn,
+    }
+  }
+
+  fn before<'a>(&'a self, pt: &Point<T>) -> impl Iterator<Item = &'a Point<T>> {
+    self
+      .points
+      .range::<Point<T>, _>((Unbounded, Excluded(pt)))
+      .rev()
+  }
+
+  fn after<'a>(&'a self, pt: &Point<T>) -> impl Iterator<Item = &'a Point<T>> {
+    self.points.range::<Point<T>, _>((Excluded(pt), Unbounded))
+  }
+
+  // Whether the point is on the inner side of the chain or on the chain.
+  fn covers(&self, pt: &Point<T>) -> bool {
+    if self.points.contains(pt) {
+      return true;
+    }
+    match (self.before(pt).next(), self.after(pt).next()) {
+      (Some(prev), Some(next)) => Point::orient(prev, pt, next) != self.turn,
+      _ => false,
+    }
+  }
+
+  fn insert(&mut self, pt: &Point<T>) -> bool {
+    if self.covers(pt) {
+      return false;
+    }
+    // Remove the neighbours that are no longer convex.
+    while let Some(next) = self.redundant(self.after(pt), |next, next_next| (pt, next, next_next)) {
+      self.points.remove(&next);
+    }
+    while let Some(prev) = self.redundant(self.before(pt), |prev, prev_prev| (prev_prev, prev, pt))
+    {
+      self.points.remove(&prev);
+    }
+    self.points.insert(pt.clone());
+    true
+  }
+
+  // The first of two neighbours if they don't turn in the direction of the
+  // chain.
+  fn redundant<'a, I, F>(&self, mut neighbours: I, triangle: F) -> Option<Point<T>>
+  where
+    I: Iterator<Item = &'a Point<T>>,
+    F: Fn(&'a Point<T>, &'a Point<T>) -> (&'a Point<T>, &'a Point<T>, &'a Point<T>),
+    T: 'a,
+  {
+    let (first, second) = (neighbours.next()?, neighbours.next()?);
+    let (a, b, c) = triangle(first, second);
+    if Point::orient(a, b, c) != self.turn {
+      Some(first.clone())
+    } else {
+      None
+    }
+  }
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::algorithms::convex_hull::graham_scan;
+  use crate::data::PointLocation;
+
+  use proptest::collection::*;
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  #[test]
+  fn colinear() {
+    let mut hull = IncrementalHull::new();
+    for i in 0..10 {
+      hull.insert(Point::new([i, 2 * i]));
+    }
+    assert_eq!(hull.len(), 2);
+    assert_eq!(hull.convex_hull().err(), Some(Error::InsufficientVertices));
+    assert!(hull.contains(&Point::new([3, 6])));
+    assert!(!hull.contains(&Point::new([3, 5])));
+    assert!(hull.insert(Point::new([3, 5])));
+    assert_eq!(hull.len(), 3);
+  }
+
+  #[test]
+  fn vertical_edges() {
+    let hull: IncrementalHull<i32> = vec![
+      Point::new([0, 0]),
+      Point::new([0, 5]),
+      Point::new([0, 2]),
+      Point::new([5, 5]),
+      Point::new([5, 0]),
+      Point::new([5, 3]),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(hull.len(), 4);
+    assert!(hull.contains(&Point::new([0, 3])));
+    assert!(!hull.contains(&Point::new([-1, 3])));
+    assert_eq!(hull.convex_hull().unwrap().validate().err(), None);
+  }
+
+  #[proptest]
+  fn matches_graham_scan(#[strategy(vec(any::<Point<i8>>(), 0..100))] pts: Vec<Point<i8>>) {
+    let mut hull = IncrementalHull::new();
+    for (i, pt) in pts.iter().enumerate() {
+      hull.insert(pt.clone());
+      match (
+        hull.convex_hull(),
+        graham_scan::convex_hull(pts[..=i].to_vec()),
+      ) {
+        (Ok(a), Ok(b)) => {
+          prop_assert_eq!(a.validate().err(), None);
+          prop_assert!(a.equals(&b));
+          prop_assert_eq!(hull.len(), b.iter().count());
+        }
+        (a, b) => prop_assert_eq!(a.err(), b.err()),
+      }
+    }
+  }
+
+  #[proptest]
+  fn contains_prop(
+    #[strategy(vec((-5i8..5, -5i8..5).prop_map(|(x, y)| Point::new([x, y])), 3..30))] pts: Vec<
+      Point<i8>,
+    >,
+    #[strategy((-6i8..6, -6i8..6).prop_map(|(x, y)| Point::new([x, y])))] query: Point<i8>,
+  ) {
+    let hull: IncrementalHull<i8> = pts.iter().cloned().collect();
+    if let Ok(poly) = hull.convex_hull() {
+      prop_assert_eq!(
+        hull.contains(&query),
+        poly.locate(&query) != PointLocation::Outside
+      );
+    }
+  }
+}
@@ -1,2 +1,7 @@
+pub mod chan;
 pub mod graham_scan;
+pub mod hull_3d;
+pub mod incremental;
 pub mod melkman;
+pub mod monotone_chain;
+pub mod quickhull;
@@ -0,0 +1,147 @@
+use crate::data::{Point, Polygon, PolygonConvex};
+use crate::{Error, PolygonScalar};
+
+// https://en.wikibooks.org/wiki/Algorithm_Implementation/Geometry/Convex_hull/Monotone_chain
+
+/// Convex hull of a set of points.
+///
+/// [Andrew's monotone chain][wiki] algorithm sorts the points lexicographically
+/// and builds the lower and upper hull in a single pass each.
+///
+/// # Errors
+/// Will return an error iff the input set contains less than three distinct points.
+///
+/// # Properties
+/// * No points from the input set will be outside the returned convex polygon.
+/// * All vertices in the convex polygon are from the input set.
+///
+/// # Time complexity
+/// $O(n \log n)$
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::convex_hull::monotone_chain::convex_hull;
+/// # use rgeometry::data::Point;
+/// let points = vec![
+///   Point::new([0, 0]),
+///   Point::new([2, 0]),
+///   Point::new([1, 1]),
+///   Point::new([2, 2]),
+///   Point::new([0, 2]),
+/// ];
+/// let hull = convex_hull(points).unwrap();
+/// assert_eq!(hull.iter().count(), 4);
+/// ```
+///
+/// [wiki]: https://en.wikibooks.org/wiki/Algorithm_Implementation/Geometry/Convex_hull/Monotone_chain
+pub fn convex_hull<T>(pts: Vec<Point<T>>) -> Result<PolygonConvex<T>, Error>
+where
+  T: PolygonScalar,
+{
+  let hull = hull_points(pts);
+  if hull.len() < 3 {
+    return Err(Error::InsufficientVertices);
+  }
+  Ok(PolygonConvex::new_unchecked(Polygon::new_unchecked(hull)))
+}
+
+// Vertices of the convex hull in counter-clockwise order, starting with the
+// smallest point. Degenerate hulls have one or two vertices.
+pub(crate) fn hull_points<T>(mut pts: Vec<Point<T>>) -> Vec<Point<T>>
+where
+  T: PolygonScalar,
+{
+  pts.sort_unstable();
+  pts.dedup();
+  if pts.len() < 3 {
+    return pts;
+  }
+  let mut hull: Vec<Point<T>> = Vec::with_capacity(pts.len() + 1);
+  // Lower hull from left to right.
+  for pt in pts.iter() {
+    push_convex(&mut hull, 0, pt);
+  }
+  // Upper hull from right to left without popping the lower hull.
+  let floor = hull.len() - 1;
+  for pt in pts.iter().rev().skip(1) {
+    push_convex(&mut hull, floor, pt);
+  }
+  // The last point is the first point again.
+  hull.pop();
+  hull
+}
+
+// Push a point after removing the vertices above 'floor' that would not make
+// a counter-clockwise turn.
+fn push_convex<T>(hull: &mut Vec<Point<T>>, floor: usize, pt: &Point<T>)
+where
+  T: PolygonScalar,
+{
+  while hull.len() >= floor + 2
+    && !Point::orient(&hull[hull.len() - 2], &hull[hull.len() - 1], pt).is_ccw()
+  {
+    hull.pop();
+  }
+  hull.push(pt.clone());
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::algorithms::convex_hull::graham_scan;
+  use crate::testing::*;
+
+  use claims::assert_ok;
+  use num_bigint::BigInt;
+
+  use proptest::collection::*;
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  #[test]
+  fn convex_hull_colinear() {
+    let points = vec![
+      Point::new([0, 0]),
+      Point::new([1, 0]),
+      Point::new([2, 0]),
+      Point::new([3, 0]),
+      Point::new([4, 0]),
+      Point::new([1, 1]),
+    ];
+    let poly = convex_hull(points).unwrap();
+    assert_ok!(poly.validate());
+    assert_eq!(poly.iter().count(), 3);
+  }
+
+  #[test]
+  fn convex_hull_insufficient() {
+    let dups = vec![Point::new([0, 0]), Point::new([2, 2])].repeat(3);
+    assert_eq!(convex_hull(dups).err(), Some(Error::InsufficientVertices));
+    let line = vec![Point::new([0, 0]), Point::new([1, 1]), Point::new([2, 2])];
+    assert_eq!(convex_hull(line).err(), Some(Error::InsufficientVertices));
+  }
+
+  #[proptest]
+  fn matches_graham_scan(#[strategy(vec(any_r(), 0..100))] pts: Vec<Point<BigInt>>) {
+    match (convex_hull(pts.clone()), graham_scan::convex_hull(pts)) {
+      (Ok(a), Ok(b)) => {
+        prop_assert_eq!(a.validate().err(), None);
+        prop_assert!(a.equals(&b));
+      }
+      (a, b) => prop_assert_eq!(a.err(), b.err()),
+    }
+  }
+
+  #[proptest]
+  fn matches_graham_scan_i8(#[strategy(vec(any::<Point<i8>>(), 0..100))] pts: Vec<Point<i8>>) {
+    match (convex_hull(pts.clone()), graham_scan::convex_hull(pts)) {
+      (Ok(a), Ok(b)) => {
+        prop_assert_eq!(a.validate().err(), None);
+        prop_assert!(a.equals(&b));
+      }
+      (a, b) => prop_assert_eq!(a.err(), b.err()),
+    }
+  }
+}
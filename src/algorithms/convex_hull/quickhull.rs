@@ -0,0 +1,165 @@
+use crate::data::{Point, Polygon, PolygonConvex};
+use crate::{Error, PolygonScalar};
+
+use std::cmp::Ordering;
+
+// https://en.wikipedia.org/wiki/Quickhull
+
+/// Convex hull of a set of points.
+///
+/// [QuickHull][wiki] splits the points by the line through the leftmost and
+/// rightmost point and recursively adds the point farthest from each edge of
+/// the hull until no points are left outside. Distances are compared with
+/// [`PolygonScalar::cmp_cross`].
+///
+/// # Errors
+/// Will return an error iff the input set contains less than three distinct points.
+///
+/// # Properties
+/// * No points from the input set will be outside the returned convex polygon.
+/// * All vertices in the convex polygon are from the input set.
+///
+/// # Time complexity
+/// $O(n \log n)$ expected, $O(n^2)$ in the worst case.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::convex_hull::quickhull::convex_hull;
+/// # use rgeometry::data::Point;
+/// let points = vec![
+///   Point::new([0, 0]),
+///   Point::new([2, 0]),
+///   Point::new([1, 1]),
+///   Point::new([2, 2]),
+///   Point::new([0, 2]),
+/// ];
+/// let hull = convex_hull(points).unwrap();
+/// assert_eq!(hull.iter().count(), 4);
+/// ```
+///
+/// [wiki]: https://en.wikipedia.org/wiki/Quickhull
+pub fn convex_hull<T>(pts: Vec<Point<T>>) -> Result<PolygonConvex<T>, Error>
+where
+  T: PolygonScalar,
+{
+  let (left, right) = match (pts.iter().min(), pts.iter().max()) {
+    (Some(left), Some(right)) if left != right => (left.clone(), right.clone()),
+    _ => return Err(Error::InsufficientVertices),
+  };
+  let (below, above): (Vec<Point<T>>, Vec<Point<T>>) = pts
+    .into_iter()
+    .filter(|pt| !Point::orient(&left, &right, pt).is_colinear())
+    .partition(|pt| Point::orient(&left, &right, pt).is_cw());
+  let mut hull = Vec::new();
+  hull.push(left.clone());
+  expand(&left, &right, below, &mut hull);
+  hull.push(right.clone());
+  expand(&right, &left, above, &mut hull);
+  if hull.len() < 3 {
+    return Err(Error::InsufficientVertices);
+  }
+  Ok(PolygonConvex::new_unchecked(Polygon::new_unchecked(hull)))
+}
+
+// Push the hull vertices strictly between 'p' and 'q'. All points are to the
+// right of the line from 'p' to 'q'.
+fn expand<T>(p: &Point<T>, q: &Point<T>, pts: Vec<Point<T>>, hull: &mut Vec<Point<T>>)
+where
+  T: PolygonScalar,
+{
+  let farthest = pts
+    .iter()
+    // Ties are broken lexicographically so the farthest point is never in the
+    // middle of an edge.
+    .max_by(|a, b| cmp_distance(p, q, a, b).then_with(|| a.cmp(b)))
+    .cloned();
+  let farthest = match farthest {
+    Some(farthest) => farthest,
+    None => return,
+  };
+  // Points inside the triangle p, farthest, q are dropped.
+  let mut before = Vec::new();
+  let mut after = Vec::new();
+  for pt in pts {
+    if Point::orient(p, &farthest, &pt).is_cw() {
+      before.push(pt);
+    } else if Point::orient(&farthest, q, &pt).is_cw() {
+      after.push(pt);
+    }
+  }
+  expand(p, &farthest, before, hull);
+  hull.push(farthest.clone());
+  expand(&farthest, q, after, hull);
+}
+
+// Compare the distances of 'a' and 'b' to the line through 'p' and 'q'. Both
+// points are to the right of the line so 'a' is farther iff 'a - b' points to
+// the right of 'q - p'.
+fn cmp_distance<T>(p: &Point<T>, q: &Point<T>, a: &Point<T>, b: &Point<T>) -> Ordering
+where
+  T: PolygonScalar,
+{
+  T::cmp_cross(&p.array, &q.array, &b.array, &a.array).reverse()
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::algorithms::convex_hull::graham_scan;
+  use crate::testing::*;
+
+  use claims::assert_ok;
+  use num_bigint::BigInt;
+
+  use proptest::collection::*;
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  #[test]
+  fn convex_hull_parallel() {
+    // Two points at the same distance from the first split line.
+    let points = vec![
+      Point::new([0, 0]),
+      Point::new([10, 0]),
+      Point::new([3, -5]),
+      Point::new([7, -5]),
+      Point::new([5, -5]),
+      Point::new([5, 3]),
+    ];
+    let poly = convex_hull(points).unwrap();
+    assert_ok!(poly.validate());
+    assert_eq!(poly.iter().count(), 5);
+  }
+
+  #[test]
+  fn convex_hull_insufficient() {
+    let dups = vec![Point::new([0, 0]), Point::new([2, 2])].repeat(3);
+    assert_eq!(convex_hull(dups).err(), Some(Error::InsufficientVertices));
+    let empty: Vec<Point<i8>> = vec![];
+    assert_eq!(convex_hull(empty).err(), Some(Error::InsufficientVertices));
+  }
+
+  #[proptest]
+  fn matches_graham_scan(#[strategy(vec(any_r(), 0..100))] pts: Vec<Point<BigInt>>) {
+    match (convex_hull(pts.clone()), graham_scan::convex_hull(pts)) {
+      (Ok(a), Ok(b)) => {
+        prop_assert_eq!(a.validate().err(), None);
+        prop_assert!(a.equals(&b));
+      }
+      (a, b) => prop_assert_eq!(a.err(), b.err()),
+    }
+  }
+
+  #[proptest]
+  fn matches_graham_scan_i8(#[strategy(vec(any::<Point<i8>>(), 0..100))] pts: Vec<Point<i8>>) {
+    match (convex_hull(pts.clone()), graham_scan::convex_hull(pts)) {
+      (Ok(a), Ok(b)) => {
+        prop_assert_eq!(a.validate().err(), None);
+        prop_assert!(a.equals(&b));
+      }
+      (a, b) => prop_assert_eq!(a.err(), b.err()),
+    }
+  }
+}
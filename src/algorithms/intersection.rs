@@ -1 +1,139 @@
+pub mod bentley_ottmann;
+pub mod convex;
 pub mod naive;
+pub mod shamos_hoey;
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::data::*;
+  use crate::Intersects;
+
+  use proptest::collection::vec;
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  // Map reported pairs back to indices so results can be compared.
+  fn indices<'a, E>(
+    edges: &'a [E],
+    pairs: impl Iterator<Item = (&'a E, &'a E)>,
+  ) -> Vec<(usize, usize)> {
+    let index = |e: &E| edges.iter().position(|x| std::ptr::eq(x, e)).unwrap();
+    pairs.map(|(a, b)| (index(a), index(b))).collect()
+  }
+
+  fn check_segments(segments: &[LineSegment<i8>]) {
+    let expected = indices(segments, naive::segment_intersections(segments));
+    let actual = indices(segments, bentley_ottmann::segment_intersections(segments));
+    assert_eq!(expected, actual);
+  }
+
+  fn check_any_segments(segments: &[LineSegment<i8>]) {
+    let expected = naive::segment_intersections(segments).next().is_some();
+    match shamos_hoey::segment_intersection(segments) {
+      None => assert!(!expected),
+      Some((a, b)) => assert!(a.intersect(b).is_some()),
+    }
+  }
+
+  fn check_polygon<T: crate::PolygonScalar>(poly: &Polygon<T>) {
+    let edges: Vec<DirectedEdge<'_, T, 2>> = poly.iter_boundary_edges().collect();
+    let expected = indices(&edges, naive::segment_intersections(&edges));
+    let actual = indices(&edges, bentley_ottmann::segment_intersections(&edges));
+    assert_eq!(expected, actual);
+  }
+
+  // Small coordinates to provoke degenerate cases: shared endpoints, vertical
+  // segments, colinear overlaps and multiple segments crossing at one point.
+  // Zero-length segments are skipped since 'LineSegmentView::intersect' treats
+  // them as being colinear with every other segment.
+  fn small_segment() -> impl Strategy<Value = LineSegment<i8>> {
+    let endpoint = ((-4i8..4, -4i8..4), any::<bool>()).prop_map(|((x, y), inclusive)| {
+      let pt = Point::new([x, y]);
+      if inclusive {
+        EndPoint::Inclusive(pt)
+      } else {
+        EndPoint::Exclusive(pt)
+      }
+    });
+    (endpoint.clone(), endpoint)
+      .prop_filter("zero-length segment", |(a, b)| a.inner() != b.inner())
+      .prop_map(|(a, b)| LineSegment::new(a, b))
+  }
+
+  #[proptest]
+  fn sweep_matches_naive_segments(
+    #[strategy(vec(small_segment(), 0..30))] segments: Vec<LineSegment<i8>>,
+  ) {
+    check_segments(&segments);
+  }
+
+  #[proptest]
+  fn any_matches_naive_segments(
+    #[strategy(vec(small_segment(), 0..8))] segments: Vec<LineSegment<i8>>,
+  ) {
+    check_any_segments(&segments);
+  }
+
+  #[proptest]
+  fn sweep_matches_naive_i8_segments(
+    #[strategy(vec(any::<(i8, i8, i8, i8)>(), 0..30))] segments: Vec<(i8, i8, i8, i8)>,
+  ) {
+    let segments: Vec<LineSegment<i8>> = segments
+      .into_iter()
+      .filter(|(a, b, c, d)| (a, b) != (c, d))
+      .map(|(a, b, c, d)| LineSegment::from((a, b)..=(c, d)))
+      .collect();
+    check_segments(&segments);
+  }
+
+  #[proptest]
+  fn sweep_matches_naive_polygon(poly: Polygon<i8>) {
+    check_polygon(&poly);
+  }
+
+  #[proptest]
+  fn sweep_matches_naive_random_polygon(
+    #[strategy(vec(any::<Point<i8, 2>>(), 3..30))] pts: Vec<Point<i8, 2>>,
+  ) {
+    let poly = Polygon::new_unchecked(pts);
+    check_polygon(&poly);
+  }
+
+  #[test]
+  fn sweep_star() {
+    let segments: Vec<LineSegment<i8>> = vec![
+      LineSegment::from((-2, 0)..=(2, 0)),
+      LineSegment::from((0, -2)..=(0, 2)),
+      LineSegment::from((-2, -2)..=(2, 2)),
+      LineSegment::from((-2, 2)..=(2, -2)),
+    ];
+    check_segments(&segments);
+    assert_eq!(bentley_ottmann::segment_intersections(&segments).count(), 6);
+  }
+
+  #[test]
+  fn sweep_overlap() {
+    let segments: Vec<LineSegment<i8>> = vec![
+      LineSegment::from((0, 0)..=(4, 4)),
+      LineSegment::from((1, 1)..=(2, 2)),
+      LineSegment::from((2, 2)..(6, 6)),
+      LineSegment::from((4, 4)..=(5, 5)),
+    ];
+    check_segments(&segments);
+  }
+
+  #[test]
+  fn sweep_vertical() {
+    let segments: Vec<LineSegment<i8>> = vec![
+      LineSegment::from((0, -4)..=(0, 4)),
+      LineSegment::from((-1, 3)..=(1, 3)),
+      LineSegment::from((-1, -3)..=(1, 1)),
+      LineSegment::from((0, 4)..=(0, 5)),
+      LineSegment::from((-1, 0)..=(1, 0)),
+    ];
+    check_segments(&segments);
+    assert_eq!(bentley_ottmann::segment_intersections(&segments).count(), 5);
+  }
+}
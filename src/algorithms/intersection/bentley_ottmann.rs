@@ -0,0 +1,150 @@
+use crate::data::{LineSegmentView, Point};
+use crate::utils::exact::{line_intersection, to_rational};
+use crate::utils::Treap;
+use crate::{Intersects, PolygonScalar};
+
+use num::BigRational;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Find all line segment intersections with the Bentley-Ottmann sweep line
+/// algorithm. Reports the same pairs, in the same order, as
+/// [`naive::segment_intersections`](super::naive::segment_intersections).
+///
+/// # Time complexity
+/// $O((n+k) \log n)$ where $k$ is the number of intersections.
+///
+/// # Space complexity
+/// $O(n+k)$
+pub fn segment_intersections<'a, Edge, T>(
+  edges: &'a [Edge],
+) -> impl Iterator<Item = (&'a Edge, &'a Edge)>
+where
+  &'a Edge: Into<LineSegmentView<'a, T, 2>>,
+  T: PolygonScalar + 'a,
+{
+  // Coordinates are converted to rationals because the intersection points
+  // between segments cannot be represented exactly in T.
+  let segments: Vec<Segment> = edges
+    .iter()
+    .map(|edge| {
+      let view: LineSegmentView<'a, T, 2> = edge.into();
+      Segment {
+        min: to_rational(view.min.inner()),
+        max: to_rational(view.max.inner()),
+      }
+    })
+    .collect();
+  // Pairs of closed segments that touch. Some of them won't intersect when the
+  // inclusiveness of their endpoints is taken into account.
+  let candidates = sweep(&segments);
+  candidates.into_iter().filter_map(move |(a, b)| {
+    let a_edge: LineSegmentView<'a, T, 2> = (&edges[a]).into();
+    let b_edge: LineSegmentView<'a, T, 2> = (&edges[b]).into();
+    let _isect = Intersects::intersect(a_edge, b_edge)?;
+    Some((&edges[a], &edges[b]))
+  })
+}
+
+// Closed line segment with min < max (or min == max for degenerate segments).
+struct Segment {
+  min: Point<BigRational, 2>,
+  max: Point<BigRational, 2>,
+}
+
+// Returns all pairs (a,b) with a > b of closed segments that intersect.
+fn sweep(segments: &[Segment]) -> BTreeSet<(usize, usize)> {
+  let mut found = BTreeSet::new();
+  // Event points mapped to the segments that start at them.
+  let mut events: BTreeMap<Point<BigRational, 2>, Vec<usize>> = BTreeMap::new();
+  for (idx, segment) in segments.iter().enumerate() {
+    events.entry(segment.min.clone()).or_default().push(idx);
+    events.entry(segment.max.clone()).or_default();
+  }
+  // Segments that cross the sweep line, ordered from bottom to top.
+  let mut status: Treap<usize> = Treap::new();
+
+  while let Some(pt) = events.keys().next().cloned() {
+    let starting = events.remove(&pt).unwrap_or_default();
+
+    // Segments are below 'pt' if 'pt' lies to the left of them.
+    let is_below = |&s: &usize| Point::orient(&segments[s].min, &segments[s].max, &pt).is_ccw();
+    let is_above = |&s: &usize| Point::orient(&segments[s].min, &segments[s].max, &pt).is_cw();
+
+    // Segments that end at 'pt' or contain it in their interior.
+    let touching = status.remove_range(is_below, is_above);
+
+    let mut involved: Vec<usize> = touching.clone();
+    involved.extend(starting.iter().copied());
+    for (i, &a) in involved.iter().enumerate() {
+      for &b in &involved[..i] {
+        if a != b {
+          found.insert((a.max(b), a.min(b)));
+        }
+      }
+    }
+
+    // Segments that continue past 'pt', ordered by their slope.
+    let mut continuing: Vec<usize> = touching
+      .into_iter()
+      .chain(starting)
+      .filter(|&s| segments[s].max > pt)
+      .collect();
+    continuing.sort_by(
+      |&a, &b| match Point::orient(&pt, &segments[a].max, &segments[b].max) {
+        crate::Orientation::CounterClockWise => Ordering::Less,
+        crate::Orientation::ClockWise => Ordering::Greater,
+        crate::Orientation::CoLinear => Ordering::Equal,
+      },
+    );
+
+    let (lower, upper) = status.insert_range(is_below, &continuing);
+    match (continuing.first(), continuing.last()) {
+      (Some(&first), Some(&last)) => {
+        if let Some(lower) = lower {
+          add_crossing_event(&mut events, segments, &pt, lower, first);
+        }
+        if let Some(upper) = upper {
+          add_crossing_event(&mut events, segments, &pt, last, upper);
+        }
+      }
+      _ => {
+        if let (Some(lower), Some(upper)) = (lower, upper) {
+          add_crossing_event(&mut events, segments, &pt, lower, upper);
+        }
+      }
+    }
+  }
+  found
+}
+
+// Add an event for the point where two segments cross, if that point is to the
+// right of the sweep line. Intersections at endpoints are already events.
+fn add_crossing_event(
+  events: &mut BTreeMap<Point<BigRational, 2>, Vec<usize>>,
+  segments: &[Segment],
+  pt: &Point<BigRational, 2>,
+  a: usize,
+  b: usize,
+) {
+  if let Some(crossing) = crossing_point(&segments[a], &segments[b]) {
+    if &crossing > pt {
+      events.entry(crossing).or_default();
+    }
+  }
+}
+
+// Intersection point of two segments that properly cross each other.
+fn crossing_point(a: &Segment, b: &Segment) -> Option<Point<BigRational, 2>> {
+  let o1 = Point::orient(&a.min, &a.max, &b.min);
+  let o2 = Point::orient(&a.min, &a.max, &b.max);
+  let o3 = Point::orient(&b.min, &b.max, &a.min);
+  let o4 = Point::orient(&b.min, &b.max, &a.max);
+  if o1.is_colinear() || o2.is_colinear() || o3.is_colinear() || o4.is_colinear() {
+    return None;
+  }
+  if o1 != o2.reverse() || o3 != o4.reverse() {
+    return None;
+  }
+  Some(line_intersection(&a.min, &a.max, &b.min, &b.max))
+}
@@ -0,0 +1,328 @@
+//! Intersection of convex polygons.
+//!
+//! A convex polygon is the region between its lower and upper chain. The
+//! intersection of two convex polygons is therefore the region below the
+//! lower envelope of the upper chains and above the upper envelope of the
+//! lower chains. Both envelopes are found by merging the chains from left to
+//! right, which takes linear time since the chains are already sorted.
+use crate::data::{Point, Polygon, PolygonConvex};
+use crate::PolygonScalar;
+
+use num::{BigRational, Signed};
+
+// Vertices of a chain with strictly increasing x-coordinates.
+type Chain = Vec<(BigRational, BigRational)>;
+
+/// Intersection of two convex polygons. Polygons that don't overlap, or only
+/// touch at a vertex or along an edge, have no intersection.
+///
+/// Vertices are computed exactly and rounded with
+/// [`PolygonScalar::from_rational`].
+///
+/// # Time complexity
+/// $O(n + m)$
+///
+/// # Examples
+/// ```rust
+/// # use rgeometry::data::*;
+/// # use rgeometry::algorithms::intersection::convex::convex_intersection;
+/// let square = |x: i32, y: i32| {
+///   PolygonConvex::new_unchecked(Polygon::new(vec![
+///     Point::new([x, y]),
+///     Point::new([x + 2, y]),
+///     Point::new([x + 2, y + 2]),
+///     Point::new([x, y + 2]),
+///   ]).unwrap())
+/// };
+/// let overlap = convex_intersection(&square(0, 0), &square(1, 1)).unwrap();
+/// assert_eq!(overlap.signed_area::<f64>(), 1.0);
+/// assert!(convex_intersection(&square(0, 0), &square(2, 0)).is_none());
+/// ```
+pub fn convex_intersection<T>(
+  a: &PolygonConvex<T>,
+  b: &PolygonConvex<T>,
+) -> Option<PolygonConvex<T>>
+where
+  T: PolygonScalar,
+{
+  let (lower_a, upper_a) = chains(a);
+  let (lower_b, upper_b) = chains(b);
+  let x0 = (&lower_a[0].0).max(&lower_b[0].0).clone();
+  let x1 = (&lower_a[lower_a.len() - 1].0)
+    .min(&lower_b[lower_b.len() - 1].0)
+    .clone();
+  if x0 >= x1 {
+    return None;
+  }
+  let upper: Chain = merge(&clip(&upper_a, &x0, &x1), &clip(&upper_b, &x0, &x1))
+    .into_iter()
+    .map(|(x, ya, yb)| (x, ya.min(yb)))
+    .collect();
+  let lower: Chain = merge(&clip(&lower_a, &x0, &x1), &clip(&lower_b, &x0, &x1))
+    .into_iter()
+    .map(|(x, ya, yb)| (x, ya.max(yb)))
+    .collect();
+
+  // The vertical distance between the envelopes is concave so the envelopes
+  // only cross twice at most.
+  let mut bottom = Vec::new();
+  let mut top = Vec::new();
+  for (x, y_upper, y_lower) in merge(&upper, &lower) {
+    if y_upper >= y_lower {
+      bottom.push(Point::new([x.clone(), y_lower]));
+      top.push(Point::new([x, y_upper]));
+    }
+  }
+  let ring = simplify(bottom.into_iter().chain(top.into_iter().rev()));
+  if ring.len() < 3 {
+    return None;
+  }
+  let vertices = ring
+    .iter()
+    .map(|pt| {
+      Point::new([
+        T::from_rational(&pt.array[0]),
+        T::from_rational(&pt.array[1]),
+      ])
+    })
+    .collect();
+  Some(PolygonConvex::new_unchecked(Polygon::new_unchecked(
+    vertices,
+  )))
+}
+
+// Lower and upper chain from the leftmost to the rightmost x-coordinate.
+// Vertical edges at either end are left out.
+fn chains<T>(poly: &PolygonConvex<T>) -> (Chain, Chain)
+where
+  T: PolygonScalar,
+{
+  let pts: Chain = poly
+    .iter()
+    .map(|pt| (pt.array[0].to_rational(), pt.array[1].to_rational()))
+    .collect();
+  let n = pts.len();
+  let min = (0..n).min_by(|&i, &j| pts[i].cmp(&pts[j])).unwrap();
+  let max = (0..n).max_by(|&i, &j| pts[i].cmp(&pts[j])).unwrap();
+  let walk = |from: usize, to: usize| {
+    let mut chain: Chain = Vec::new();
+    let mut i = from;
+    loop {
+      chain.push(pts[i].clone());
+      if i == to {
+        break;
+      }
+      i = (i + 1) % n;
+    }
+    if chain[chain.len() - 2].0 == chain[chain.len() - 1].0 {
+      chain.pop();
+    }
+    chain
+  };
+  let lower = walk(min, max);
+  let mut upper = walk(max, min);
+  upper.reverse();
+  (lower, upper)
+}
+
+// Linear interpolation at each x-coordinate. Both have to be sorted and inside
+// the range of the chain.
+fn resample(chain: &[(BigRational, BigRational)], xs: &[BigRational]) -> Vec<BigRational> {
+  let mut i = 0;
+  xs.iter()
+    .map(|x| {
+      while chain[i + 1].0 < *x && i + 2 < chain.len() {
+        i += 1;
+      }
+      let (x0, y0) = &chain[i];
+      let (x1, y1) = &chain[i + 1];
+      y0 + (y1 - y0) * (x - x0) / (x1 - x0)
+    })
+    .collect()
+}
+
+// Part of a chain between two x-coordinates.
+fn clip(chain: &[(BigRational, BigRational)], x0: &BigRational, x1: &BigRational) -> Chain {
+  let ends = resample(chain, &[x0.clone(), x1.clone()]);
+  let mut out = vec![(x0.clone(), ends[0].clone())];
+  out.extend(chain.iter().filter(|(x, _)| x > x0 && x < x1).cloned());
+  out.push((x1.clone(), ends[1].clone()));
+  out
+}
+
+// Evaluate two chains with the same x-range at every vertex of either chain
+// and at the points where they cross.
+fn merge(
+  a: &[(BigRational, BigRational)],
+  b: &[(BigRational, BigRational)],
+) -> Vec<(BigRational, BigRational, BigRational)> {
+  let mut xs: Vec<BigRational> = Vec::with_capacity(a.len() + b.len());
+  let (mut i, mut j) = (0, 0);
+  while i < a.len() || j < b.len() {
+    let x = if j == b.len() || (i < a.len() && a[i].0 <= b[j].0) {
+      i += 1;
+      &a[i - 1].0
+    } else {
+      j += 1;
+      &b[j - 1].0
+    };
+    if xs.last() != Some(x) {
+      xs.push(x.clone());
+    }
+  }
+  let ya = resample(a, &xs);
+  let yb = resample(b, &xs);
+
+  let mut out = Vec::with_capacity(xs.len() * 2);
+  for k in 0..xs.len() {
+    if k > 0 {
+      let d0 = &ya[k - 1] - &yb[k - 1];
+      let d1 = &ya[k] - &yb[k];
+      if (d0.is_positive() && d1.is_negative()) || (d0.is_negative() && d1.is_positive()) {
+        let t = &d0 / (&d0 - &d1);
+        let x = &xs[k - 1] + (&xs[k] - &xs[k - 1]) * &t;
+        let y = &ya[k - 1] + (&ya[k] - &ya[k - 1]) * &t;
+        out.push((x, y.clone(), y));
+      }
+    }
+    out.push((xs[k].clone(), ya[k].clone(), yb[k].clone()));
+  }
+  out
+}
+
+// Remove duplicate and colinear vertices from a ring.
+fn simplify(ring: impl Iterator<Item = Point<BigRational, 2>>) -> Vec<Point<BigRational, 2>> {
+  let mut out: Vec<Point<BigRational, 2>> = Vec::new();
+  for pt in ring {
+    while out.len() >= 2
+      && Point::orient(&out[out.len() - 2], &out[out.len() - 1], &pt).is_colinear()
+    {
+      out.pop();
+    }
+    if out.last() != Some(&pt) {
+      out.push(pt);
+    }
+  }
+  loop {
+    let n = out.len();
+    if n < 3 {
+      break;
+    } else if out[n - 1] == out[0] || Point::orient(&out[n - 2], &out[n - 1], &out[0]).is_colinear()
+    {
+      out.pop();
+    } else if Point::orient(&out[n - 1], &out[0], &out[1]).is_colinear() {
+      out.remove(0);
+    } else {
+      break;
+    }
+  }
+  out
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  use crate::algorithms::boolean;
+  use crate::data::{MultiPolygon, Vector};
+  use crate::testing::{rational, square};
+
+  use test_strategy::proptest;
+
+  fn shifted(poly: &PolygonConvex<i8>, dx: i8, dy: i8) -> PolygonConvex<BigRational> {
+    let shift = Vector([
+      BigRational::from_integer(dx.into()),
+      BigRational::from_integer(dy.into()),
+    ]);
+    PolygonConvex::new_unchecked(rational(poly.polygon().clone()).map_points(|pt| pt + &shift))
+  }
+
+  fn convex_square(x: i32, y: i32, size: i32) -> PolygonConvex<BigRational> {
+    PolygonConvex::new_unchecked(rational(square(x, y, size)))
+  }
+
+  fn area(poly: &Polygon<BigRational>) -> BigRational {
+    poly.signed_area_2x::<BigRational>()
+  }
+
+  #[test]
+  fn nested() {
+    let outer = convex_square(0, 0, 10);
+    let inner = convex_square(2, 2, 3);
+    let result = convex_intersection(&outer, &inner).unwrap();
+    assert!(result.equals(&inner));
+    let result = convex_intersection(&inner, &outer).unwrap();
+    assert!(result.equals(&inner));
+  }
+
+  #[test]
+  fn identical() {
+    let poly = convex_square(0, 0, 4);
+    let result = convex_intersection(&poly, &poly).unwrap();
+    assert!(result.equals(&poly));
+    assert_eq!(result.validate().err(), None);
+  }
+
+  #[test]
+  fn touching() {
+    let poly = convex_square(0, 0, 4);
+    // Shared edge.
+    assert!(convex_intersection(&poly, &convex_square(4, 0, 4)).is_none());
+    assert!(convex_intersection(&poly, &convex_square(0, 4, 4)).is_none());
+    // Shared vertex.
+    assert!(convex_intersection(&poly, &convex_square(4, 4, 4)).is_none());
+    // Far apart.
+    assert!(convex_intersection(&poly, &convex_square(10, 0, 4)).is_none());
+  }
+
+  #[test]
+  fn rational_vertices() {
+    let triangle = |pts: [(i32, i32); 3]| {
+      PolygonConvex::new_unchecked(Polygon::new_unchecked(
+        pts
+          .iter()
+          .map(|&(x, y)| {
+            Point::new([
+              BigRational::from_integer(x.into()),
+              BigRational::from_integer(y.into()),
+            ])
+          })
+          .collect(),
+      ))
+    };
+    let a = triangle([(0, 0), (3, 0), (0, 3)]);
+    let b = triangle([(0, 1), (1, 0), (2, 2)]);
+    let result = convex_intersection(&a, &b).unwrap();
+    assert_eq!(result.validate().err(), None);
+    // The edge from (1,0) to (2,2) leaves 'a' at (5/3, 4/3).
+    let exit = Point::new([
+      BigRational::new(5.into(), 3.into()),
+      BigRational::new(4.into(), 3.into()),
+    ]);
+    assert!(result.iter().any(|pt| pt == &exit));
+  }
+
+  #[proptest]
+  fn matches_boolean_intersection(
+    #[any(3..20)] a: PolygonConvex<i8>,
+    #[any(3..20)] b: PolygonConvex<i8>,
+    dx: i8,
+    dy: i8,
+  ) {
+    let a = shifted(&a, 0, 0);
+    let b = shifted(&b, dx, dy);
+    let expected = boolean::intersection(a.polygon(), b.polygon());
+    let expected = MultiPolygon::signed_area_2x::<BigRational>(&expected);
+    match convex_intersection(&a, &b) {
+      None => assert_eq!(expected, BigRational::from_integer(0.into())),
+      Some(result) => {
+        assert_eq!(result.validate().err(), None);
+        assert_eq!(area(result.polygon()), expected);
+      }
+    }
+  }
+}
@@ -0,0 +1,99 @@
+use crate::data::{LineSegmentView, Point};
+use crate::utils::Treap;
+use crate::{Intersects, Orientation, PolygonScalar};
+
+use std::cmp::Ordering;
+
+/// Find a pair of intersecting line segments, if any exist. Uses the Shamos-Hoey
+/// sweep line algorithm which, unlike
+/// [`bentley_ottmann::segment_intersections`](super::bentley_ottmann::segment_intersections),
+/// stops at the first intersection and never has to compute intersection points.
+///
+/// # Time complexity
+/// $O(n \log n)$
+///
+/// # Space complexity
+/// $O(n)$
+pub fn segment_intersection<'a, Edge, T>(edges: &'a [Edge]) -> Option<(&'a Edge, &'a Edge)>
+where
+  &'a Edge: Into<LineSegmentView<'a, T, 2>>,
+  T: PolygonScalar + 'a,
+{
+  let segments: Vec<LineSegmentView<'a, T, 2>> = edges.iter().map(|edge| edge.into()).collect();
+  let (a, b) = sweep(&segments, |a, b| {
+    segments[a].intersect(segments[b]).is_some()
+  })?;
+  Some((&edges[a], &edges[b]))
+}
+
+// Indices of two segments for which 'intersects' holds. 'intersects' may
+// ignore some intersections (such as shared endpoints) but must report every
+// other pair of segments that touch.
+pub(crate) fn sweep<T, F>(
+  segments: &[LineSegmentView<'_, T, 2>],
+  intersects: F,
+) -> Option<(usize, usize)>
+where
+  T: PolygonScalar,
+  F: Fn(usize, usize) -> bool,
+{
+  let mut events: Vec<&Point<T, 2>> = segments
+    .iter()
+    .flat_map(|s| [*s.min.inner(), *s.max.inner()])
+    .collect();
+  events.sort_unstable();
+  events.dedup();
+
+  // Segments sorted by their left-most endpoint.
+  let mut starting: Vec<usize> = (0..segments.len()).collect();
+  starting.sort_by(|&a, &b| segments[a].min.inner().cmp(segments[b].min.inner()));
+  let mut starting = starting.into_iter().peekable();
+
+  // Segments that cross the sweep line, ordered from bottom to top. The ordering
+  // is consistent as long as no intersections have been found.
+  let mut status: Treap<usize> = Treap::new();
+
+  for pt in events {
+    let orient = |s: usize| Point::orient(segments[s].min.inner(), segments[s].max.inner(), pt);
+    let is_below = |&s: &usize| orient(s).is_ccw();
+    let is_above = |&s: &usize| orient(s).is_cw();
+
+    // Segments that end at 'pt' or contain it in their interior.
+    let mut involved = status.remove_range(is_below, is_above);
+    while let Some(s) = starting.next_if(|&s| *segments[s].min.inner() == pt) {
+      involved.push(s);
+    }
+    for (i, &a) in involved.iter().enumerate() {
+      for &b in &involved[..i] {
+        if intersects(a, b) {
+          return Some((a, b));
+        }
+      }
+    }
+
+    // Segments that continue past 'pt', ordered by their slope.
+    let mut continuing: Vec<usize> = involved
+      .into_iter()
+      .filter(|&s| *segments[s].max.inner() > pt)
+      .collect();
+    continuing.sort_by(|&a, &b| {
+      match Point::orient(pt, segments[a].max.inner(), segments[b].max.inner()) {
+        Orientation::CounterClockWise => Ordering::Less,
+        Orientation::ClockWise => Ordering::Greater,
+        Orientation::CoLinear => Ordering::Equal,
+      }
+    });
+
+    let (lower, upper) = status.insert_range(is_below, &continuing);
+    let neighbours = match (continuing.first(), continuing.last()) {
+      (Some(&first), Some(&last)) => [lower.map(|l| (l, first)), upper.map(|u| (last, u))],
+      _ => [lower.zip(upper), None],
+    };
+    for &(a, b) in neighbours.iter().flatten() {
+      if intersects(a, b) {
+        return Some((a, b));
+      }
+    }
+  }
+  None
+}
@@ -0,0 +1,369 @@
+//! Minkowski sums of polygons.
+//!
+//! The Minkowski sum of two sets is the set of all sums `a + b` where `a` is in
+//! the first set and `b` is in the second. Sliding a robot shaped like `-b`
+//! around an obstacle `a` traces the boundary of the sum, which makes it the
+//! basic building block for configuration spaces in motion planning.
+use crate::data::{MultiPolygon, Point, PointId, Polygon, PolygonConvex, Vector};
+use crate::PolygonScalar;
+
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+use super::boolean;
+use super::triangulation::earclip::earclip;
+
+/// Minkowski sum of two convex polygons. The edges of both polygons are merged
+/// by angle, and parallel edges are combined so the result is strictly convex.
+///
+/// # Time complexity
+/// $O(n + m)$
+///
+/// # Examples
+/// ```rust
+/// # use rgeometry::data::*;
+/// # use rgeometry::algorithms::minkowski::convex_minkowski_sum;
+/// let square = PolygonConvex::new_unchecked(Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([1, 0]),
+///   Point::new([1, 1]),
+///   Point::new([0, 1]),
+/// ]).unwrap());
+/// let sum = convex_minkowski_sum(&square, &square);
+/// assert_eq!(sum.iter().count(), 4);
+/// assert_eq!(sum.signed_area::<f64>(), 4.0);
+/// ```
+pub fn convex_minkowski_sum<T>(a: &PolygonConvex<T>, b: &PolygonConvex<T>) -> PolygonConvex<T>
+where
+  T: PolygonScalar,
+{
+  let (start_a, edges_a) = edges_from_bottom(a);
+  let (start_b, edges_b) = edges_from_bottom(b);
+  let origin = Vector([T::from_constant(0), T::from_constant(0)]);
+  let mut pt = start_a + &Vector(start_b.array);
+  let mut vertices = Vec::with_capacity(edges_a.len() + edges_b.len());
+  let (mut i, mut j) = (0, 0);
+  while i < edges_a.len() || j < edges_b.len() {
+    vertices.push(pt.clone());
+    let order = if i == edges_a.len() {
+      Ordering::Greater
+    } else if j == edges_b.len() {
+      Ordering::Less
+    } else {
+      origin.ccw_cmp_around(&edges_a[i], &edges_b[j])
+    };
+    if order != Ordering::Greater {
+      pt += &edges_a[i];
+      i += 1;
+    }
+    if order != Ordering::Less {
+      pt += &edges_b[j];
+      j += 1;
+    }
+  }
+  PolygonConvex::new_unchecked(Polygon::new_unchecked(vertices))
+}
+
+// Start at the lowest vertex (leftmost if there are several) so that the
+// edges are sorted by their counter-clockwise angle from the x-axis.
+fn edges_from_bottom<T>(poly: &PolygonConvex<T>) -> (Point<T, 2>, Vec<Vector<T, 2>>)
+where
+  T: PolygonScalar,
+{
+  let vertices: Vec<&Point<T, 2>> = poly.iter().collect();
+  let n = vertices.len();
+  let start = (0..n)
+    .min_by(|&i, &j| {
+      let (p, q) = (vertices[i], vertices[j]);
+      p.array[1]
+        .total_cmp(&q.array[1])
+        .then_with(|| p.array[0].total_cmp(&q.array[0]))
+    })
+    .unwrap();
+  let edges = (0..n)
+    .map(|k| vertices[(start + k + 1) % n] - vertices[(start + k) % n])
+    .collect();
+  (vertices[start].clone(), edges)
+}
+
+/// Minkowski sum of two polygons. Holes are supported.
+///
+/// Both polygons are split into convex pieces and the sums of every pair of
+/// pieces are merged. The result may have holes and touching components are
+/// kept separate. New vertices from overlapping sums are rounded with
+/// [`PolygonScalar::from_rational`].
+///
+/// # Time complexity
+/// $O((N+k) \cdot N)$ where $N = r_b n + r_a m$ is the total size of the
+/// pairwise sums, $r_a$ and $r_b$ are the number of convex pieces and $k$ is
+/// the number of intersections between the sums. A polygon with $r$ reflex
+/// vertices is split into at most $2r+1$ pieces, so $N$ is $O(n + m)$ if both
+/// polygons are convex and $O(nm)$ in the worst case.
+pub fn minkowski_sum<T>(a: &Polygon<T>, b: &Polygon<T>) -> MultiPolygon<T>
+where
+  T: PolygonScalar,
+{
+  let pieces_a = convex_pieces(a);
+  let pieces_b = convex_pieces(b);
+  let mut sums = Vec::with_capacity(pieces_a.len() * pieces_b.len());
+  for x in &pieces_a {
+    for y in &pieces_b {
+      sums.push(convex_minkowski_sum(x, y).into());
+    }
+  }
+  boolean::union_all(&sums)
+}
+
+// Hertel-Mehlhorn: Triangulate the polygon and remove every diagonal that
+// leaves a convex piece behind. The remaining diagonals each have a reflex
+// endpoint that needs them, which bounds the pieces to 2r+1 for r reflex
+// vertices. Takes O(n^2) time in the worst case. Diagonals are keyed by
+// PointId rather than by coordinates since rings may touch at a point.
+fn convex_pieces<T>(poly: &Polygon<T>) -> Vec<PolygonConvex<T>>
+where
+  T: PolygonScalar,
+{
+  let is_convex = |ring: &[PointId]| {
+    let n = ring.len();
+    (0..n).all(|k| {
+      let [a, b, c] = [ring[(k + n - 1) % n], ring[k], ring[(k + 1) % n]];
+      !Point::orient(poly.point(a), poly.point(b), poly.point(c)).is_cw()
+    })
+  };
+  // Triangles with a positive area.
+  let mut pieces: Vec<Option<Vec<PointId>>> = earclip(poly)
+    .filter(|&(a, b, c)| Point::orient(poly.point(a), poly.point(b), poly.point(c)).is_ccw())
+    .map(|(a, b, c)| Some(vec![a, b, c]))
+    .collect();
+  // The piece to the left of each directed edge.
+  let mut owner = BTreeMap::new();
+  for (idx, piece) in pieces.iter().enumerate() {
+    for edge in ring_edges(piece.as_ref().unwrap()) {
+      owner.insert(edge, idx);
+    }
+  }
+  let diagonals: Vec<(PointId, PointId)> = owner
+    .keys()
+    .filter(|&&(p, q)| p < q && owner.contains_key(&(q, p)))
+    .cloned()
+    .collect();
+  for (p, q) in diagonals {
+    let a = owner[&(p, q)];
+    let b = owner[&(q, p)];
+    let merged = merge(
+      pieces[a].as_ref().unwrap(),
+      pieces[b].as_ref().unwrap(),
+      p,
+      q,
+    );
+    if is_convex(&merged) {
+      owner.remove(&(p, q));
+      owner.remove(&(q, p));
+      for edge in ring_edges(&merged) {
+        owner.insert(edge, a);
+      }
+      pieces[a] = Some(merged);
+      pieces[b] = None;
+    }
+  }
+  pieces
+    .into_iter()
+    .flatten()
+    .map(|ring| {
+      // Vertices where a diagonal was removed may be colinear.
+      let n = ring.len();
+      let corners = (0..n)
+        .filter(|&k| {
+          let [a, b, c] = [ring[(k + n - 1) % n], ring[k], ring[(k + 1) % n]];
+          !Point::orient(poly.point(a), poly.point(b), poly.point(c)).is_colinear()
+        })
+        .map(|k| poly.point(ring[k]).clone())
+        .collect();
+      PolygonConvex::new_unchecked(Polygon::new_unchecked(corners))
+    })
+    .collect()
+}
+
+fn ring_edges(ring: &[PointId]) -> Vec<(PointId, PointId)> {
+  (0..ring.len())
+    .map(|k| (ring[k], ring[(k + 1) % ring.len()]))
+    .collect()
+}
+
+// Join the piece 'a' with the edge p->q and the piece 'b' with the edge q->p.
+fn merge(a: &[PointId], b: &[PointId], p: PointId, q: PointId) -> Vec<PointId> {
+  let i = a.iter().position(|&v| v == q).unwrap();
+  let j = b.iter().position(|&v| v == p).unwrap();
+  // Walk 'a' from q to p and then 'b' from p to q without the endpoints.
+  (0..a.len())
+    .map(|k| a[(i + k) % a.len()])
+    .chain((1..b.len() - 1).map(|k| b[(j + k) % b.len()]))
+    .collect()
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  use crate::testing::polygon_with_touching_holes;
+
+  use num::{BigRational, ToPrimitive};
+  use test_strategy::proptest;
+
+  fn polygon(coords: &[(i64, i64)]) -> Polygon<i64> {
+    Polygon::new(coords.iter().map(|&(x, y)| Point::new([x, y])).collect()).unwrap()
+  }
+
+  fn convex(coords: &[(i64, i64)]) -> PolygonConvex<i64> {
+    PolygonConvex::new_unchecked(polygon(coords))
+  }
+
+  fn area_2x(multi: &MultiPolygon<i64>) -> i64 {
+    multi.signed_area_2x::<i64>()
+  }
+
+  #[test]
+  fn squares() {
+    let a = convex(&[(0, 0), (2, 0), (2, 2), (0, 2)]);
+    let b = convex(&[(5, 5), (6, 5), (6, 6), (5, 6)]);
+    let sum = convex_minkowski_sum(&a, &b);
+    assert!(sum.equals(&polygon(&[(5, 5), (8, 5), (8, 8), (5, 8)])));
+    assert_eq!(sum.validate().err(), None);
+  }
+
+  #[test]
+  fn triangle_and_reflection() {
+    let a = convex(&[(0, 0), (2, 0), (0, 2)]);
+    let b = convex(&[(0, 0), (-2, 0), (0, -2)]);
+    let sum = convex_minkowski_sum(&a, &b);
+    assert_eq!(sum.iter().count(), 6);
+    assert_eq!(sum.validate().err(), None);
+    assert_eq!(sum.signed_area_2x::<i64>(), 24);
+  }
+
+  #[test]
+  fn l_shape() {
+    // L-shape made of three unit squares plus a unit square.
+    let l = polygon(&[(0, 0), (2, 0), (2, 1), (1, 1), (1, 2), (0, 2)]);
+    let square = polygon(&[(0, 0), (1, 0), (1, 1), (0, 1)]);
+    let sum = minkowski_sum(&l, &square);
+    assert_eq!(sum.len(), 1);
+    // 3x3 box without the 1x1 corner.
+    assert_eq!(area_2x(&sum), 2 * 8);
+  }
+
+  #[test]
+  fn convex_pieces_l_shape() {
+    let l = polygon(&[(0, 0), (2, 0), (2, 1), (1, 1), (1, 2), (0, 2)]);
+    let pieces = convex_pieces(&l);
+    assert_eq!(pieces.len(), 2);
+    let square = polygon(&[(0, 0), (1, 0), (2, 0), (2, 2), (0, 2)]);
+    let pieces = convex_pieces(&square);
+    assert_eq!(pieces.len(), 1);
+    assert_eq!(pieces[0].iter().count(), 4);
+  }
+
+  #[proptest]
+  fn convex_pieces_prop(#[any((3..30, ()))] poly: Polygon<i8>) {
+    let poly = poly.cast::<i64>();
+    let reflex = poly
+      .iter_boundary()
+      .filter(|cursor| cursor.orientation() == crate::Orientation::ClockWise)
+      .count();
+    let pieces = convex_pieces(&poly);
+    assert!(pieces.len() <= 2 * reflex + 1);
+    let mut area_2x = 0;
+    for piece in &pieces {
+      assert_eq!(piece.validate().err(), None);
+      area_2x += piece.signed_area_2x::<i64>();
+    }
+    assert_eq!(area_2x, poly.signed_area_2x::<i64>());
+  }
+
+  #[proptest]
+  fn convex_pieces_touching_holes(
+    #[any((3..20, ()))] a: Polygon<i8>,
+    #[any((3..20, ()))] b: Polygon<i8>,
+  ) {
+    // The first hole touches the boundary and the second hole touches the first.
+    let poly = polygon_with_touching_holes(&[a, b]).map(|v| v.to_integer().to_i64().unwrap());
+    let pieces: Vec<Polygon<i64>> = convex_pieces(&poly)
+      .into_iter()
+      .map(Polygon::from)
+      .collect();
+    for piece in &pieces {
+      assert_eq!(piece.validate().err(), None);
+    }
+    let multi = MultiPolygon::new(pieces).unwrap();
+    assert_eq!(area_2x(&multi), area_2x(&MultiPolygon::from(poly)));
+  }
+
+  #[test]
+  fn hole_is_filled() {
+    let frame = Polygon::new_with_holes(
+      vec![
+        Point::new([0, 0]),
+        Point::new([6, 0]),
+        Point::new([6, 6]),
+        Point::new([0, 6]),
+      ],
+      vec![vec![
+        Point::new([2, 2]),
+        Point::new([2, 4]),
+        Point::new([4, 4]),
+        Point::new([4, 2]),
+      ]],
+    )
+    .unwrap()
+    .cast::<i64>();
+    let small = polygon(&[(0, 0), (1, 0), (1, 1), (0, 1)]);
+    let sum = minkowski_sum(&frame, &small);
+    assert_eq!(sum.len(), 1);
+    assert_eq!(sum[0].iter_holes().count(), 1);
+    assert_eq!(area_2x(&sum), 2 * (7 * 7 - 1));
+
+    let big = polygon(&[(0, 0), (3, 0), (3, 3), (0, 3)]);
+    let sum = minkowski_sum(&frame, &big);
+    assert_eq!(sum[0].iter_holes().count(), 0);
+    assert_eq!(area_2x(&sum), 2 * 9 * 9);
+  }
+
+  #[proptest]
+  fn convex_sum_prop(#[any(3..20)] a: PolygonConvex<i8>, #[any(3..20)] b: PolygonConvex<i8>) {
+    let cast =
+      |p: &PolygonConvex<i8>| PolygonConvex::new_unchecked(p.polygon().clone().cast::<i64>());
+    let (a, b) = (cast(&a), cast(&b));
+    let sum = convex_minkowski_sum(&a, &b);
+    assert_eq!(sum.validate().err(), None);
+    assert!(sum.iter().count() <= a.iter().count() + b.iter().count());
+    for p in a.iter() {
+      for q in b.iter() {
+        let pt = p + &Vector(q.array);
+        assert!(sum.contains(&pt));
+      }
+    }
+  }
+
+  #[proptest]
+  fn general_sum_matches_convex(
+    #[any(3..6)] a: PolygonConvex<i8>,
+    #[any(3..6)] b: PolygonConvex<i8>,
+  ) {
+    let cast = |p: &PolygonConvex<i8>| {
+      PolygonConvex::new_unchecked(Polygon::new_unchecked(
+        p.iter()
+          .map(|pt| pt.map(|v| BigRational::from_integer(v.into())))
+          .collect(),
+      ))
+    };
+    let (a, b) = (cast(&a), cast(&b));
+    let expected = convex_minkowski_sum(&a, &b);
+    let sum = minkowski_sum(a.polygon(), b.polygon());
+    assert_eq!(sum.len(), 1);
+    assert!(sum[0].equals(&expected));
+  }
+}
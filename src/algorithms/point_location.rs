@@ -0,0 +1,521 @@
+//! Point location in planar subdivisions.
+//!
+//! A trapezoidal map splits the plane into trapezoids by extending a vertical
+//! wall up and down from every vertex until it hits an edge. Edges are
+//! inserted in random order and a search structure (a DAG) records how the
+//! trapezoids were split, so the trapezoid containing a point can be found in
+//! expected $O(\log n)$ time.
+//!
+//! Points are compared lexicographically, which is the same as shearing the
+//! plane by an infinitesimal amount. Vertical edges and vertices with the same
+//! x-coordinate therefore need no special treatment.
+use crate::algorithms::triangulation::Triangulation;
+use crate::data::dcel::{Dcel, FaceId};
+use crate::data::{Point, PointId, PointLocation, Polygon};
+use crate::{Orientation, PolygonScalar};
+
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone)]
+struct Segment {
+  // 'left' is lexicographically smaller than 'right'.
+  left: PointId,
+  right: PointId,
+  above: FaceId,
+  below: FaceId,
+}
+
+#[derive(Debug, Clone)]
+struct Trapezoid {
+  // None if the trapezoid is unbounded in that direction.
+  top: Option<usize>,
+  bottom: Option<usize>,
+  leftp: Option<PointId>,
+  rightp: Option<PointId>,
+  // Neighbours across the walls above and below 'leftp' and 'rightp'. A wall
+  // has no neighbour if it has zero length.
+  upper_left: Option<usize>,
+  lower_left: Option<usize>,
+  upper_right: Option<usize>,
+  lower_right: Option<usize>,
+  // Leaf in the search structure.
+  node: usize,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+  // Points smaller than 'point' go left, others go right.
+  X {
+    point: PointId,
+    left: usize,
+    right: usize,
+  },
+  Y {
+    segment: usize,
+    above: usize,
+    below: usize,
+  },
+  Leaf(usize),
+}
+
+/// Point location structure for planar subdivisions.
+///
+/// ```rust
+/// # use rgeometry::data::*;
+/// # use rgeometry::algorithms::point_location::TrapezoidalMap;
+/// let square = Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([2, 0]),
+///   Point::new([2, 2]),
+///   Point::new([0, 2]),
+/// ]).unwrap();
+/// let map = TrapezoidalMap::from_polygon(&square);
+/// let unbounded = map.dcel().unbounded_face();
+/// let (face, location) = map.locate(&Point::new([1, 1]));
+/// assert_ne!(face, unbounded);
+/// assert_eq!(location, PointLocation::Inside);
+/// assert_eq!(map.locate(&Point::new([3, 1])), (unbounded, PointLocation::Inside));
+/// assert_eq!(map.locate(&Point::new([2, 1])).1, PointLocation::OnBoundary);
+/// ```
+#[derive(Debug, Clone)]
+pub struct TrapezoidalMap<T> {
+  dcel: Dcel<T>,
+  segments: Vec<Segment>,
+  trapezoids: Vec<Trapezoid>,
+  // Node 0 is the root.
+  nodes: Vec<Node>,
+}
+
+impl<T> TrapezoidalMap<T>
+where
+  T: PolygonScalar,
+{
+  /// Preprocess a planar subdivision. Isolated vertices are ignored.
+  ///
+  /// # Time complexity
+  /// $O(n \log n)$ expected
+  ///
+  /// # Space complexity
+  /// $O(n)$ expected
+  pub fn new(dcel: Dcel<T>) -> TrapezoidalMap<T> {
+    let mut segments: Vec<Segment> = dcel
+      .iter_half_edges()
+      .step_by(2)
+      .map(|eid| {
+        let twin = dcel.twin(eid);
+        let (fwd, bwd) = if dcel.point(dcel.origin(eid)) < dcel.point(dcel.target(eid)) {
+          (eid, twin)
+        } else {
+          (twin, eid)
+        };
+        // The face above a segment is on the left when walking left to right.
+        Segment {
+          left: dcel.origin(fwd),
+          right: dcel.target(fwd),
+          above: dcel.face(fwd),
+          below: dcel.face(bwd),
+        }
+      })
+      .collect();
+    segments.shuffle(&mut SmallRng::seed_from_u64(0));
+
+    let mut map = TrapezoidalMap {
+      dcel,
+      segments: Vec::with_capacity(segments.len()),
+      trapezoids: Vec::new(),
+      nodes: Vec::new(),
+    };
+    map.new_trapezoid(None, None, None, None);
+    for segment in segments {
+      map.insert(segment);
+    }
+    map
+  }
+
+  /// Point location for the inside, outside and holes of a polygon. See
+  /// [`Dcel::from_polygon`].
+  pub fn from_polygon(poly: &Polygon<T>) -> TrapezoidalMap<T> {
+    TrapezoidalMap::new(Dcel::from_polygon(poly))
+  }
+
+  /// Point location for a triangulation. Triangle `i` is face `i + 1`. See
+  /// [`Dcel::from_triangulation`].
+  pub fn from_triangulation(points: Vec<Point<T>>, trig: &Triangulation) -> TrapezoidalMap<T> {
+    TrapezoidalMap::new(Dcel::from_triangulation(points, trig))
+  }
+
+  pub fn dcel(&self) -> &Dcel<T> {
+    &self.dcel
+  }
+
+  /// Find the face that contains a point. Points on an edge or a vertex are
+  /// `OnBoundary` and the face is one of the faces touching the point. Other
+  /// points are `Inside` the face, which is the unbounded face if the point
+  /// is outside of every bounded face.
+  ///
+  /// # Time complexity
+  /// $O(\log n)$ expected
+  pub fn locate(&self, pt: &Point<T>) -> (FaceId, PointLocation) {
+    let mut location = PointLocation::Inside;
+    let mut node = 0;
+    let trapezoid = loop {
+      match self.nodes[node] {
+        Node::X { point, left, right } => {
+          node = match pt.cmp(self.dcel.point(point)) {
+            Ordering::Less => left,
+            Ordering::Equal => {
+              location = PointLocation::OnBoundary;
+              right
+            }
+            Ordering::Greater => right,
+          }
+        }
+        Node::Y {
+          segment,
+          above,
+          below,
+        } => {
+          node = match self.orient(segment, pt) {
+            Orientation::CounterClockWise => above,
+            Orientation::ClockWise => below,
+            Orientation::CoLinear => {
+              location = PointLocation::OnBoundary;
+              above
+            }
+          }
+        }
+        Node::Leaf(trapezoid) => break trapezoid,
+      }
+    };
+    (self.face_of(trapezoid), location)
+  }
+
+  fn face_of(&self, trapezoid: usize) -> FaceId {
+    let trapezoid = &self.trapezoids[trapezoid];
+    match (trapezoid.bottom, trapezoid.top) {
+      (Some(bottom), _) => self.segments[bottom].above,
+      (None, Some(top)) => self.segments[top].below,
+      (None, None) => self.dcel.unbounded_face(),
+    }
+  }
+
+  // Position of a point relative to a segment. Counter-clockwise is above.
+  fn orient(&self, segment: usize, pt: &Point<T>) -> Orientation {
+    let segment = &self.segments[segment];
+    Point::orient(
+      self.dcel.point(segment.left),
+      self.dcel.point(segment.right),
+      pt,
+    )
+  }
+
+  // Trapezoid that contains the start of a segment that is about to be
+  // inserted. Segments sharing the left endpoint are compared by slope.
+  fn find_start(&self, segment: &Segment) -> usize {
+    let p = self.dcel.point(segment.left);
+    let q = self.dcel.point(segment.right);
+    let mut node = 0;
+    loop {
+      match self.nodes[node] {
+        Node::X { point, left, right } => {
+          node = if p < self.dcel.point(point) {
+            left
+          } else {
+            right
+          };
+        }
+        Node::Y {
+          segment,
+          above,
+          below,
+        } => {
+          let side = match self.orient(segment, p) {
+            Orientation::CoLinear => self.orient(segment, q),
+            side => side,
+          };
+          node = if side.is_ccw() { above } else { below };
+        }
+        Node::Leaf(trapezoid) => return trapezoid,
+      }
+    }
+  }
+
+  fn new_trapezoid(
+    &mut self,
+    top: Option<usize>,
+    bottom: Option<usize>,
+    leftp: Option<PointId>,
+    rightp: Option<PointId>,
+  ) -> usize {
+    let idx = self.trapezoids.len();
+    self.trapezoids.push(Trapezoid {
+      top,
+      bottom,
+      leftp,
+      rightp,
+      upper_left: None,
+      lower_left: None,
+      upper_right: None,
+      lower_right: None,
+      node: self.nodes.len(),
+    });
+    self.nodes.push(Node::Leaf(idx));
+    idx
+  }
+
+  // Make 'left' and 'right' neighbours across the wall above a point.
+  fn link_upper(&mut self, left: Option<usize>, right: Option<usize>) {
+    if let Some(left) = left {
+      self.trapezoids[left].upper_right = right;
+    }
+    if let Some(right) = right {
+      self.trapezoids[right].upper_left = left;
+    }
+  }
+
+  // Make 'left' and 'right' neighbours across the wall below a point.
+  fn link_lower(&mut self, left: Option<usize>, right: Option<usize>) {
+    if let Some(left) = left {
+      self.trapezoids[left].lower_right = right;
+    }
+    if let Some(right) = right {
+      self.trapezoids[right].lower_left = left;
+    }
+  }
+
+  fn push_node(&mut self, node: Node) -> usize {
+    self.nodes.push(node);
+    self.nodes.len() - 1
+  }
+
+  // Split the trapezoids crossed by a segment into the parts above and below
+  // it. Walls that are cut off by the segment disappear and the trapezoids on
+  // either side of them are merged.
+  fn insert(&mut self, segment: Segment) {
+    let (p, q) = (segment.left, segment.right);
+    let mut crossed = vec![self.find_start(&segment)];
+    let s = self.segments.len();
+    self.segments.push(segment);
+    loop {
+      let last = &self.trapezoids[*crossed.last().unwrap()];
+      match last.rightp {
+        Some(r) if self.dcel.point(r) < self.dcel.point(q) => {
+          let next = if self.orient(s, self.dcel.point(r)).is_ccw() {
+            last.lower_right
+          } else {
+            last.upper_right
+          };
+          crossed.push(next.expect("walls below 'q' have neighbours"));
+        }
+        _ => break,
+      }
+    }
+
+    // Trapezoids above and below the segment for each crossed trapezoid.
+    let mut uppers: Vec<usize> = Vec::with_capacity(crossed.len());
+    let mut lowers: Vec<usize> = Vec::with_capacity(crossed.len());
+    let first = self.trapezoids[crossed[0]].clone();
+    let upper = self.new_trapezoid(first.top, Some(s), Some(p), None);
+    let lower = self.new_trapezoid(Some(s), first.bottom, Some(p), None);
+    uppers.push(upper);
+    lowers.push(lower);
+    let left_part = if first.leftp != Some(p) {
+      let part = self.new_trapezoid(first.top, first.bottom, first.leftp, Some(p));
+      self.link_upper(first.upper_left, Some(part));
+      self.link_lower(first.lower_left, Some(part));
+      self.link_upper(Some(part), Some(upper));
+      self.link_lower(Some(part), Some(lower));
+      Some(part)
+    } else {
+      self.link_upper(first.upper_left, Some(upper));
+      self.link_lower(first.lower_left, Some(lower));
+      None
+    };
+
+    for i in 1..crossed.len() {
+      let prev = self.trapezoids[crossed[i - 1]].clone();
+      let curr = self.trapezoids[crossed[i]].clone();
+      let r = curr.leftp;
+      let (upper, lower) = (uppers[i - 1], lowers[i - 1]);
+      if self.orient(s, self.dcel.point(r.unwrap())).is_ccw() {
+        // The wall below 'r' is cut short by the segment.
+        let next = self.new_trapezoid(curr.top, Some(s), r, None);
+        self.trapezoids[upper].rightp = r;
+        self.link_upper(Some(upper), prev.upper_right);
+        self.link_lower(Some(upper), Some(next));
+        self.link_upper(curr.upper_left, Some(next));
+        uppers.push(next);
+        lowers.push(lower);
+      } else {
+        // The wall above 'r' is cut short by the segment.
+        let next = self.new_trapezoid(Some(s), curr.bottom, r, None);
+        self.trapezoids[lower].rightp = r;
+        self.link_lower(Some(lower), prev.lower_right);
+        self.link_upper(Some(lower), Some(next));
+        self.link_lower(curr.lower_left, Some(next));
+        uppers.push(upper);
+        lowers.push(next);
+      }
+    }
+
+    let last = self.trapezoids[*crossed.last().unwrap()].clone();
+    let (upper, lower) = (*uppers.last().unwrap(), *lowers.last().unwrap());
+    self.trapezoids[upper].rightp = Some(q);
+    self.trapezoids[lower].rightp = Some(q);
+    let right_part = if last.rightp != Some(q) {
+      let part = self.new_trapezoid(last.top, last.bottom, Some(q), last.rightp);
+      self.link_upper(Some(part), last.upper_right);
+      self.link_lower(Some(part), last.lower_right);
+      self.link_upper(Some(upper), Some(part));
+      self.link_lower(Some(lower), Some(part));
+      Some(part)
+    } else {
+      self.link_upper(Some(upper), last.upper_right);
+      self.link_lower(Some(lower), last.lower_right);
+      None
+    };
+
+    // Replace the leaves of the crossed trapezoids.
+    let k = crossed.len() - 1;
+    for (i, &old) in crossed.iter().enumerate() {
+      let mut node = Node::Y {
+        segment: s,
+        above: self.trapezoids[uppers[i]].node,
+        below: self.trapezoids[lowers[i]].node,
+      };
+      if let (0, Some(part)) = (i, left_part) {
+        node = Node::X {
+          point: p,
+          left: self.trapezoids[part].node,
+          right: self.push_node(node),
+        };
+      }
+      if let (true, Some(part)) = (i == k, right_part) {
+        node = Node::X {
+          point: q,
+          left: self.push_node(node),
+          right: self.trapezoids[part].node,
+        };
+      }
+      let leaf = self.trapezoids[old].node;
+      self.nodes[leaf] = node;
+    }
+  }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  use crate::algorithms::triangulation::delaunay::delaunay;
+  use crate::data::TriangleView;
+  use crate::testing::frame;
+
+  use proptest::collection::vec;
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  #[test]
+  fn polygon_with_hole() {
+    let map = TrapezoidalMap::from_polygon(&frame());
+    let unbounded = map.dcel().unbounded_face();
+    let (inside, location) = map.locate(&Point::new([2, 6]));
+    assert_eq!(location, PointLocation::Inside);
+    assert_ne!(inside, unbounded);
+    let (hole, location) = map.locate(&Point::new([6, 6]));
+    assert_eq!(location, PointLocation::Inside);
+    assert_ne!(hole, unbounded);
+    assert_ne!(hole, inside);
+    // Vertically aligned with the hole's vertical edges.
+    assert_eq!(
+      map.locate(&Point::new([4, 2])),
+      (inside, PointLocation::Inside)
+    );
+    assert_eq!(
+      map.locate(&Point::new([8, 10])),
+      (inside, PointLocation::Inside)
+    );
+    assert_eq!(
+      map.locate(&Point::new([-1, 6])),
+      (unbounded, PointLocation::Inside)
+    );
+    assert_eq!(
+      map.locate(&Point::new([0, 20])),
+      (unbounded, PointLocation::Inside)
+    );
+    for pt in [[4, 4], [4, 6], [6, 8], [12, 12], [12, 5], [0, 3]] {
+      assert_eq!(map.locate(&Point::new(pt)).1, PointLocation::OnBoundary);
+    }
+  }
+
+  #[test]
+  fn empty() {
+    let map: TrapezoidalMap<i32> = TrapezoidalMap::new(Dcel::from_segments_unchecked(&[]));
+    let location = map.locate(&Point::new([0, 0]));
+    assert_eq!(
+      location,
+      (map.dcel().unbounded_face(), PointLocation::Inside)
+    );
+  }
+
+  #[proptest]
+  fn polygon_prop(
+    poly: Polygon<i8>,
+    #[strategy(vec(any::<(i8, i8)>(), 0..20))] queries: Vec<(i8, i8)>,
+  ) {
+    let map = TrapezoidalMap::from_polygon(&poly);
+    let unbounded = map.dcel().unbounded_face();
+    let queries = queries
+      .into_iter()
+      .map(|(x, y)| Point::new([x, y]))
+      .chain(poly.iter().cloned());
+    for pt in queries {
+      let (face, location) = map.locate(&pt);
+      match poly.locate(&pt) {
+        PointLocation::Inside => {
+          prop_assert_ne!(face, unbounded);
+          prop_assert_eq!(location, PointLocation::Inside);
+        }
+        PointLocation::Outside => {
+          prop_assert_eq!(face, unbounded);
+          prop_assert_eq!(location, PointLocation::Inside);
+        }
+        PointLocation::OnBoundary => {
+          prop_assert_eq!(location, PointLocation::OnBoundary);
+        }
+      }
+    }
+  }
+
+  #[proptest]
+  fn triangulation_prop(
+    #[strategy(vec(any::<(i8, i8)>(), 3..30))] coords: Vec<(i8, i8)>,
+    #[strategy(vec(any::<(i8, i8)>(), 0..20))] queries: Vec<(i8, i8)>,
+  ) {
+    let points: Vec<Point<i8>> = coords.iter().map(|&(x, y)| Point::new([x, y])).collect();
+    let trig = delaunay(&points);
+    let map = TrapezoidalMap::from_triangulation(points.clone(), &trig);
+    let triangle = |idx: usize| {
+      let (a, b, c) = trig.triangle(idx);
+      TriangleView::new_unchecked([&points[a.usize()], &points[b.usize()], &points[c.usize()]])
+    };
+    for (x, y) in queries {
+      let pt = Point::new([x, y]);
+      let (face, location) = map.locate(&pt);
+      if face == map.dcel().unbounded_face() {
+        for idx in 0..trig.len() {
+          prop_assert_ne!(triangle(idx).locate(&pt), PointLocation::Inside);
+        }
+      } else {
+        prop_assert_eq!(triangle(face.usize() - 1).locate(&pt), location);
+      }
+    }
+  }
+}
@@ -0,0 +1,495 @@
+//! Rotating calipers on convex polygons.
+//!
+//! Two parallel lines are rotated around a convex polygon while staying in
+//! contact with it. Every time one of the lines becomes flush with an edge,
+//! the points touched by the calipers are candidates for extremal measures
+//! such as the diameter, the width or the smallest enclosing rectangle. Each
+//! caliper only ever moves forward so a full rotation takes linear time.
+//!
+//! All measures are compared with exact rational arithmetic.
+use crate::data::{DirectedIndexEdge, Point, PointId, PolygonConvex};
+use crate::utils::exact::{cross, dot, sub, to_rational, Vec2};
+use crate::PolygonScalar;
+
+use num::{BigRational, Signed, Zero};
+
+/// Rectangle that encloses a convex polygon and is flush with one of its
+/// edges. The remaining three sides touch the vertices `right`, `top` and
+/// `left`, in counter-clockwise order from the edge.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct EnclosingRectangle {
+  pub edge: DirectedIndexEdge,
+  pub right: PointId,
+  pub top: PointId,
+  pub left: PointId,
+}
+
+impl EnclosingRectangle {
+  /// Corners of the rectangle in counter-clockwise order, starting with the
+  /// corner on the line through `edge` closest to `left`.
+  ///
+  /// Corners are computed exactly and rounded with
+  /// [`PolygonScalar::from_rational`].
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn corners<T>(&self, poly: &PolygonConvex<T>) -> [Point<T, 2>; 4]
+  where
+    T: PolygonScalar,
+  {
+    let pt = |pid: PointId| to_rational(poly.point(pid));
+    let origin = pt(self.edge.src);
+    let d = sub(&pt(self.edge.dst), &origin);
+    let dd = dot(&d, &d);
+    let along = |pid: PointId| dot(&d, &sub(&pt(pid), &origin)) / &dd;
+    let height = cross(&d, &sub(&pt(self.top), &origin)) / &dd;
+    let corner = |t: &BigRational, h: &BigRational| {
+      Point::new([
+        T::from_rational(&(&origin.array[0] + &d[0] * t - &d[1] * h)),
+        T::from_rational(&(&origin.array[1] + &d[1] * t + &d[0] * h)),
+      ])
+    };
+    let (left, right) = (along(self.left), along(self.right));
+    let zero = BigRational::zero();
+    [
+      corner(&left, &zero),
+      corner(&right, &zero),
+      corner(&right, &height),
+      corner(&left, &height),
+    ]
+  }
+}
+
+/// All antipodal pairs of a convex polygon. Two vertices are antipodal if
+/// they touch a pair of parallel lines that enclose the polygon. Each pair is
+/// reported once.
+///
+/// # Time complexity
+/// $O(n)$
+///
+/// # Examples
+/// ```rust
+/// # use rgeometry::data::*;
+/// # use rgeometry::algorithms::rotating_calipers::antipodal_pairs;
+/// let square = PolygonConvex::new_unchecked(Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([1, 0]),
+///   Point::new([1, 1]),
+///   Point::new([0, 1]),
+/// ]).unwrap());
+/// // Opposite sides and both diagonals.
+/// assert_eq!(antipodal_pairs(&square).len(), 6);
+/// ```
+pub fn antipodal_pairs<T>(poly: &PolygonConvex<T>) -> Vec<(PointId, PointId)>
+where
+  T: PolygonScalar,
+{
+  let ids = poly.boundary_slice();
+  let n = ids.len();
+  let calipers = rotate(poly);
+  let mut pairs = Vec::new();
+  for i in 0..n {
+    // A vertex is antipodal to everything between the farthest vertices of
+    // its two edges.
+    let from = calipers[(i + n - 1) % n].top;
+    let to = calipers[i].top_last;
+    let mut j = from;
+    loop {
+      if i < j {
+        pairs.push((ids[i], ids[j]));
+      }
+      if j == to {
+        break;
+      }
+      j = (j + 1) % n;
+    }
+  }
+  pairs
+}
+
+/// Pair of vertices with the largest distance between them.
+///
+/// # Time complexity
+/// $O(n)$
+///
+/// # Examples
+/// ```rust
+/// # use rgeometry::data::*;
+/// # use rgeometry::algorithms::rotating_calipers::diameter;
+/// let poly = PolygonConvex::new_unchecked(Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([4, 0]),
+///   Point::new([5, 3]),
+///   Point::new([0, 1]),
+/// ]).unwrap());
+/// let (a, b) = diameter(&poly);
+/// assert_eq!(poly.point(a).squared_euclidean_distance::<i32>(poly.point(b)), 34);
+/// ```
+pub fn diameter<T>(poly: &PolygonConvex<T>) -> (PointId, PointId)
+where
+  T: PolygonScalar,
+{
+  let dist = |&(a, b): &(PointId, PointId)| {
+    let d = sub(&to_rational(poly.point(a)), &to_rational(poly.point(b)));
+    dot(&d, &d)
+  };
+  antipodal_pairs(poly)
+    .into_iter()
+    .map(|pair| (dist(&pair), pair))
+    .max_by(|(a, _), (b, _)| a.cmp(b))
+    .map(|(_, pair)| pair)
+    .unwrap()
+}
+
+/// Narrowest pair of parallel lines that enclose the polygon. One of the
+/// lines goes through the returned edge and the other through the returned
+/// vertex.
+///
+/// # Time complexity
+/// $O(n)$
+///
+/// # Examples
+/// ```rust
+/// # use rgeometry::data::*;
+/// # use rgeometry::algorithms::rotating_calipers::width;
+/// let poly = PolygonConvex::new_unchecked(Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([4, 0]),
+///   Point::new([4, 1]),
+///   Point::new([0, 1]),
+/// ]).unwrap());
+/// let (edge, vertex) = width(&poly);
+/// assert_eq!(poly.point(edge.src).y_coord(), &0);
+/// assert_eq!(poly.point(vertex).y_coord(), &1);
+/// ```
+pub fn width<T>(poly: &PolygonConvex<T>) -> (DirectedIndexEdge, PointId)
+where
+  T: PolygonScalar,
+{
+  let ids = poly.boundary_slice();
+  let pts = rational_points(poly);
+  rotate(poly)
+    .into_iter()
+    .map(|caliper| {
+      let (d, origin) = edge_vector(&pts, caliper.edge);
+      let height = cross(&d, &sub(&pts[caliper.top], origin));
+      // Squared width, (height/|d|)^2.
+      let measure = &height * &height / dot(&d, &d);
+      (measure, caliper)
+    })
+    .min_by(|(a, _), (b, _)| a.cmp(b))
+    .map(|(_, caliper)| (index_edge(ids, caliper.edge), ids[caliper.top]))
+    .unwrap()
+}
+
+/// Enclosing rectangle with the smallest area. One side of the rectangle is
+/// always flush with an edge of the polygon.
+///
+/// # Time complexity
+/// $O(n)$
+///
+/// # Examples
+/// ```rust
+/// # use rgeometry::data::*;
+/// # use rgeometry::algorithms::rotating_calipers::min_area_rectangle;
+/// let diamond = PolygonConvex::new_unchecked(Polygon::new(vec![
+///   Point::new([0, -2]),
+///   Point::new([2, 0]),
+///   Point::new([0, 2]),
+///   Point::new([-2, 0]),
+/// ]).unwrap());
+/// let rect = min_area_rectangle(&diamond);
+/// assert_eq!(rect.corners(&diamond), [
+///   Point::new([0, -2]),
+///   Point::new([2, 0]),
+///   Point::new([0, 2]),
+///   Point::new([-2, 0]),
+/// ]);
+/// ```
+pub fn min_area_rectangle<T>(poly: &PolygonConvex<T>) -> EnclosingRectangle
+where
+  T: PolygonScalar,
+{
+  min_rectangle(poly, |width, height, dd| width * height / dd)
+}
+
+/// Enclosing rectangle with the smallest perimeter. One side of the rectangle
+/// is always flush with an edge of the polygon.
+///
+/// # Time complexity
+/// $O(n)$
+pub fn min_perimeter_rectangle<T>(poly: &PolygonConvex<T>) -> EnclosingRectangle
+where
+  T: PolygonScalar,
+{
+  // Squared half perimeter, ((width+height)/|d|)^2.
+  min_rectangle(poly, |width, height, dd| {
+    let half = width + height;
+    &half * &half / dd
+  })
+}
+
+fn min_rectangle<T, F>(poly: &PolygonConvex<T>, measure: F) -> EnclosingRectangle
+where
+  T: PolygonScalar,
+  F: Fn(&BigRational, &BigRational, &BigRational) -> BigRational,
+{
+  let ids = poly.boundary_slice();
+  let pts = rational_points(poly);
+  rotate(poly)
+    .into_iter()
+    .map(|caliper| {
+      let (d, origin) = edge_vector(&pts, caliper.edge);
+      let width = dot(&d, &sub(&pts[caliper.right], &pts[caliper.left]));
+      let height = cross(&d, &sub(&pts[caliper.top], origin));
+      (measure(&width, &height, &dot(&d, &d)), caliper)
+    })
+    .min_by(|(a, _), (b, _)| a.cmp(b))
+    .map(|(_, caliper)| EnclosingRectangle {
+      edge: index_edge(ids, caliper.edge),
+      right: ids[caliper.right],
+      top: ids[caliper.top],
+      left: ids[caliper.left],
+    })
+    .unwrap()
+}
+
+// Extreme vertices when the calipers are flush with an edge. All fields are
+// positions in the boundary. If the edge is parallel to an opposite edge then
+// 'top' and 'top_last' are the two ends of that edge.
+struct Caliper {
+  edge: usize,
+  right: usize,
+  top: usize,
+  top_last: usize,
+  left: usize,
+}
+
+fn rotate<T>(poly: &PolygonConvex<T>) -> Vec<Caliper>
+where
+  T: PolygonScalar,
+{
+  let pts = rational_points(poly);
+  let n = pts.len();
+  let next = |i: usize| (i + 1) % n;
+  let step = |i: usize| sub(&pts[next(i)], &pts[i]);
+  let (mut right, mut top, mut left) = (1, 1, 0);
+  let mut calipers = Vec::with_capacity(n);
+  for edge in 0..n {
+    let d = step(edge);
+    while dot(&d, &step(right)).is_positive() {
+      right = next(right);
+    }
+    while cross(&d, &step(top)).is_positive() {
+      top = next(top);
+    }
+    let top_last = if cross(&d, &step(top)).is_zero() {
+      next(top)
+    } else {
+      top
+    };
+    if edge == 0 {
+      left = top;
+    }
+    while dot(&d, &step(left)).is_negative() {
+      left = next(left);
+    }
+    calipers.push(Caliper {
+      edge,
+      right,
+      top,
+      top_last,
+      left,
+    });
+  }
+  calipers
+}
+
+fn rational_points<T: PolygonScalar>(poly: &PolygonConvex<T>) -> Vec<Point<BigRational, 2>> {
+  poly.iter().map(to_rational).collect()
+}
+
+fn edge_vector(pts: &[Point<BigRational, 2>], edge: usize) -> (Vec2, &Point<BigRational, 2>) {
+  let origin = &pts[edge];
+  (sub(&pts[(edge + 1) % pts.len()], origin), origin)
+}
+
+fn index_edge(ids: &[PointId], edge: usize) -> DirectedIndexEdge {
+  DirectedIndexEdge {
+    src: ids[edge],
+    dst: ids[(edge + 1) % ids.len()],
+  }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  use crate::data::{IndexEdge, Polygon};
+
+  use std::collections::BTreeSet;
+  use test_strategy::proptest;
+
+  fn pt(poly: &PolygonConvex<i8>, pid: PointId) -> [i64; 2] {
+    let p = poly.point(pid);
+    [p.array[0] as i64, p.array[1] as i64]
+  }
+
+  fn cross_at(a: [i64; 2], b: [i64; 2], c: [i64; 2]) -> i64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+  }
+
+  fn dot_at(a: [i64; 2], b: [i64; 2], c: [i64; 2]) -> i64 {
+    (b[0] - a[0]) * (c[0] - a[0]) + (b[1] - a[1]) * (c[1] - a[1])
+  }
+
+  fn squared_dist(a: [i64; 2], b: [i64; 2]) -> i64 {
+    dot_at(a, b, b)
+  }
+
+  fn ratio(numer: i64, denom: i64) -> BigRational {
+    BigRational::new(numer.into(), denom.into())
+  }
+
+  fn edges(poly: &PolygonConvex<i8>) -> Vec<DirectedIndexEdge> {
+    let ids = poly.boundary_slice();
+    (0..ids.len()).map(|i| index_edge(ids, i)).collect()
+  }
+
+  // Vertices at the largest distance from the line through an edge.
+  fn farthest(poly: &PolygonConvex<i8>, edge: DirectedIndexEdge) -> Vec<PointId> {
+    let (a, b) = (pt(poly, edge.src), pt(poly, edge.dst));
+    let height = |pid: &PointId| cross_at(a, b, pt(poly, *pid));
+    let ids = poly.boundary_slice();
+    let max = ids.iter().map(height).max().unwrap();
+    ids.iter().copied().filter(|p| height(p) == max).collect()
+  }
+
+  // Squared width perpendicular to an edge, and the area and squared half
+  // perimeter of the enclosing rectangle that is flush with the edge.
+  fn measures(
+    poly: &PolygonConvex<i8>,
+    edge: DirectedIndexEdge,
+  ) -> (BigRational, BigRational, BigRational) {
+    let (a, b) = (pt(poly, edge.src), pt(poly, edge.dst));
+    let ids = poly.boundary_slice();
+    let along = |pid: &PointId| dot_at(a, b, pt(poly, *pid));
+    let width = ids.iter().map(along).max().unwrap() - ids.iter().map(along).min().unwrap();
+    let height = cross_at(a, b, pt(poly, farthest(poly, edge)[0]));
+    let dd = squared_dist(a, b);
+    (
+      ratio(height * height, dd),
+      ratio(width * height, dd),
+      ratio((width + height).pow(2), dd),
+    )
+  }
+
+  // The rectangle is flush with its edge and encloses the polygon.
+  fn check_rectangle(poly: &PolygonConvex<i8>, rect: &EnclosingRectangle) {
+    let poly = PolygonConvex::new_unchecked(
+      poly
+        .polygon()
+        .clone()
+        .map(|v| BigRational::from_integer(v.into())),
+    );
+    let corners = rect.corners(&poly);
+    assert!(Point::orient(&corners[0], &corners[1], poly.point(rect.edge.src)).is_colinear());
+    assert!(Point::orient(&corners[0], &corners[1], poly.point(rect.edge.dst)).is_colinear());
+    for vertex in poly.iter() {
+      for i in 0..4 {
+        assert!(!Point::orient(&corners[i], &corners[(i + 1) % 4], vertex).is_cw());
+      }
+    }
+  }
+
+  #[test]
+  fn triangle() {
+    let poly = PolygonConvex::new_unchecked(
+      Polygon::new(vec![
+        Point::new([0, 0]),
+        Point::new([10, 0]),
+        Point::new([3, 2]),
+      ])
+      .unwrap(),
+    );
+    assert_eq!(antipodal_pairs(&poly).len(), 3);
+    let (a, b) = diameter(&poly);
+    assert_eq!(squared_dist(pt(&poly, a), pt(&poly, b)), 100);
+    let (edge, vertex) = width(&poly);
+    assert_eq!(pt(&poly, edge.src), [0, 0]);
+    assert_eq!(pt(&poly, vertex), [3, 2]);
+  }
+
+  #[test]
+  fn rotated_square() {
+    let pt = |x: i32, y: i32| {
+      Point::new([
+        BigRational::from_integer(x.into()),
+        BigRational::from_integer(y.into()),
+      ])
+    };
+    let square = Polygon::new(vec![pt(0, 0), pt(2, 1), pt(1, 3), pt(-1, 2)]).unwrap();
+    let poly = PolygonConvex::new_unchecked(square.clone());
+    for rect in [min_area_rectangle(&poly), min_perimeter_rectangle(&poly)] {
+      assert!(Polygon::new_unchecked(rect.corners(&poly).to_vec()).equals(&square));
+    }
+  }
+
+  #[proptest]
+  fn antipodal_pairs_prop(#[any(3..20)] poly: PolygonConvex<i8>) {
+    let found: Vec<IndexEdge> = antipodal_pairs(&poly)
+      .into_iter()
+      .map(|(a, b)| IndexEdge::new(a, b))
+      .collect();
+    let unique: BTreeSet<IndexEdge> = found.iter().copied().collect();
+    assert_eq!(unique.len(), found.len());
+    let mut expected = BTreeSet::new();
+    for edge in edges(&poly) {
+      for vertex in farthest(&poly, edge) {
+        expected.insert(IndexEdge::new(edge.src, vertex));
+        expected.insert(IndexEdge::new(edge.dst, vertex));
+      }
+    }
+    assert_eq!(unique, expected);
+  }
+
+  #[proptest]
+  fn diameter_prop(#[any(3..20)] poly: PolygonConvex<i8>) {
+    let (a, b) = diameter(&poly);
+    let max = poly
+      .boundary_slice()
+      .iter()
+      .flat_map(|&p| poly.boundary_slice().iter().map(move |&q| (p, q)))
+      .map(|(p, q)| squared_dist(pt(&poly, p), pt(&poly, q)))
+      .max()
+      .unwrap();
+    assert_eq!(squared_dist(pt(&poly, a), pt(&poly, b)), max);
+  }
+
+  #[proptest]
+  fn width_and_rectangles_prop(#[any(3..20)] poly: PolygonConvex<i8>) {
+    let all: Vec<_> = edges(&poly)
+      .into_iter()
+      .map(|edge| measures(&poly, edge))
+      .collect();
+
+    let (edge, vertex) = width(&poly);
+    assert!(farthest(&poly, edge).contains(&vertex));
+    let min = all.iter().map(|m| &m.0).min().unwrap();
+    assert_eq!(&measures(&poly, edge).0, min);
+
+    let rect = min_area_rectangle(&poly);
+    check_rectangle(&poly, &rect);
+    let min = all.iter().map(|m| &m.1).min().unwrap();
+    assert_eq!(&measures(&poly, rect.edge).1, min);
+
+    let rect = min_perimeter_rectangle(&poly);
+    check_rectangle(&poly, &rect);
+    let min = all.iter().map(|m| &m.2).min().unwrap();
+    assert_eq!(&measures(&poly, rect.edge).2, min);
+  }
+}
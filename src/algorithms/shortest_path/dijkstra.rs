@@ -0,0 +1,220 @@
+use super::{distance, path_length};
+use crate::algorithms::visibility::graph::{visibility_graph, Cone};
+use crate::algorithms::visibility::naive::iter_vertices;
+use crate::data::{Point, Polygon};
+use crate::PolygonScalar;
+
+use ordered_float::OrderedFloat;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+/// Shortest path between two points inside a polygon with or without holes.
+///
+/// The source and the target are connected to the vertices they can see and
+/// Dijkstra's algorithm finds the shortest path through the
+/// [visibility graph](visibility_graph). Returns the path from `source` to
+/// `target` together with its Euclidean length, or `None` if either point is
+/// outside the polygon.
+///
+/// # Time complexity
+/// $O(n^2 \log n)$
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::shortest_path::dijkstra::shortest_path;
+/// # use rgeometry::data::{Point, Polygon};
+/// let polygon = Polygon::new_with_holes(
+///   vec![
+///     Point::new([0, 0]),
+///     Point::new([6, 0]),
+///     Point::new([6, 6]),
+///     Point::new([0, 6]),
+///   ],
+///   vec![vec![
+///     Point::new([2, 1]),
+///     Point::new([2, 4]),
+///     Point::new([4, 4]),
+///     Point::new([4, 1]),
+///   ]],
+/// )
+/// .unwrap();
+/// let (path, length) = shortest_path(&polygon, &Point::new([1, 3]), &Point::new([5, 3])).unwrap();
+/// assert_eq!(
+///   path,
+///   vec![
+///     Point::new([1, 3]),
+///     Point::new([2, 4]),
+///     Point::new([4, 4]),
+///     Point::new([5, 3]),
+///   ]
+/// );
+/// assert!((length - (2.0 + 2.0 * 2f64.sqrt())).abs() < 1e-9);
+/// ```
+pub fn shortest_path<T>(
+  polygon: &Polygon<T>,
+  source: &Point<T>,
+  target: &Point<T>,
+) -> Option<(Vec<Point<T>>, f64)>
+where
+  T: PolygonScalar,
+{
+  let source_cone = Cone::new(polygon, source)?;
+  let target_cone = Cone::new(polygon, target)?;
+
+  // Nodes are the vertices followed by the source and the target.
+  let n = polygon.points.len();
+  let (s, t) = (n, n + 1);
+  let point = |node: usize| match node {
+    _ if node == s => source,
+    _ if node == t => target,
+    _ => &polygon.points[node],
+  };
+  let mut neighbours = vec![Vec::new(); n + 2];
+  let mut connect = |a: usize, b: usize| {
+    neighbours[a].push(b);
+    neighbours[b].push(a);
+  };
+  for (a, b) in visibility_graph(polygon) {
+    connect(a.usize(), b.usize());
+  }
+  for vertex in iter_vertices(polygon) {
+    if source_cone.sees(polygon, source, vertex.point()) {
+      connect(s, vertex.point_id().usize());
+    }
+    if target_cone.sees(polygon, target, vertex.point()) {
+      connect(t, vertex.point_id().usize());
+    }
+  }
+  if source_cone.sees(polygon, source, target) {
+    connect(s, t);
+  }
+
+  let mut dist = vec![f64::INFINITY; n + 2];
+  let mut prev = vec![None; n + 2];
+  let mut queue = BinaryHeap::new();
+  dist[s] = 0.0;
+  queue.push(Reverse((OrderedFloat(0.0), s)));
+  while let Some(Reverse((OrderedFloat(d), node))) = queue.pop() {
+    if node == t {
+      break;
+    }
+    if d > dist[node] {
+      continue;
+    }
+    for &next in &neighbours[node] {
+      let d_next = d + distance(point(node), point(next));
+      if d_next < dist[next] {
+        dist[next] = d_next;
+        prev[next] = Some(node);
+        queue.push(Reverse((OrderedFloat(d_next), next)));
+      }
+    }
+  }
+
+  let mut path = vec![target.clone()];
+  let mut node = t;
+  while node != s {
+    node = prev[node].expect("The interior of a polygon is connected");
+    path.push(point(node).clone());
+  }
+  path.reverse();
+  path.dedup();
+  let length = path_length(&path);
+  Some((path, length))
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::algorithms::visibility::graph::crosses;
+  use crate::data::{MultiPolygon, PointLocation};
+  use crate::testing::polygon_with_holes;
+
+  use num::{BigRational, ToPrimitive};
+  use std::cmp::Ordering;
+  use test_strategy::proptest;
+
+  // Cut the segment at the vertices on it and check that the pieces are inside
+  // the polygon. 'doubled' is the polygon scaled by two.
+  fn inside(
+    polygon: &Polygon<i64>,
+    doubled: &MultiPolygon<i64>,
+    a: &Point<i64>,
+    b: &Point<i64>,
+  ) -> bool {
+    let mut cuts = vec![a, b];
+    for cursor in iter_vertices(polygon) {
+      let (src, dst) = (cursor.point(), cursor.next().point());
+      if crosses(a, b, src, dst) {
+        return false;
+      }
+      if Point::orient(a, b, src).is_colinear()
+        && a.cmp_distance_to(src, b) == Ordering::Less
+        && b.cmp_distance_to(src, a) == Ordering::Less
+      {
+        cuts.push(src);
+      }
+    }
+    cuts.sort_by(|p, q| a.cmp_distance_to(p, q));
+    cuts.windows(2).all(|pair| {
+      let mid = Point::new([
+        pair[0].x_coord() + pair[1].x_coord(),
+        pair[0].y_coord() + pair[1].y_coord(),
+      ]);
+      doubled.locate(&mid) != PointLocation::Outside
+    })
+  }
+
+  #[proptest]
+  fn valid_paths(
+    #[any((3..8, ()))] a: Polygon<i8>,
+    #[any((3..8, ()))] b: Polygon<i8>,
+    #[strategy(0i64..1000)] x1: i64,
+    #[strategy(0i64..500)] y1: i64,
+    #[strategy(0i64..1000)] x2: i64,
+    #[strategy(0i64..500)] y2: i64,
+  ) {
+    let polygon = polygon_with_holes(&[a, b]).map(|v| v.to_integer().to_i64().unwrap() * 2);
+    let doubled = MultiPolygon::from(polygon.clone().map(|v| v * 2));
+    let (source, target) = (Point::new([x1, y1]), Point::new([x2, y2]));
+    let located =
+      |pt: &Point<i64>| doubled.locate(&Point::new([pt.x_coord() * 2, pt.y_coord() * 2]));
+    match shortest_path(&polygon, &source, &target) {
+      None => assert!(
+        located(&source) == PointLocation::Outside || located(&target) == PointLocation::Outside
+      ),
+      Some((path, length)) => {
+        assert_eq!(path.first(), Some(&source));
+        assert_eq!(path.last(), Some(&target));
+        for pair in path.windows(2) {
+          assert!(inside(&polygon, &doubled, &pair[0], &pair[1]));
+        }
+        let (_, reverse) = shortest_path(&polygon, &target, &source).unwrap();
+        assert!((length - reverse).abs() <= 1e-9 * length.max(1.0));
+      }
+    }
+  }
+
+  #[test]
+  fn between_holes() {
+    let square = |x: i8| {
+      Polygon::new(vec![
+        Point::new([x, 0]),
+        Point::new([x + 1, 0]),
+        Point::new([x + 1, 1]),
+        Point::new([x, 1]),
+      ])
+      .unwrap()
+    };
+    let polygon = polygon_with_holes(&[square(0), square(0)]);
+    let pt = |x: i64, y: i64| Point::new([x, y].map(|v| BigRational::from_integer(v.into())));
+    // The straight line touches a corner of each hole.
+    let (_, length) = shortest_path(&polygon, &pt(1, 0), &pt(4, 3)).unwrap();
+    assert!((length - 18f64.sqrt()).abs() < 1e-9);
+    // Over the first hole.
+    let (_, length) = shortest_path(&polygon, &pt(0, 1), &pt(5, 3)).unwrap();
+    assert!((length - (2f64.sqrt() + 17f64.sqrt())).abs() < 1e-9);
+  }
+}
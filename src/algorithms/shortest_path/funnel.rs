@@ -0,0 +1,236 @@
+use super::path_length;
+use crate::algorithms::triangulation::Triangulation;
+use crate::data::{Point, PointId, PointLocation, Polygon, TriangleView};
+use crate::PolygonScalar;
+
+use std::collections::VecDeque;
+
+/// Shortest path between two points inside a simple polygon.
+///
+/// The polygon is triangulated and the triangles between the two points are
+/// found by walking the dual tree of the triangulation. The funnel algorithm
+/// then pulls the path taut through the diagonals that separate those
+/// triangles, so the path only bends at vertices of the polygon. Returns the
+/// path from `source` to `target` together with its Euclidean length, or
+/// `None` if either point is outside the polygon.
+///
+/// # Time complexity
+/// $O(n^2)$
+///
+/// # Panics
+///
+/// Panics if the polygon has holes. Use
+/// [`dijkstra::shortest_path`](super::dijkstra::shortest_path) instead.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::shortest_path::funnel::shortest_path;
+/// # use rgeometry::data::{Point, Polygon};
+/// let polygon = Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([6, 0]),
+///   Point::new([6, 6]),
+///   Point::new([4, 6]),
+///   Point::new([4, 2]),
+///   Point::new([2, 2]),
+///   Point::new([2, 6]),
+///   Point::new([0, 6]),
+/// ])
+/// .unwrap();
+/// let (path, length) = shortest_path(&polygon, &Point::new([1, 5]), &Point::new([5, 5])).unwrap();
+/// assert_eq!(
+///   path,
+///   vec![
+///     Point::new([1, 5]),
+///     Point::new([2, 2]),
+///     Point::new([4, 2]),
+///     Point::new([5, 5]),
+///   ]
+/// );
+/// assert!((length - (2.0 + 2.0 * 10f64.sqrt())).abs() < 1e-9);
+/// ```
+pub fn shortest_path<T>(
+  polygon: &Polygon<T>,
+  source: &Point<T>,
+  target: &Point<T>,
+) -> Option<(Vec<Point<T>>, f64)>
+where
+  T: PolygonScalar,
+{
+  assert_eq!(
+    polygon.rings.len(),
+    1,
+    "The funnel algorithm only supports polygons without holes."
+  );
+  let triangulation = Triangulation::from_triangles(
+    polygon
+      .triangulate()
+      .map(|(a, b, c)| [a.point_id(), b.point_id(), c.point_id()])
+      .collect(),
+  );
+  let locate = |pt: &Point<T>| {
+    triangulation.iter().position(|(a, b, c)| {
+      let trig =
+        TriangleView::new_unchecked([polygon.point(a), polygon.point(b), polygon.point(c)]);
+      trig.locate(pt) != PointLocation::Outside
+    })
+  };
+  let start = locate(source)?;
+  let end = locate(target)?;
+
+  let mut portals = vec![(source, source)];
+  for (left, right) in diagonals(&triangulation, start, end) {
+    portals.push((polygon.point(left), polygon.point(right)));
+  }
+  portals.push((target, target));
+  let mut path = funnel(&portals);
+  path.dedup();
+  let length = path_length(&path);
+  Some((path, length))
+}
+
+// Diagonals crossed on the way from triangle 'start' to triangle 'end' as
+// (left, right) pairs, seen in the direction of travel.
+fn diagonals(triangulation: &Triangulation, start: usize, end: usize) -> Vec<(PointId, PointId)> {
+  let mut parent = vec![None; triangulation.len()];
+  parent[start] = Some(start);
+  let mut queue = VecDeque::from(vec![start]);
+  while let Some(idx) = queue.pop_front() {
+    if idx == end {
+      break;
+    }
+    for &next in triangulation.neighbours(idx).iter().flatten() {
+      if parent[next].is_none() {
+        parent[next] = Some(idx);
+        queue.push_back(next);
+      }
+    }
+  }
+
+  let mut diagonals = Vec::new();
+  let mut idx = end;
+  while idx != start {
+    let prev = parent[idx].expect("The dual of a triangulated polygon is connected");
+    // The neighbour opposite of corner 'i' shares the edge from corner i+1 to
+    // corner i+2. That edge is counter-clockwise around 'prev' so corner i+2 is
+    // on the left when leaving 'prev'.
+    let i = triangulation
+      .neighbours(prev)
+      .iter()
+      .position(|&n| n == Some(idx))
+      .unwrap();
+    let corners = triangulation.triangles[prev];
+    diagonals.push((corners[(i + 2) % 3], corners[(i + 1) % 3]));
+    idx = prev;
+  }
+  diagonals.reverse();
+  diagonals
+}
+
+// Simple stupid funnel algorithm: The funnel is narrowed one portal at a time.
+// When one side of the funnel crosses over the other side, the vertex on the
+// other side becomes the new apex and the scan restarts from the portal where
+// that vertex was added.
+fn funnel<T>(portals: &[(&Point<T>, &Point<T>)]) -> Vec<Point<T>>
+where
+  T: PolygonScalar,
+{
+  let mut path = vec![portals[0].0.clone()];
+  let (mut apex, mut left, mut right) = (portals[0].0, portals[0].0, portals[0].1);
+  let (mut left_idx, mut right_idx) = (0, 0);
+  let mut i = 1;
+  while i < portals.len() {
+    let (next_left, next_right) = portals[i];
+
+    if !Point::orient(apex, right, next_right).is_cw() {
+      if apex == right || !Point::orient(apex, left, next_right).is_ccw() {
+        right = next_right;
+        right_idx = i;
+      } else {
+        path.push(left.clone());
+        apex = left;
+        right = left;
+        right_idx = left_idx;
+        i = left_idx + 1;
+        continue;
+      }
+    }
+
+    if !Point::orient(apex, left, next_left).is_ccw() {
+      if apex == left || !Point::orient(apex, right, next_left).is_cw() {
+        left = next_left;
+        left_idx = i;
+      } else {
+        path.push(right.clone());
+        apex = right;
+        left = right;
+        left_idx = right_idx;
+        i = right_idx + 1;
+        continue;
+      }
+    }
+    i += 1;
+  }
+  path.push(portals[portals.len() - 1].0.clone());
+  path
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::algorithms::shortest_path::dijkstra;
+
+  use test_strategy::proptest;
+
+  // Vertices, edge midpoints and triangle centroids of the polygon scaled by
+  // six so all of them have integer coordinates.
+  fn candidates(polygon: &Polygon<i64>) -> Vec<Point<i64>> {
+    let sum = |pts: &[&Point<i64>], k: i64| {
+      Point::new([
+        pts.iter().map(|pt| pt.x_coord() * k).sum(),
+        pts.iter().map(|pt| pt.y_coord() * k).sum(),
+      ])
+    };
+    let mut points = vec![];
+    for cursor in polygon.iter_boundary() {
+      points.push(sum(&[cursor.point()], 6));
+      points.push(sum(&[cursor.point(), cursor.next().point()], 3));
+    }
+    for (a, b, c) in polygon.triangulate() {
+      points.push(sum(&[a.point(), b.point(), c.point()], 2));
+    }
+    points
+  }
+
+  #[proptest]
+  fn matches_dijkstra(#[any((3..20, ()))] polygon: Polygon<i8>, source: usize, target: usize) {
+    let polygon = polygon.map(i64::from);
+    let points = candidates(&polygon);
+    let (source, target) = (
+      &points[source % points.len()],
+      &points[target % points.len()],
+    );
+    let polygon = polygon.map(|v| v * 6);
+    let (path, length) = shortest_path(&polygon, source, target).unwrap();
+    let (_, expected) = dijkstra::shortest_path(&polygon, source, target).unwrap();
+    assert_eq!(path.first(), Some(source));
+    assert_eq!(path.last(), Some(target));
+    assert!((length - expected).abs() <= 1e-9 * expected.max(1.0));
+  }
+
+  #[test]
+  fn outside() {
+    let polygon = Polygon::new(vec![
+      Point::new([0, 0]),
+      Point::new([2, 0]),
+      Point::new([0, 2]),
+    ])
+    .unwrap();
+    assert_eq!(
+      shortest_path(&polygon, &Point::new([0, 0]), &Point::new([2, 2])),
+      None
+    );
+  }
+}
@@ -0,0 +1,76 @@
+pub mod dijkstra;
+pub mod funnel;
+
+use crate::data::{Point, Polygon};
+use crate::PolygonScalar;
+
+use num_traits::ToPrimitive;
+
+/// Shortest path between two points inside a polygon.
+///
+/// Simple polygons use the [funnel algorithm](funnel::shortest_path) and
+/// polygons with holes use [Dijkstra's algorithm](dijkstra::shortest_path) on
+/// the visibility graph. Returns the path from `source` to `target` together
+/// with its Euclidean length, or `None` if either point is outside the polygon.
+///
+/// # Time complexity
+/// $O(n^2)$ for simple polygons and $O(n^2 \log n)$ for polygons with holes.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::shortest_path::shortest_path;
+/// # use rgeometry::data::{Point, Polygon};
+/// let polygon = Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([4, 0]),
+///   Point::new([4, 2]),
+///   Point::new([2, 2]),
+///   Point::new([2, 4]),
+///   Point::new([0, 4]),
+/// ])
+/// .unwrap();
+/// let (path, length) = shortest_path(&polygon, &Point::new([3, 1]), &Point::new([1, 4])).unwrap();
+/// assert_eq!(
+///   path,
+///   vec![Point::new([3, 1]), Point::new([2, 2]), Point::new([1, 4])]
+/// );
+/// assert!((length - (2f64.sqrt() + 5f64.sqrt())).abs() < 1e-9);
+/// ```
+pub fn shortest_path<T>(
+  polygon: &Polygon<T>,
+  source: &Point<T>,
+  target: &Point<T>,
+) -> Option<(Vec<Point<T>>, f64)>
+where
+  T: PolygonScalar,
+{
+  if polygon.rings.len() == 1 {
+    funnel::shortest_path(polygon, source, target)
+  } else {
+    dijkstra::shortest_path(polygon, source, target)
+  }
+}
+
+// Euclidean distance. The coordinates are subtracted exactly before rounding.
+fn distance<T>(a: &Point<T>, b: &Point<T>) -> f64
+where
+  T: PolygonScalar,
+{
+  let dx = b.x_coord().to_rational() - a.x_coord().to_rational();
+  let dy = b.y_coord().to_rational() - a.y_coord().to_rational();
+  (dx.clone() * dx + dy.clone() * dy)
+    .to_f64()
+    .unwrap_or(f64::INFINITY)
+    .sqrt()
+}
+
+fn path_length<T>(path: &[Point<T>]) -> f64
+where
+  T: PolygonScalar,
+{
+  path
+    .windows(2)
+    .map(|pair| distance(&pair[0], &pair[1]))
+    .sum()
+}
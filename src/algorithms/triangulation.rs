@@ -1 +1,111 @@
+pub mod constrained_delaunay;
+pub mod delaunay;
 pub mod earclip;
+
+use crate::data::PointId;
+
+use std::collections::HashMap;
+
+/// Triangles over a set of points. Triangles are counter-clockwise and refer
+/// to points by their index.
+#[derive(Debug, Clone, Default)]
+pub struct Triangulation {
+  pub(crate) triangles: Vec<[PointId; 3]>,
+  // Neighbour i is on the other side of the edge opposite to vertex i.
+  pub(crate) neighbours: Vec<[Option<usize>; 3]>,
+}
+
+impl Triangulation {
+  /// Link counter-clockwise triangles that share an edge. Every directed edge
+  /// may only be used by a single triangle.
+  ///
+  /// # Panics
+  /// Panics if two triangles use the same directed edge.
+  ///
+  /// # Time complexity
+  /// $O(n)$
+  pub(crate) fn from_triangles(triangles: Vec<[PointId; 3]>) -> Triangulation {
+    let mut edges: HashMap<(usize, usize), usize> = HashMap::new();
+    for (t, tri) in triangles.iter().enumerate() {
+      for i in 0..3 {
+        let edge = (tri[i].usize(), tri[(i + 1) % 3].usize());
+        if let Some(other) = edges.insert(edge, t) {
+          panic!(
+            "triangles {} and {} both use the directed edge {:?}",
+            other, t, edge
+          );
+        }
+      }
+    }
+    let neighbours = triangles
+      .iter()
+      .map(|tri| {
+        let mut ns = [None; 3];
+        for (i, n) in ns.iter_mut().enumerate() {
+          let (u, v) = (tri[(i + 1) % 3], tri[(i + 2) % 3]);
+          *n = edges.get(&(v.usize(), u.usize())).copied();
+        }
+        ns
+      })
+      .collect();
+    Triangulation {
+      triangles,
+      neighbours,
+    }
+  }
+
+  pub fn len(&self) -> usize {
+    self.triangles.len()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.triangles.is_empty()
+  }
+
+  /// Vertices of a triangle in counter-clockwise order.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn triangle(&self, idx: usize) -> (PointId, PointId, PointId) {
+    let [a, b, c] = self.triangles[idx];
+    (a, b, c)
+  }
+
+  /// Triangles adjacent to a triangle. Neighbour `i` shares the edge opposite
+  /// to vertex `i`. Edges without a neighbour are on the boundary.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn neighbours(&self, idx: usize) -> [Option<usize>; 3] {
+    self.neighbours[idx]
+  }
+
+  pub fn iter(&self) -> impl Iterator<Item = (PointId, PointId, PointId)> + '_ {
+    self.triangles.iter().map(|&[a, b, c]| (a, b, c))
+  }
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn from_triangles_neighbours() {
+    let trigs = Triangulation::from_triangles(vec![
+      [PointId(0), PointId(1), PointId(2)],
+      [PointId(0), PointId(2), PointId(3)],
+    ]);
+    assert_eq!(trigs.neighbours(0), [None, Some(1), None]);
+    assert_eq!(trigs.neighbours(1), [None, None, Some(0)]);
+  }
+
+  #[test]
+  #[should_panic(expected = "triangles 0 and 1 both use the directed edge (0, 1)")]
+  fn from_triangles_duplicate_edge() {
+    Triangulation::from_triangles(vec![
+      [PointId(0), PointId(1), PointId(2)],
+      [PointId(0), PointId(1), PointId(3)],
+    ]);
+  }
+}
@@ -0,0 +1,238 @@
+use crate::data::{Point, PointId, Polygon};
+use crate::PolygonScalar;
+
+use super::earclip::earclip;
+use super::Triangulation;
+
+use std::cmp::Ordering;
+
+/// Constrained Delaunay triangulation of a polygon. Holes are supported and
+/// every edge of the boundary and of the holes is kept. Where rings touch, the
+/// shared vertex has a [`PointId`] in each ring and a triangle may refer to it
+/// by either of them.
+///
+/// The polygon is first triangulated by [`earclip`] and then edges are flipped
+/// until the triangulation is constrained Delaunay. See [`flip_to_delaunay`].
+///
+/// # Time complexity
+/// $O(n^2)$
+///
+/// # Space complexity
+/// $O(n)$
+pub fn constrained_delaunay<T>(poly: &Polygon<T>) -> Triangulation
+where
+  T: PolygonScalar,
+{
+  flip_to_delaunay(poly, earclip(poly))
+}
+
+/// Turn a triangulation of a polygon into a constrained Delaunay triangulation
+/// by flipping edges. Edges on the boundary or on a hole are never flipped.
+///
+/// The triangles must be counter-clockwise and cover the polygon without
+/// overlapping, such as the output of [`earclip`] or
+/// [`earclip_hashed`](super::earclip::earclip_hashed).
+///
+/// Cocircular points are handled by symbolically perturbing the points so the
+/// result is unique for a given polygon.
+///
+/// # Time complexity
+/// $O(n^2)$ flips worst case.
+pub fn flip_to_delaunay<T, I>(poly: &Polygon<T>, triangles: I) -> Triangulation
+where
+  T: PolygonScalar,
+  I: IntoIterator<Item = (PointId, PointId, PointId)>,
+{
+  let mut trig =
+    Triangulation::from_triangles(triangles.into_iter().map(|(a, b, c)| [a, b, c]).collect());
+  let mut stack: Vec<usize> = (0..trig.len()).collect();
+  while let Some(t) = stack.pop() {
+    for i in 0..3 {
+      if let Some(n) = flip(poly, &mut trig, t, i) {
+        stack.push(t);
+        stack.push(n);
+        break;
+      }
+    }
+  }
+  trig
+}
+
+// Flip the edge opposite to vertex 'i' of triangle 't' if it isn't locally
+// Delaunay. Edges without a neighbour are constrained. Returns the neighbour
+// if the edge was flipped.
+//
+//      c              c
+//     /|\            / \
+//    a | d   =>     a---d
+//     \|/            \ /
+//      b              b
+fn flip<T>(poly: &Polygon<T>, trig: &mut Triangulation, t: usize, i: usize) -> Option<usize>
+where
+  T: PolygonScalar,
+{
+  let n = trig.neighbours[t][i]?;
+  let tri = trig.triangles[t];
+  let (a, b, c) = (tri[i], tri[(i + 1) % 3], tri[(i + 2) % 3]);
+  let other = trig.triangles[n];
+  let j = (0..3)
+    .find(|&j| other[(j + 1) % 3] == c && other[(j + 2) % 3] == b)
+    .expect("neighbours must share an edge");
+  let d = other[j];
+  let points = [a, b, c, d].map(|v| poly.point(v));
+  let ids = [a, b, c, d].map(|v| v.usize());
+  if Point::incircle_sos(points, ids) != Ordering::Greater {
+    return None;
+  }
+
+  let n_ca = trig.neighbours[t][(i + 1) % 3];
+  let n_ab = trig.neighbours[t][(i + 2) % 3];
+  let n_bd = trig.neighbours[n][(j + 1) % 3];
+  let n_dc = trig.neighbours[n][(j + 2) % 3];
+  trig.triangles[t] = [a, b, d];
+  trig.neighbours[t] = [n_bd, Some(n), n_ab];
+  trig.triangles[n] = [a, d, c];
+  trig.neighbours[n] = [n_dc, n_ca, Some(t)];
+  relink(trig, n_bd, n, t);
+  relink(trig, n_ca, t, n);
+  Some(n)
+}
+
+// Triangle 'at' used to be adjacent to 'from' and is now adjacent to 'to'.
+fn relink(trig: &mut Triangulation, at: Option<usize>, from: usize, to: usize) {
+  if let Some(at) = at {
+    for neighbour in trig.neighbours[at].iter_mut() {
+      if *neighbour == Some(from) {
+        *neighbour = Some(to);
+      }
+    }
+  }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  use crate::algorithms::triangulation::earclip::earclip_hashed;
+  use crate::data::{MultiPolygon, TriangleView};
+  use crate::testing::{frame, polygon_with_holes, polygon_with_touching_holes};
+  use crate::Orientation;
+
+  use num::ToPrimitive;
+  use num_bigint::BigInt;
+  use std::collections::BTreeMap;
+  use test_strategy::proptest;
+
+  fn check_cdt<T>(poly: &Polygon<T>, trig: &Triangulation)
+  where
+    T: PolygonScalar + Into<BigInt>,
+  {
+    let mut area_2x = BigInt::from(0);
+    // Directed edges by their coordinates, with the vertex opposite to them.
+    let mut edges = BTreeMap::new();
+    for (a, b, c) in trig.iter() {
+      let tri = [a, b, c].map(|v| poly.point(v));
+      let view = TriangleView::new_unchecked(tri);
+      assert_eq!(view.orientation(), Orientation::CounterClockWise);
+      area_2x += view.signed_area_2x::<BigInt>();
+      for i in 0..3 {
+        edges.insert((tri[(i + 1) % 3], tri[(i + 2) % 3]), tri[i]);
+      }
+    }
+    let multi = MultiPolygon::from(poly.clone());
+    assert_eq!(area_2x, multi.signed_area_2x::<BigInt>());
+    for (idx, (a, b, c)) in trig.iter().enumerate() {
+      let tri = [a, b, c].map(|v| poly.point(v));
+      for (i, neighbour) in trig.neighbours(idx).iter().enumerate() {
+        let (u, v) = (tri[(i + 1) % 3], tri[(i + 2) % 3]);
+        // Edges inside the polygon are linked and locally Delaunay.
+        if let Some(&d) = edges.get(&(v, u)) {
+          assert!(neighbour.is_some(), "unlinked edge inside the polygon");
+          assert_ne!(
+            Point::incircle(tri[0], tri[1], tri[2], d),
+            Ordering::Greater
+          );
+        }
+      }
+    }
+    for ring in &poly.rings {
+      for (k, &u) in ring.iter().enumerate() {
+        let v = ring[(k + 1) % ring.len()];
+        let edge = (poly.point(u), poly.point(v));
+        assert!(edges.contains_key(&edge), "missing polygon edge");
+      }
+    }
+  }
+
+  #[test]
+  fn square() {
+    let poly = Polygon::new(vec![
+      Point::new([0, 0]),
+      Point::new([1, 0]),
+      Point::new([1, 1]),
+      Point::new([0, 1]),
+    ])
+    .unwrap();
+    let trig = constrained_delaunay(&poly);
+    assert_eq!(trig.len(), 2);
+    check_cdt(&poly, &trig);
+  }
+
+  #[test]
+  fn skinny() {
+    // Ear clipping happily produces slivers here.
+    let mut pts: Vec<Point<i32>> = (0..10).map(|i| Point::new([i * 10, 0])).collect();
+    pts.extend((0..10).rev().map(|i| Point::new([i * 10, 10 + i % 2])));
+    let poly = Polygon::new(pts).unwrap();
+    let trig = constrained_delaunay(&poly);
+    assert_eq!(trig.len(), poly.iter().count() - 2);
+    check_cdt(&poly, &trig);
+  }
+
+  #[test]
+  fn with_hole() {
+    let poly = frame();
+    let trig = constrained_delaunay(&poly);
+    // n + 2h - 2 triangles.
+    assert_eq!(trig.len(), 8 + 2 - 2);
+    check_cdt(&poly, &trig);
+  }
+
+  #[test]
+  fn flip_hashed() {
+    let poly: Polygon<i64> = frame().cast();
+    let trig = flip_to_delaunay(&poly, earclip_hashed(&poly));
+    check_cdt(&poly, &trig);
+  }
+
+  #[proptest]
+  fn cdt_prop(poly: Polygon<i8>) {
+    let trig = constrained_delaunay(&poly);
+    assert_eq!(trig.len(), poly.iter().count() - 2);
+    check_cdt(&poly, &trig);
+  }
+
+  #[proptest]
+  fn cdt_holes_prop(#[any((3..20, ()))] a: Polygon<i8>, #[any((3..20, ()))] b: Polygon<i8>) {
+    let poly = polygon_with_holes(&[a, b]).map(|v| v.to_integer().to_i64().unwrap());
+    let trig = constrained_delaunay(&poly);
+    // n + 2h - 2 triangles.
+    assert_eq!(trig.len(), poly.iter().count() + 2 * 2 - 2);
+    check_cdt(&poly, &trig);
+  }
+
+  #[proptest]
+  fn cdt_touching_holes_prop(
+    #[any((3..20, ()))] a: Polygon<i8>,
+    #[any((3..20, ()))] b: Polygon<i8>,
+  ) {
+    // The boundary and both holes share vertices under distinct PointIds.
+    let poly = polygon_with_touching_holes(&[a, b]).map(|v| v.to_integer().to_i64().unwrap());
+    let trig = constrained_delaunay(&poly);
+    check_cdt(&poly, &trig);
+  }
+}
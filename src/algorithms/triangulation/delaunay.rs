@@ -0,0 +1,346 @@
+use crate::data::{Point, PointId};
+use crate::{Orientation, PolygonScalar};
+
+use super::Triangulation;
+
+use rand::rngs::SmallRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+// Vertex at infinity. Triangles that use it are ghost triangles and there is one
+// ghost triangle for every edge on the convex hull.
+const INF: usize = usize::MAX;
+
+/// Delaunay triangulation of a set of points. Points are inserted one at a
+/// time in random order.
+///
+/// Cocircular points are handled by symbolically perturbing the points on the
+/// lifting paraboloid so the result is always a valid Delaunay triangulation.
+/// Duplicate points are ignored and colinear points have no triangles.
+///
+/// # Time complexity
+/// $O(n^2)$ worst case. Inserting in random order is usually much faster.
+///
+/// # Space complexity
+/// $O(n)$
+pub fn delaunay<T>(points: &[Point<T>]) -> Triangulation
+where
+  T: PolygonScalar,
+{
+  let mut order: Vec<usize> = (0..points.len()).collect();
+  let mut rng = SmallRng::seed_from_u64(0xDEADBEEF);
+  order.shuffle(&mut rng);
+  match Mesh::new(points, &mut order) {
+    None => Triangulation::default(),
+    Some(mut mesh) => {
+      for vertex in order {
+        mesh.insert(vertex);
+      }
+      mesh.finish()
+    }
+  }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Triangle mesh with ghost triangles
+
+struct Mesh<'a, T> {
+  points: &'a [Point<T>],
+  // Vertices in counter-clockwise order.
+  triangles: Vec<[usize; 3]>,
+  // Triangle on the other side of the edge opposite to each vertex.
+  neighbours: Vec<[usize; 3]>,
+  // Recently created triangle without ghost vertices. Walks start here.
+  last: usize,
+}
+
+impl<'a, T> Mesh<'a, T>
+where
+  T: PolygonScalar,
+{
+  // Create a mesh from the first three points that aren't colinear. These
+  // points are removed from 'order'.
+  fn new(points: &'a [Point<T>], order: &mut Vec<usize>) -> Option<Mesh<'a, T>> {
+    let a = *order.first()?;
+    let b_pos = order.iter().position(|&i| points[i] != points[a])?;
+    let b = order[b_pos];
+    let c_pos = order
+      .iter()
+      .position(|&i| !Point::orient(&points[a], &points[b], &points[i]).is_colinear())?;
+    let c = order[c_pos];
+    order.remove(c_pos);
+    order.remove(b_pos);
+    order.remove(0);
+
+    let (b, c) = if Point::orient(&points[a], &points[b], &points[c]).is_ccw() {
+      (b, c)
+    } else {
+      (c, b)
+    };
+    Some(Mesh {
+      points,
+      triangles: vec![[a, b, c], [c, b, INF], [a, c, INF], [b, a, INF]],
+      neighbours: vec![[1, 2, 3], [3, 2, 0], [1, 3, 0], [2, 1, 0]],
+      last: 0,
+    })
+  }
+
+  fn point(&self, vertex: usize) -> &'a Point<T> {
+    &self.points[vertex]
+  }
+
+  fn is_ghost(&self, t: usize) -> bool {
+    self.triangles[t].contains(&INF)
+  }
+
+  // Walk towards 'q' until we reach a triangle that contains it or a ghost
+  // triangle whose hull edge can see it.
+  fn locate(&self, q: usize) -> usize {
+    let mut t = self.last;
+    'walk: loop {
+      if self.is_ghost(t) {
+        return t;
+      }
+      let tri = self.triangles[t];
+      for i in 0..3 {
+        let (u, v) = (tri[(i + 1) % 3], tri[(i + 2) % 3]);
+        if Point::orient(self.point(u), self.point(v), self.point(q)).is_cw() {
+          t = self.neighbours[t][i];
+          continue 'walk;
+        }
+      }
+      return t;
+    }
+  }
+
+  // Does the circumcircle of triangle 't' contain 'q'? The circumcircle of a
+  // ghost triangle is the open half-plane beyond its hull edge plus the open
+  // hull edge itself.
+  fn conflict(&self, t: usize, q: usize) -> bool {
+    let tri = self.triangles[t];
+    let pq = self.point(q);
+    match tri.iter().position(|&v| v == INF) {
+      Some(i) => {
+        let (u, v) = (self.point(tri[(i + 1) % 3]), self.point(tri[(i + 2) % 3]));
+        match Point::orient(u, v, pq) {
+          Orientation::CounterClockWise => true,
+          Orientation::ClockWise => false,
+          Orientation::CoLinear => u.min(v) < pq && pq < u.max(v),
+        }
+      }
+      None => self.incircle_sos(tri, q) == Ordering::Greater,
+    }
+  }
+
+  fn incircle_sos(&self, [a, b, c]: [usize; 3], d: usize) -> Ordering {
+    let points = [a, b, c, d].map(|v| self.point(v));
+    Point::incircle_sos(points, [a, b, c, d])
+  }
+
+  // Bowyer-Watson: Remove every triangle whose circumcircle contains 'q' and
+  // connect the edges of the resulting cavity to 'q'.
+  fn insert(&mut self, q: usize) {
+    let start = self.locate(q);
+    let pq = self.point(q);
+    if self.triangles[start]
+      .iter()
+      .any(|&v| v != INF && self.point(v) == pq)
+    {
+      // Duplicate point.
+      return;
+    }
+
+    let mut cavity = vec![start];
+    let mut visited = HashSet::new();
+    visited.insert(start);
+    // Edges of the cavity in counter-clockwise order together with the
+    // triangle on the other side.
+    let mut boundary: Vec<(usize, usize, usize)> = Vec::new();
+    let mut stack = vec![start];
+    while let Some(t) = stack.pop() {
+      for i in 0..3 {
+        let n = self.neighbours[t][i];
+        if visited.contains(&n) {
+          continue;
+        }
+        if self.conflict(n, q) {
+          visited.insert(n);
+          cavity.push(n);
+          stack.push(n);
+        } else {
+          let tri = self.triangles[t];
+          boundary.push((tri[(i + 1) % 3], tri[(i + 2) % 3], n));
+        }
+      }
+    }
+
+    // The cavity is star-shaped so there are two more new triangles than old
+    // triangles. Reuse the slots of the old triangles.
+    let mut slots = cavity;
+    while slots.len() < boundary.len() {
+      slots.push(self.triangles.len());
+      self.triangles.push([INF; 3]);
+      self.neighbours.push([INF; 3]);
+    }
+    let starting_at: HashMap<usize, usize> = boundary
+      .iter()
+      .zip(&slots)
+      .map(|(&(u, _, _), &t)| (u, t))
+      .collect();
+    let ending_at: HashMap<usize, usize> = boundary
+      .iter()
+      .zip(&slots)
+      .map(|(&(_, v, _), &t)| (v, t))
+      .collect();
+    for (&(u, v, outside), &t) in boundary.iter().zip(&slots) {
+      self.triangles[t] = [u, v, q];
+      self.neighbours[t] = [starting_at[&v], ending_at[&u], outside];
+      let tri = self.triangles[outside];
+      let j = (0..3)
+        .find(|&j| tri[(j + 1) % 3] == v && tri[(j + 2) % 3] == u)
+        .expect("cavity edge must be shared");
+      self.neighbours[outside][j] = t;
+      if u != INF && v != INF {
+        self.last = t;
+      }
+    }
+  }
+
+  // Drop the ghost triangles.
+  fn finish(self) -> Triangulation {
+    let mut index = vec![None; self.triangles.len()];
+    let mut triangles = Vec::new();
+    for (t, tri) in self.triangles.iter().enumerate() {
+      if !tri.contains(&INF) {
+        index[t] = Some(triangles.len());
+        triangles.push(tri.map(PointId));
+      }
+    }
+    let neighbours = self
+      .neighbours
+      .iter()
+      .zip(&index)
+      .filter(|(_, idx)| idx.is_some())
+      .map(|(ns, _)| ns.map(|n| index[n]))
+      .collect();
+    Triangulation {
+      triangles,
+      neighbours,
+    }
+  }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  use crate::algorithms::convex_hull;
+  use crate::data::TriangleView;
+
+  use num_bigint::BigInt;
+  use proptest::collection::vec;
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  fn check_delaunay<T>(points: &[Point<T>], trigs: &Triangulation)
+  where
+    T: PolygonScalar + Into<BigInt>,
+  {
+    let mut area_2x = BigInt::from(0);
+    let mut used = std::collections::BTreeSet::new();
+    for (idx, (a, b, c)) in trigs.iter().enumerate() {
+      let [pa, pb, pc] = [a, b, c].map(|v| &points[v.usize()]);
+      let trig = TriangleView::new_unchecked([pa, pb, pc]);
+      assert_eq!(trig.orientation(), Orientation::CounterClockWise);
+      area_2x += trig.signed_area_2x::<BigInt>();
+      for pt in points {
+        assert_ne!(Point::incircle(pa, pb, pc, pt), Ordering::Greater);
+      }
+      used.extend([pa, pb, pc]);
+      // Neighbours share an edge in opposite directions.
+      let tri = [a, b, c];
+      for (i, neighbour) in trigs.neighbours(idx).iter().enumerate() {
+        if let Some(n) = *neighbour {
+          let (u, v) = (tri[(i + 1) % 3], tri[(i + 2) % 3]);
+          let (x, y, z) = trigs.triangle(n);
+          let other = [x, y, z];
+          assert!((0..3).any(|j| other[j] == v && other[(j + 1) % 3] == u));
+        }
+      }
+    }
+    match convex_hull(points.to_vec()) {
+      Ok(hull) => {
+        assert_eq!(area_2x, hull.signed_area_2x::<BigInt>());
+        assert!(points.iter().all(|pt| used.contains(pt)));
+      }
+      Err(_) => assert!(trigs.is_empty()),
+    }
+  }
+
+  #[test]
+  fn degenerate() {
+    let empty: Vec<Point<i32>> = vec![];
+    assert!(delaunay(&empty).is_empty());
+    assert!(delaunay(&[Point::new([0, 0])]).is_empty());
+    assert!(delaunay(&[Point::new([0, 0]), Point::new([0, 0])]).is_empty());
+    let line: Vec<Point<i32>> = (0..10).map(|i| Point::new([i, 2 * i])).collect();
+    assert!(delaunay(&line).is_empty());
+  }
+
+  #[test]
+  fn square() {
+    let points = vec![
+      Point::new([0, 0]),
+      Point::new([1, 0]),
+      Point::new([1, 1]),
+      Point::new([0, 1]),
+    ];
+    let trigs = delaunay(&points);
+    assert_eq!(trigs.len(), 2);
+    check_delaunay(&points, &trigs);
+  }
+
+  #[test]
+  fn grid() {
+    let points: Vec<Point<i32>> = (0..25).map(|i| Point::new([i % 5, i / 5])).collect();
+    let trigs = delaunay(&points);
+    // 2n - 2 - h where h is the number of points on the convex hull.
+    assert_eq!(trigs.len(), 2 * 25 - 2 - 16);
+    check_delaunay(&points, &trigs);
+  }
+
+  #[test]
+  fn duplicates() {
+    let points = vec![
+      Point::new([0, 0]),
+      Point::new([4, 0]),
+      Point::new([0, 0]),
+      Point::new([0, 4]),
+      Point::new([4, 0]),
+      Point::new([1, 1]),
+    ];
+    let trigs = delaunay(&points);
+    assert_eq!(trigs.len(), 3);
+    check_delaunay(&points, &trigs);
+  }
+
+  #[proptest]
+  fn delaunay_prop(#[strategy(vec(any::<Point<i8>>(), 0..30))] points: Vec<Point<i8>>) {
+    check_delaunay(&points, &delaunay(&points));
+  }
+
+  #[proptest]
+  fn delaunay_degenerate_prop(
+    #[strategy(vec((-3i8..3, -3i8..3).prop_map(|(x, y)| Point::new([x, y])), 0..20))] points: Vec<
+      Point<i8>,
+    >,
+  ) {
+    check_delaunay(&points, &delaunay(&points));
+  }
+}
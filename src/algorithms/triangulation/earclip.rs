@@ -1,39 +1,148 @@
 use crate::algorithms::zhash::{ZHashBox, ZHashable};
-use crate::data::{Point, PointId, PointLocation, Polygon, TriangleView};
+use crate::data::{
+  EndPoint, LineSegmentView, Point, PointId, PointLocation, Polygon, TriangleView,
+};
+use crate::Intersects;
 use crate::Orientation;
 use crate::PolygonScalar;
 
+use std::borrow::Cow;
+
 // use rand::rngs::mock::StepRng;
 use rand::rngs::SmallRng;
 use rand::Rng;
 use rand::SeedableRng;
 
 /// $O(n^2)$ Polygon triangulation. Ears are selected in a pseudo-random manner.
+///
+/// Holes are connected to the boundary before triangulating. The triangles
+/// refer to the original vertices of both the boundary and the holes.
 pub fn earclip<T>(poly: &Polygon<T>) -> impl Iterator<Item = (PointId, PointId, PointId)> + '_
 where
   T: PolygonScalar,
 {
-  // FIXME: Support holes.
-  assert!(poly.rings.len() == 1);
   // let rng = StepRng::new(0, 0);
   let rng = SmallRng::seed_from_u64(0xDEADBEEF);
-  triangulate_list(&poly.points, &poly.rings[0], rng)
+  triangulate_ring(&poly.points, bridge_holes(poly), rng)
 }
 
 /// $O(n)$ Polygon triangulation. Ears are selected in a pseudo-random manner.
 ///
-/// $O(n^2)$ worst case complexity. Expected time is linear.
+/// $O(n^2)$ worst case complexity. Expected time is linear. Holes are handled
+/// the same way as in [`earclip`].
 pub fn earclip_hashed<T>(
   poly: &Polygon<T>,
 ) -> impl Iterator<Item = (PointId, PointId, PointId)> + '_
 where
   T: PolygonScalar + ZHashable,
 {
-  // FIXME: Support holes.
-  assert!(poly.rings.len() == 1);
   // let rng = StepRng::new(0, 0);
   let rng = SmallRng::seed_from_u64(0xDEADBEEF);
-  triangulate_list_hashed(&poly.points, &poly.rings[0], rng)
+  triangulate_ring_hashed(&poly.points, bridge_holes(poly), rng)
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Hole bridging
+
+// Connect every hole to the boundary with a pair of overlapping edges, turning
+// the polygon into a single weakly simple ring. The vertices at either end of a
+// bridge appear twice in the ring.
+//
+// Holes are processed from left to right and bridged from their left-most
+// vertex. Everything to the left of that vertex is already part of the ring so
+// a bridge always exists. Candidates are tried from nearest to farthest. A hole
+// that touches the ring at a vertex is spliced in at that vertex instead.
+//
+// O(h n^2) worst case but the nearest vertex is usually a valid bridge.
+fn bridge_holes<T>(poly: &Polygon<T>) -> Cow<'_, [PointId]>
+where
+  T: PolygonScalar,
+{
+  if poly.rings.len() == 1 {
+    return Cow::Borrowed(&poly.rings[0]);
+  }
+  let point = |pid: PointId| poly.point(pid);
+  let leftmost = |ring: &[PointId]| {
+    (0..ring.len())
+      .min_by(|&a, &b| point(ring[a]).cmp(point(ring[b])))
+      .unwrap()
+  };
+  let mut holes: Vec<&[PointId]> = poly.rings[1..].iter().map(Vec::as_slice).collect();
+  holes.sort_by(|a, b| point(a[leftmost(a)]).cmp(point(b[leftmost(b)])));
+
+  let mut ring: Vec<PointId> = poly.rings[0].clone();
+  for (nth, hole) in holes.iter().enumerate() {
+    let len = hole.len();
+    let touching = (0..len).find_map(|k| {
+      let h_next = point(hole[(k + 1) % len]);
+      (0..ring.len())
+        .find(|&i| {
+          let v_prev = point(ring[(i + ring.len() - 1) % ring.len()]);
+          let v_next = point(ring[(i + 1) % ring.len()]);
+          point(ring[i]) == point(hole[k]) && in_wedge(v_prev, point(ring[i]), v_next, h_next)
+        })
+        .map(|i| (k, i))
+    });
+    if let Some((k, i)) = touching {
+      let detour: Vec<PointId> = (1..=len).map(|j| hole[(k + j) % len]).collect();
+      ring.splice(i + 1..i + 1, detour);
+      continue;
+    }
+
+    let start = leftmost(hole);
+    let h = point(hole[start]);
+    let h_prev = point(hole[(start + len - 1) % len]);
+    let h_next = point(hole[(start + 1) % len]);
+
+    let is_bridge = |i: usize| {
+      let v = point(ring[i]);
+      let v_prev = point(ring[(i + ring.len() - 1) % ring.len()]);
+      let v_next = point(ring[(i + 1) % ring.len()]);
+      let bridge = LineSegmentView::new(EndPoint::Exclusive(h), EndPoint::Exclusive(v));
+      in_wedge(h_prev, h, h_next, v)
+        && in_wedge(v_prev, v, v_next, h)
+        && ring_edges(&ring)
+          .chain(holes[nth..].iter().flat_map(|hole| ring_edges(hole)))
+          .all(|(a, b)| {
+            let edge =
+              LineSegmentView::new(EndPoint::Inclusive(point(a)), EndPoint::Inclusive(point(b)));
+            bridge.intersect(edge).is_none()
+          })
+    };
+    let mut candidates: Vec<usize> = (0..ring.len()).collect();
+    candidates.sort_by(|&a, &b| h.cmp_distance_to(point(ring[a]), point(ring[b])));
+    let i = candidates
+      .into_iter()
+      .find(|&i| is_bridge(i))
+      .expect("holes must be inside the boundary");
+
+    let v = ring[i];
+    let detour: Vec<PointId> = (0..=len)
+      .map(|k| hole[(start + k) % len])
+      .chain(std::iter::once(v))
+      .collect();
+    ring.splice(i + 1..i + 1, detour);
+  }
+  Cow::Owned(ring)
+}
+
+fn ring_edges(ring: &[PointId]) -> impl Iterator<Item = (PointId, PointId)> + '_ {
+  (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()]))
+}
+
+// Is the direction from 'v' to 'x' strictly inside the interior angle at 'v'?
+// The interior is on the left of prev -> v -> next.
+fn in_wedge<T>(prev: &Point<T>, v: &Point<T>, next: &Point<T>, x: &Point<T>) -> bool
+where
+  T: PolygonScalar,
+{
+  let left_of_next = Point::orient(v, next, x).is_ccw();
+  let right_of_prev = Point::orient(v, prev, x).is_cw();
+  if Point::orient(prev, v, next).is_ccw() {
+    left_of_next && right_of_prev
+  } else {
+    left_of_next || right_of_prev
+  }
 }
 
 // triangulate: Vec<Point<T,2>> + Vec<PointId> -> Vec<(PointId,PointId,PointId)>
@@ -52,6 +161,18 @@ where
 pub fn triangulate_list<'a, T, R>(
   points: &'a [Point<T>],
   order: &'a [PointId],
+  rng: R,
+) -> impl Iterator<Item = (PointId, PointId, PointId)> + 'a
+where
+  T: PolygonScalar,
+  R: Rng + 'static,
+{
+  triangulate_ring(points, Cow::Borrowed(order), rng)
+}
+
+fn triangulate_ring<'a, T, R>(
+  points: &'a [Point<T>],
+  order: Cow<'a, [PointId]>,
   mut rng: R,
 ) -> impl Iterator<Item = (PointId, PointId, PointId)> + 'a
 where
@@ -59,8 +180,8 @@ where
   R: Rng + 'static,
 {
   let mut len = order.len();
-  let mut vertices = List::new(points, order);
   let mut possible_ears = EarStore::new(order.len());
+  let mut vertices = List::new(points, order);
   std::iter::from_fn(move || match len {
     0..=2 => None,
     _ => loop {
@@ -84,11 +205,17 @@ fn is_ear<T>(a: Cursor<'_, T>, b: Cursor<'_, T>, c: Cursor<'_, T>) -> bool
 where
   T: PolygonScalar,
 {
-  let trig = TriangleView::new_unchecked([a.point(), b.point(), c.point()]);
+  let corners = [a.point(), b.point(), c.point()];
+  let trig = TriangleView::new_unchecked(corners);
   if trig.orientation() == Orientation::CounterClockWise {
     let mut focus = c.next();
     while focus != a {
-      if trig.locate(focus.point()) != PointLocation::Outside {
+      if blocks_ear(
+        &corners,
+        focus.prev().point(),
+        focus.point(),
+        focus.next().point(),
+      ) {
         return false;
       }
       focus = focus.next();
@@ -99,12 +226,42 @@ where
   }
 }
 
+// Vertices at the ends of a bridge appear twice. A copy of a corner only blocks
+// the ear if one of its edges enters the triangle.
+fn blocks_ear<T>(corners: &[&Point<T>; 3], prev: &Point<T>, pt: &Point<T>, next: &Point<T>) -> bool
+where
+  T: PolygonScalar,
+{
+  if TriangleView::new_unchecked(*corners).locate(pt) == PointLocation::Outside {
+    return false;
+  }
+  match corners.iter().position(|&corner| corner == pt) {
+    None => true,
+    Some(i) => {
+      let (corner_prev, corner_next) = (corners[(i + 2) % 3], corners[(i + 1) % 3]);
+      in_wedge(corner_prev, pt, corner_next, prev) || in_wedge(corner_prev, pt, corner_next, next)
+    }
+  }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 // Z-order hash ear clipping
 
 pub fn triangulate_list_hashed<'a, T, R>(
   points: &'a [Point<T>],
   order: &'a [PointId],
+  rng: R,
+) -> impl Iterator<Item = (PointId, PointId, PointId)> + 'a
+where
+  T: PolygonScalar + ZHashable,
+  R: Rng + 'static,
+{
+  triangulate_ring_hashed(points, Cow::Borrowed(order), rng)
+}
+
+fn triangulate_ring_hashed<'a, T, R>(
+  points: &'a [Point<T>],
+  order: Cow<'a, [PointId]>,
   mut rng: R,
 ) -> impl Iterator<Item = (PointId, PointId, PointId)> + 'a
 where
@@ -112,16 +269,16 @@ where
   R: Rng + 'static,
 {
   let mut len = order.len();
-  let mut vertices = List::new(points, order);
   let mut possible_ears = EarStore::new(order.len());
 
-  let zbox = zbox_slice(points, order);
+  let zbox = zbox_slice(points, &order);
   let key = ZHashable::zhash_key(zbox);
   let zhashes: Vec<u64> = order
     .iter()
     .map(|&pid| ZHashable::zhash_fn(key, &points[pid.usize()]))
     .collect();
-  let mut zorder = List::new_sorted(points, order, zhashes);
+  let mut zorder = List::new_sorted(points, order.clone(), zhashes);
+  let mut vertices = List::new(points, order);
 
   std::iter::from_fn(move || match len {
     0..=2 => None,
@@ -131,6 +288,7 @@ where
       let next = vertices.next(focus);
       if is_ear_hashed(
         key,
+        &vertices,
         zorder.cursor(prev),
         zorder.cursor(focus),
         zorder.cursor(next),
@@ -140,7 +298,11 @@ where
         vertices.delete(focus);
         zorder.delete(focus);
         len -= 1;
-        let out = (order[prev], order[focus], order[next]);
+        let out = (
+          vertices.point_id(prev),
+          vertices.point_id(focus),
+          vertices.point_id(next),
+        );
         return Some(out);
       }
     },
@@ -149,6 +311,7 @@ where
 
 fn is_ear_hashed<T: ZHashable>(
   key: <T as ZHashable>::ZHashKey,
+  vertices: &List<'_, T>,
   a: Cursor<'_, T>,
   b: Cursor<'_, T>,
   c: Cursor<'_, T>,
@@ -156,7 +319,8 @@ fn is_ear_hashed<T: ZHashable>(
 where
   T: PolygonScalar,
 {
-  let trig = TriangleView::new_unchecked([a.point(), b.point(), c.point()]);
+  let corners = [a.point(), b.point(), c.point()];
+  let trig = TriangleView::new_unchecked(corners);
   if trig.orientation() == Orientation::CounterClockWise {
     // Points inside the triangle are guaranteed to have a zhash
     // between the hashes of the bounding box.
@@ -171,10 +335,16 @@ where
 
     let cond = |cursor: Cursor<'_, T>| cursor.valid() && cursor.between(min_hash, max_hash);
     let check = |cursor: Cursor<'_, T>| {
+      let vertex = vertices.cursor(cursor.position);
       cursor != a
         && cursor != b
         && cursor != c
-        && trig.locate(cursor.point()) != PointLocation::Outside
+        && blocks_ear(
+          &corners,
+          vertex.prev().point(),
+          vertex.point(),
+          vertex.next().point(),
+        )
     };
 
     while cond(up_focus) && cond(down_focus) {
@@ -252,6 +422,8 @@ where
 mod tests {
   use super::*;
   use crate::data::*;
+  use crate::testing::polygon_with_holes;
+  use num::ToPrimitive;
   use num_bigint::BigInt;
   use rand::rngs::SmallRng;
   use rand::SeedableRng;
@@ -341,6 +513,108 @@ mod tests {
       triangulate_list_hashed(&poly.points, &poly.rings[0], rng).collect();
     prop_assert_eq!(not_hashed, hashed);
   }
+
+  // Scale 'polygon_with_holes' by three so the centroids of triangles are
+  // integers.
+  fn with_holes(holes: &[Polygon<i8>]) -> Polygon<i64> {
+    polygon_with_holes(holes).map(|v| v.to_integer().to_i64().unwrap() * 3)
+  }
+
+  fn check_triangulation(poly: &Polygon<i64>, trigs: &[(PointId, PointId, PointId)]) {
+    let multi = MultiPolygon::from(poly.clone());
+    let mut area_2x = BigInt::from(0);
+    for &(a, b, c) in trigs {
+      let [a, b, c] = [poly.point(a), poly.point(b), poly.point(c)];
+      let trig = TriangleView::new_unchecked([a, b, c]);
+      assert_eq!(trig.orientation(), Orientation::CounterClockWise);
+      let centroid = Point::new([0, 1].map(|d| (a.array[d] + b.array[d] + c.array[d]) / 3));
+      assert_eq!(multi.locate(&centroid), PointLocation::Inside);
+      area_2x += trig.signed_area_2x::<BigInt>();
+    }
+    assert_eq!(area_2x, multi.signed_area_2x::<BigInt>());
+    // A hole that shares a vertex with another ring is joined to it without a
+    // bridge, so it doesn't add the two bridge triangles.
+    let holes = poly.iter_holes().count();
+    let distinct: std::collections::BTreeSet<&Point<i64>> = poly.points.iter().collect();
+    let shared = poly.points.len() - distinct.len();
+    assert_eq!(trigs.len(), poly.points.len() + 2 * (holes - shared) - 2);
+  }
+
+  #[test]
+  fn square_hole() {
+    let square = Polygon::new(vec![
+      Point::new([0, 0]),
+      Point::new([1, 0]),
+      Point::new([1, 1]),
+      Point::new([0, 1]),
+    ])
+    .unwrap();
+    let poly = with_holes(&[square]);
+    let trigs: Vec<(PointId, PointId, PointId)> = earclip(&poly).collect();
+    assert_eq!(trigs.len(), 8);
+    check_triangulation(&poly, &trigs);
+    assert_eq!(poly.triangulate().count(), 8);
+  }
+
+  #[test]
+  fn shared_bridge_vertex() {
+    // Both holes are closest to the same boundary vertex.
+    let poly = Polygon::new_with_holes(
+      vec![
+        Point::new([0, 0]),
+        Point::new([30, 0]),
+        Point::new([30, 30]),
+        Point::new([0, 30]),
+      ],
+      vec![
+        vec![Point::new([3, 6]), Point::new([6, 6]), Point::new([6, 3])],
+        vec![Point::new([6, 9]), Point::new([9, 12]), Point::new([9, 9])],
+        vec![
+          Point::new([12, 3]),
+          Point::new([12, 6]),
+          Point::new([15, 3]),
+        ],
+      ],
+    )
+    .unwrap();
+    let trigs: Vec<(PointId, PointId, PointId)> = earclip(&poly).collect();
+    check_triangulation(&poly, &trigs);
+  }
+
+  #[test]
+  fn touching_holes() {
+    // One hole touches the boundary and the other touches the first hole. All
+    // coordinates are multiples of 3 so the centroids are exact.
+    let poly = Polygon::new_with_holes(
+      vec![
+        Point::new([0, 0]),
+        Point::new([30, 0]),
+        Point::new([30, 30]),
+        Point::new([0, 30]),
+      ],
+      vec![
+        vec![
+          Point::new([12, 6]),
+          Point::new([18, 12]),
+          Point::new([21, 9]),
+        ],
+        vec![Point::new([0, 0]), Point::new([6, 12]), Point::new([12, 6])],
+      ],
+    )
+    .unwrap();
+    let trigs: Vec<(PointId, PointId, PointId)> = earclip(&poly).collect();
+    assert_eq!(trigs.len(), 8);
+    check_triangulation(&poly, &trigs);
+  }
+
+  #[proptest]
+  fn holes_prop(a: Polygon<i8>, b: Polygon<i8>) {
+    let poly = with_holes(&[a, b]);
+    let trigs: Vec<(PointId, PointId, PointId)> = earclip(&poly).collect();
+    check_triangulation(&poly, &trigs);
+    let hashed: Vec<(PointId, PointId, PointId)> = earclip_hashed(&poly).collect();
+    prop_assert_eq!(trigs, hashed);
+  }
 }
 
 ///////////////////////////////////////////////////////////////////////////////
@@ -404,14 +678,14 @@ impl<'a, T> Cursor<'a, T> {
 
 struct List<'a, T> {
   points: &'a [Point<T>],
-  order: &'a [PointId],
+  order: Cow<'a, [PointId]>,
   hashes: Vec<u64>,
   prev: Vec<usize>,
   next: Vec<usize>,
 }
 
 impl<'a, T> List<'a, T> {
-  fn new(points: &'a [Point<T>], order: &'a [PointId]) -> List<'a, T> {
+  fn new(points: &'a [Point<T>], order: Cow<'a, [PointId]>) -> List<'a, T> {
     let size = order.len();
     let mut prev = Vec::with_capacity(size);
     let mut next = Vec::with_capacity(size);
@@ -470,7 +744,7 @@ impl<'a, T> List<'a, T> {
 }
 
 impl<'a, T> List<'a, T> {
-  fn new_sorted(points: &'a [Point<T>], order: &'a [PointId], keys: Vec<u64>) -> List<'a, T> {
+  fn new_sorted(points: &'a [Point<T>], order: Cow<'a, [PointId]>, keys: Vec<u64>) -> List<'a, T> {
     let size = keys.len();
     let mut v: Vec<usize> = (0..size).collect();
     v.sort_unstable_by_key(|&idx| keys[idx]);
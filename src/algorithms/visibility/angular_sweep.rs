@@ -0,0 +1,297 @@
+use std::cmp::Ordering;
+
+use super::naive::{get_intersection_colinear, iter_vertices, sort_around};
+use crate::data::{Cursor, DirectedEdge, HalfLineSoS, IHalfLineLineSegmentSoS, Point, Polygon};
+use crate::utils::exact::{line_intersection, to_rational};
+use crate::utils::Treap;
+use crate::{Intersects, Orientation, PolygonScalar};
+
+use num::BigRational;
+
+/// Visibility polygon of a point inside a polygon with or without holes.
+///
+/// The vertices are visited in counter-clockwise order around the point while
+/// the edges that cross the current direction are kept sorted by their distance
+/// to the point. The nearest edges on either side of each vertex are hit by the
+/// same SoS rays as in [`naive::get_visibility_polygon`](super::naive::get_visibility_polygon).
+/// Only the hits are new points and they are computed exactly, so the result
+/// has the same points as the naive algorithm gives for the input converted
+/// with [`PolygonScalar::to_rational`].
+///
+/// # Time complexity
+/// $O(n \log n)$
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::visibility::angular_sweep::get_visibility_polygon;
+/// # use rgeometry::data::{Point, Polygon};
+/// # use num::BigRational;
+/// let polygon = Polygon::new_with_holes(
+///   vec![
+///     Point::new([0, 0]),
+///     Point::new([8, 0]),
+///     Point::new([8, 8]),
+///     Point::new([0, 8]),
+///   ],
+///   vec![vec![
+///     Point::new([3, 5]),
+///     Point::new([3, 6]),
+///     Point::new([5, 6]),
+///     Point::new([5, 5]),
+///   ]],
+/// )
+/// .unwrap();
+/// let visible = get_visibility_polygon(&Point::new([4, 2]), &polygon).unwrap();
+/// let expected = Polygon::new(vec![
+///   Point::new([8, 8]),
+///   Point::new([6, 8]),
+///   Point::new([5, 5]),
+///   Point::new([3, 5]),
+///   Point::new([2, 8]),
+///   Point::new([0, 8]),
+///   Point::new([0, 0]),
+///   Point::new([8, 0]),
+/// ])
+/// .unwrap()
+/// .map(|v| BigRational::from_integer(v.into()));
+/// assert!(visible.equals(&expected));
+/// ```
+pub fn get_visibility_polygon<T>(
+  point: &Point<T>,
+  polygon: &Polygon<T>,
+) -> Option<Polygon<BigRational>>
+where
+  T: PolygonScalar,
+{
+  let mut vertices: Vec<Cursor<'_, T>> = iter_vertices(polygon).collect();
+  vertices.sort_by(|a, b| sort_around(point, a, b));
+
+  // Vertices in the same direction from 'point' share an angle index. Only the
+  // nearest vertex in each direction is used for the rays.
+  let mut angles = vec![0; polygon.points.len()];
+  let mut nearest: Vec<Cursor<'_, T>> = Vec::new();
+  for &vertex in &vertices {
+    match nearest.last() {
+      Some(prev) if point.ccw_cmp_around(prev, &vertex).is_eq() => (),
+      _ => nearest.push(vertex),
+    }
+    angles[vertex.point_id().usize()] = nearest.len() - 1;
+  }
+
+  // Edges that aren't colinear with 'point', with their endpoints in
+  // counter-clockwise order around it.
+  let mut edges: Vec<DirectedEdge<'_, T>> = Vec::new();
+  let mut starting = vec![Vec::new(); nearest.len()];
+  let mut ending = vec![Vec::new(); nearest.len()];
+  let mut status: Treap<usize> = Treap::new();
+  for cursor in iter_vertices(polygon) {
+    let (src, dst) = match Point::orient(point, cursor.point(), cursor.next().point()) {
+      Orientation::CounterClockWise => (cursor, cursor.next()),
+      Orientation::ClockWise => (cursor.next(), cursor),
+      Orientation::CoLinear => continue,
+    };
+    let id = edges.len();
+    edges.push(DirectedEdge {
+      src: src.point(),
+      dst: dst.point(),
+    });
+    let (first, last) = (
+      angles[src.point_id().usize()],
+      angles[dst.point_id().usize()],
+    );
+    starting[first].push(id);
+    ending[last].push(id);
+    // Edges that wrap around the starting direction are crossed before the
+    // first vertex is reached.
+    if first > last {
+      insert(&mut status, &edges, id);
+    }
+  }
+
+  let mut polygon_points = Vec::new();
+  for (angle, vertex) in nearest.into_iter().enumerate() {
+    // Nearest edges just clockwise and just counter-clockwise of the vertex.
+    let right = edges[status.min()?];
+    for &id in &ending[angle] {
+      let removed = status.remove_range(
+        |&e| is_closer(&edges[e], &edges[id]),
+        |&e| is_closer(&edges[id], &edges[e]),
+      );
+      debug_assert_eq!(removed, vec![id]);
+    }
+    for &id in &starting[angle] {
+      insert(&mut status, &edges, id);
+    }
+    let left = edges[status.min()?];
+
+    let (origin, through) = (to_rational(point), to_rational(vertex.point()));
+    for edge in [right, left] {
+      let intersection = get_hit(point, vertex.point(), edge);
+      if origin.cmp_distance_to(&intersection, &through) != Ordering::Less {
+        polygon_points.push(intersection);
+      }
+    }
+  }
+  polygon_points.dedup();
+
+  // The hits are sorted around the point by construction. Validating them
+  // would take O(n log n) time with a larger constant than the sweep.
+  Some(Polygon::new_unchecked(polygon_points))
+}
+
+pub(super) fn insert<T>(status: &mut Treap<usize>, edges: &[DirectedEdge<'_, T>], id: usize)
+where
+  T: PolygonScalar,
+{
+  status.insert_range(|&e| is_closer(&edges[e], &edges[id]), &[id]);
+}
+
+// Point where the ray from 'origin' through 'through' hits 'edge', which blocks
+// it. A hit in the middle of the edge is a new point which overflows
+// fixed-width scalars.
+fn get_hit<T>(
+  origin: &Point<T>,
+  through: &Point<T>,
+  edge: DirectedEdge<'_, T>,
+) -> Point<BigRational>
+where
+  T: PolygonScalar,
+{
+  let ray_sos = HalfLineSoS::new_through(origin, through);
+  match ray_sos.intersect(edge) {
+    Some(IHalfLineLineSegmentSoS::Crossing(Orientation::CoLinear)) => line_intersection(
+      &to_rational(origin),
+      &to_rational(through),
+      &to_rational(edge.src),
+      &to_rational(edge.dst),
+    ),
+    _ => to_rational(&get_intersection_colinear(ray_sos, edge)),
+  }
+}
+
+// Whether 'a' is closer to the point than 'b' in the directions where they
+// overlap. The endpoints of both edges are counter-clockwise around the point
+// and the edges don't cross.
+pub(super) fn is_closer<T>(a: &DirectedEdge<'_, T>, b: &DirectedEdge<'_, T>) -> bool
+where
+  T: PolygonScalar,
+{
+  // The point is on the counter-clockwise side of both edges.
+  let near_a = |pt: &Point<T>| !Point::orient(a.src, a.dst, pt).is_cw();
+  let far_a = |pt: &Point<T>| !Point::orient(a.src, a.dst, pt).is_ccw();
+  if (a.src == b.src && a.dst == b.dst) || (near_a(b.src) && near_a(b.dst)) {
+    false
+  } else if far_a(b.src) && far_a(b.dst) {
+    true
+  } else {
+    // 'b' crosses the line through 'a' so 'a' is on one side of 'b'.
+    let near_b = |pt: &Point<T>| !Point::orient(b.src, b.dst, pt).is_cw();
+    near_b(a.src) && near_b(a.dst)
+  }
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::algorithms::visibility::naive;
+  use crate::data::{MultiPolygon, PointLocation};
+  use crate::testing::{polygon_with_holes, rational};
+
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  fn check_naive(point: &Point<BigRational>, polygon: &Polygon<BigRational>) {
+    // The naive algorithm only handles points strictly inside the polygon.
+    if MultiPolygon::from(polygon.clone()).locate(point) != PointLocation::Inside {
+      return;
+    }
+    let expected = naive::get_visibility_polygon(point, polygon);
+    let actual = get_visibility_polygon(point, polygon);
+    assert_eq!(
+      expected.map(|poly| poly.points),
+      actual.map(|poly| poly.points)
+    );
+  }
+
+  #[test]
+  fn square_hole() {
+    let polygon = polygon_with_holes(&[Polygon::new(vec![
+      Point::new([0, 0]),
+      Point::new([1, 0]),
+      Point::new([1, 1]),
+      Point::new([0, 1]),
+    ])
+    .unwrap()]);
+    for x in 0..=3 {
+      for y in 0..=3 {
+        let point = Point::new([x, y].map(|v| BigRational::from_integer(v.into())));
+        check_naive(&point, &polygon);
+      }
+    }
+  }
+
+  #[proptest]
+  fn matches_naive(polygon: Polygon<i8>, point: Point<i8>) {
+    check_naive(
+      &point.map(|v| BigRational::from_integer(v.into())),
+      &rational(polygon),
+    );
+  }
+
+  #[proptest]
+  fn matches_naive_holes(
+    #[any((3..20, ()))] a: Polygon<i8>,
+    #[any((3..20, ()))] b: Polygon<i8>,
+    #[strategy(0i16..600)] x: i16,
+    #[strategy(0i16..300)] y: i16,
+  ) {
+    let polygon = polygon_with_holes(&[a, b]);
+    let point = Point::new([x, y].map(|v| BigRational::new(v.into(), 2.into())));
+    check_naive(&point, &polygon);
+  }
+
+  // Fixed-width scalars give the exact result.
+  fn check_naive_fixed<T: PolygonScalar>(point: &Point<T>, polygon: &Polygon<T>) {
+    let expected = naive::get_visibility_polygon(&to_rational(point), &rational(polygon.clone()));
+    let actual = get_visibility_polygon(point, polygon);
+    assert_eq!(
+      expected.map(|poly| poly.points),
+      actual.map(|poly| poly.points)
+    );
+  }
+
+  #[test]
+  fn large_square_i8() {
+    let polygon = Polygon::new(vec![
+      Point::new([-128i8, -128]),
+      Point::new([127, -128]),
+      Point::new([127, 127]),
+      Point::new([-128, 127]),
+    ])
+    .unwrap();
+    let point = Point::new([-100i8, 90]);
+    check_naive_fixed(&point, &polygon);
+  }
+
+  #[proptest]
+  fn matches_naive_i8(#[any((3..20, ()))] polygon: Polygon<i8>, point: Point<i8>) {
+    prop_assume!(polygon.locate(&point) == PointLocation::Inside);
+    check_naive_fixed(&point, &polygon);
+  }
+
+  // The holes are placed side by side so the coordinates don't fit in i8.
+  #[proptest]
+  fn matches_naive_holes_i16(
+    #[any((3..20, ()))] a: Polygon<i8>,
+    #[any((3..20, ()))] b: Polygon<i8>,
+    #[strategy(0i16..520)] x: i16,
+    #[strategy(0i16..260)] y: i16,
+  ) {
+    let polygon = polygon_with_holes(&[a, b]).map(|v| i16::from_rational(&v));
+    let point = Point::new([x, y]);
+    prop_assume!(MultiPolygon::from(polygon.clone()).locate(&point) == PointLocation::Inside);
+    check_naive_fixed(&point, &polygon);
+  }
+}
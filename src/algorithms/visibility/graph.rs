@@ -0,0 +1,329 @@
+use super::angular_sweep::{insert, is_closer};
+use super::naive::{iter_vertices, sort_around};
+use crate::data::{Cursor, DirectedEdge, MultiPolygon, Point, PointId, PointLocation, Polygon};
+use crate::utils::Treap;
+use crate::{Orientation, PolygonScalar, TotalOrd};
+
+/// Visibility graph of a polygon with or without holes.
+///
+/// Two vertices see each other if the line segment between them lies inside
+/// the polygon. The segment may touch the boundary, run along edges and pass
+/// through other vertices, so every edge of the polygon is also an edge of the
+/// graph. Obstacles can be modelled as holes inside a bounding polygon.
+///
+/// The edges are returned as sorted pairs `(a, b)` with `a < b`.
+///
+/// # Time complexity
+/// $O(n^2 \log n)$
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::visibility::graph::visibility_graph;
+/// # use rgeometry::data::{Point, Polygon};
+/// let polygon = Polygon::new_with_holes(
+///   vec![
+///     Point::new([0, 0]),
+///     Point::new([4, 0]),
+///     Point::new([4, 4]),
+///     Point::new([0, 4]),
+///   ],
+///   vec![vec![
+///     Point::new([1, 1]),
+///     Point::new([1, 3]),
+///     Point::new([3, 3]),
+///     Point::new([3, 1]),
+///   ]],
+/// )
+/// .unwrap();
+/// let graph: Vec<(usize, usize)> = visibility_graph(&polygon)
+///   .into_iter()
+///   .map(|(a, b)| (a.usize(), b.usize()))
+///   .collect();
+/// // Corner of the boundary and corner of the hole.
+/// assert!(graph.contains(&(0, 4)));
+/// // Edge of the boundary.
+/// assert!(graph.contains(&(0, 1)));
+/// // Opposite corners of the boundary are blocked by the hole.
+/// assert!(!graph.contains(&(0, 2)));
+/// // Opposite corners of the hole only see each other through the hole.
+/// assert!(!graph.contains(&(4, 6)));
+/// ```
+pub fn visibility_graph<T>(polygon: &Polygon<T>) -> Vec<(PointId, PointId)>
+where
+  T: PolygonScalar,
+{
+  let mut graph = Vec::new();
+  for origin in iter_vertices(polygon) {
+    for vertex in visible_vertices(origin, polygon) {
+      if origin.point_id() < vertex {
+        graph.push((origin.point_id(), vertex));
+      }
+    }
+  }
+  graph.sort_unstable();
+  graph
+}
+
+// Vertices seen by 'origin'. The other vertices are visited in
+// counter-clockwise order around 'origin' while the edges that cross the
+// current direction are kept sorted by their distance to it.
+fn visible_vertices<T>(origin: Cursor<'_, T>, polygon: &Polygon<T>) -> Vec<PointId>
+where
+  T: PolygonScalar,
+{
+  let point = origin.point();
+  let mut vertices: Vec<Cursor<'_, T>> = iter_vertices(polygon)
+    .filter(|vertex| vertex.point_id() != origin.point_id())
+    .collect();
+  vertices.sort_by(|a, b| sort_around(point, a, b));
+
+  // Vertices in the same direction from 'origin' share an angle index and are
+  // sorted by distance.
+  let mut angles = vec![0; polygon.points.len()];
+  let mut groups: Vec<Vec<Cursor<'_, T>>> = Vec::new();
+  for &vertex in &vertices {
+    match groups.last_mut() {
+      Some(group) if point.ccw_cmp_around(&group[0], &vertex).is_eq() => group.push(vertex),
+      _ => groups.push(vec![vertex]),
+    }
+    angles[vertex.point_id().usize()] = groups.len() - 1;
+  }
+
+  // Edges that neither touch 'origin' nor are colinear with it, with their
+  // endpoints in counter-clockwise order around it. Such edges can only block
+  // the view by crossing a direction between their endpoints.
+  let mut edges: Vec<DirectedEdge<'_, T>> = Vec::new();
+  let mut starting = vec![Vec::new(); groups.len()];
+  let mut ending = vec![Vec::new(); groups.len()];
+  let mut status: Treap<usize> = Treap::new();
+  for cursor in iter_vertices(polygon) {
+    let next = cursor.next();
+    if cursor.point_id() == origin.point_id() || next.point_id() == origin.point_id() {
+      continue;
+    }
+    let (src, dst) = match Point::orient(point, cursor.point(), next.point()) {
+      Orientation::CounterClockWise => (cursor, next),
+      Orientation::ClockWise => (next, cursor),
+      Orientation::CoLinear => continue,
+    };
+    let id = edges.len();
+    edges.push(DirectedEdge {
+      src: src.point(),
+      dst: dst.point(),
+    });
+    let (first, last) = (
+      angles[src.point_id().usize()],
+      angles[dst.point_id().usize()],
+    );
+    starting[first].push(id);
+    ending[last].push(id);
+    if first > last {
+      insert(&mut status, &edges, id);
+    }
+  }
+
+  let mut visible = Vec::new();
+  for (angle, group) in groups.iter().enumerate() {
+    for &id in &ending[angle] {
+      status.remove_range(
+        |&e| is_closer(&edges[e], &edges[id]),
+        |&e| is_closer(&edges[id], &edges[e]),
+      );
+    }
+    // Walk outwards until the view is blocked by an edge or leaves the polygon
+    // at one of the vertices.
+    let nearest = status.min().map(|id| edges[id]);
+    let mut prev = origin;
+    for &vertex in group {
+      let blocked = match nearest {
+        Some(edge) => !Point::orient(edge.src, edge.dst, vertex.point()).is_ccw(),
+        None => false,
+      };
+      if blocked || !in_cone(prev, vertex.point()) {
+        break;
+      }
+      visible.push(vertex.point_id());
+      prev = vertex;
+    }
+    for &id in &starting[angle] {
+      insert(&mut status, &edges, id);
+    }
+  }
+  visible
+}
+
+// Whether the segment from 'vertex' towards 'pt' starts inside the polygon or
+// along one of the edges at 'vertex'.
+pub(crate) fn in_cone<T>(vertex: Cursor<'_, T>, pt: &Point<T>) -> bool
+where
+  T: PolygonScalar,
+{
+  let left_of_next = !Point::orient(vertex.point(), vertex.next().point(), pt).is_cw();
+  let right_of_prev = !Point::orient(vertex.point(), vertex.prev().point(), pt).is_ccw();
+  if vertex.orientation().is_ccw() {
+    left_of_next && right_of_prev
+  } else {
+    left_of_next || right_of_prev
+  }
+}
+
+// Directions in which a segment can leave a point in the polygon.
+pub(crate) enum Cone<'a, T: TotalOrd> {
+  // The point is in the interior of the polygon.
+  Any,
+  // The point is in the interior of an edge.
+  Edge(DirectedEdge<'a, T>),
+  // The point is a vertex.
+  Vertex(Cursor<'a, T>),
+}
+
+impl<'a, T> Cone<'a, T>
+where
+  T: PolygonScalar,
+{
+  // None if the point is outside the polygon.
+  pub(crate) fn new(polygon: &'a Polygon<T>, point: &Point<T>) -> Option<Cone<'a, T>> {
+    for cursor in iter_vertices(polygon) {
+      let edge = DirectedEdge {
+        src: cursor.point(),
+        dst: cursor.next().point(),
+      };
+      if cursor.point() == point {
+        return Some(Cone::Vertex(cursor));
+      } else if edge.contains(point) {
+        return Some(Cone::Edge(edge));
+      }
+    }
+    match MultiPolygon::from(polygon.clone()).locate(point) {
+      PointLocation::Outside => None,
+      _ => Some(Cone::Any),
+    }
+  }
+
+  fn contains(&self, pt: &Point<T>) -> bool {
+    match self {
+      Cone::Any => true,
+      Cone::Edge(edge) => !Point::orient(edge.src, edge.dst, pt).is_cw(),
+      Cone::Vertex(cursor) => in_cone(*cursor, pt),
+    }
+  }
+
+  // Whether the segment from 'origin' to 'pt' is inside the polygon. The
+  // segment must start inside the cone, must not cross any edges and must stay
+  // inside the polygon at every vertex it passes through.
+  pub(crate) fn sees(&self, polygon: &Polygon<T>, origin: &Point<T>, pt: &Point<T>) -> bool {
+    self.contains(pt)
+      && iter_vertices(polygon).all(|cursor| {
+        let (src, dst) = (cursor.point(), cursor.next().point());
+        let on_segment = Point::orient(origin, pt, src).is_colinear()
+          && origin.cmp_distance_to(src, pt).is_lt()
+          && pt.cmp_distance_to(src, origin).is_lt();
+        (!on_segment || in_cone(cursor, pt)) && !crosses(origin, pt, src, dst)
+      })
+  }
+}
+
+// Whether the segments 'ab' and 'cd' cross at a point that is interior to both.
+pub(crate) fn crosses<T>(a: &Point<T>, b: &Point<T>, c: &Point<T>, d: &Point<T>) -> bool
+where
+  T: PolygonScalar,
+{
+  let opposite = |x: Orientation, y: Orientation| !x.is_colinear() && !y.is_colinear() && x != y;
+  opposite(Point::orient(a, b, c), Point::orient(a, b, d))
+    && opposite(Point::orient(c, d, a), Point::orient(c, d, b))
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::testing::polygon_with_holes;
+
+  use num::{BigRational, ToPrimitive};
+  use std::cmp::Ordering;
+  use test_strategy::proptest;
+
+  // A segment that properly crosses an edge leaves the polygon. Otherwise, cut
+  // it at the vertices on it and check that the pieces are inside the polygon.
+  // 'doubled' is the polygon scaled by two so the midpoints have integer
+  // coordinates.
+  fn sees(
+    polygon: &Polygon<i64>,
+    doubled: &MultiPolygon<i64>,
+    a: &Point<i64>,
+    b: &Point<i64>,
+  ) -> bool {
+    let crosses = |p: &Point<i64>, q: &Point<i64>, r: &Point<i64>, s: &Point<i64>| {
+      let (o1, o2) = (Point::orient(p, q, r), Point::orient(p, q, s));
+      !o1.is_colinear() && !o2.is_colinear() && o1 != o2
+    };
+    let mut cuts = vec![a, b];
+    for cursor in iter_vertices(polygon) {
+      let (src, dst) = (cursor.point(), cursor.next().point());
+      if crosses(a, b, src, dst) && crosses(src, dst, a, b) {
+        return false;
+      }
+      if Point::orient(a, b, src).is_colinear()
+        && a.cmp_distance_to(src, b) == Ordering::Less
+        && b.cmp_distance_to(src, a) == Ordering::Less
+      {
+        cuts.push(src);
+      }
+    }
+    cuts.sort_by(|p, q| a.cmp_distance_to(p, q));
+    cuts.windows(2).all(|pair| {
+      let mid = Point::new([
+        pair[0].x_coord() + pair[1].x_coord(),
+        pair[0].y_coord() + pair[1].y_coord(),
+      ]);
+      doubled.locate(&mid) != PointLocation::Outside
+    })
+  }
+
+  fn check_graph(polygon: &Polygon<i64>) {
+    let doubled = MultiPolygon::from(polygon.clone().map(|v| v * 2));
+    let mut expected = vec![];
+    for a in iter_vertices(polygon) {
+      for b in iter_vertices(polygon) {
+        if a.point_id() < b.point_id() && sees(polygon, &doubled, a.point(), b.point()) {
+          expected.push((a.point_id(), b.point_id()));
+        }
+      }
+    }
+    expected.sort_unstable();
+    assert_eq!(visibility_graph(polygon), expected);
+  }
+
+  fn integral(polygon: Polygon<BigRational>) -> Polygon<i64> {
+    polygon.map(|v| v.to_integer().to_i64().unwrap())
+  }
+
+  #[test]
+  fn colinear_holes() {
+    let square = |x: i8| {
+      Polygon::new(vec![
+        Point::new([x, 0]),
+        Point::new([x + 1, 0]),
+        Point::new([x + 1, 1]),
+        Point::new([x, 1]),
+      ])
+      .unwrap()
+    };
+    check_graph(&integral(polygon_with_holes(&[
+      square(0),
+      square(0),
+      square(0),
+    ])));
+  }
+
+  #[proptest]
+  fn matches_oracle(#[any((3..20, ()))] polygon: Polygon<i8>) {
+    check_graph(&polygon.map(i64::from));
+  }
+
+  #[proptest]
+  fn matches_oracle_holes(#[any((3..10, ()))] a: Polygon<i8>, #[any((3..10, ()))] b: Polygon<i8>) {
+    check_graph(&integral(polygon_with_holes(&[a, b])));
+  }
+}
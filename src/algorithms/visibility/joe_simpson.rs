@@ -0,0 +1,646 @@
+use std::cmp::Ordering;
+
+use super::naive::{get_intersection, get_intersection_colinear};
+use crate::data::{
+  DirectedEdge, HalfLineSoS, IHalfLineLineSegmentSoS, Line, Point, PointLocation, Polygon,
+};
+use crate::utils::exact::to_rational;
+use crate::{Intersects, Orientation, PolygonScalar};
+
+use num::BigRational;
+
+// Joe and Simpson's correction of Lee's algorithm:
+//   B. Joe and R. B. Simpson. Corrections to Lee's visibility polygon algorithm.
+//   BIT Numerical Mathematics, 27(4):458-473, 1987.
+//
+// The boundary is walked counter-clockwise, starting at the nearest point in a
+// direction from the origin that has no vertices. The stack holds the
+// visibility polygon of the boundary walked so far. While the boundary moves
+// counter-clockwise around the origin it is visible and pushed on the stack
+// (LEFT). When it turns back it either passes in front of the stack and hides
+// some of it (RIGHT), or it goes behind the stack and is skipped until it comes
+// out of hiding (SCANA, SCANB, SCANC and SCAND). Every vertex is pushed and
+// popped at most once.
+//
+// The scans use SoS rays that lean towards the side the boundary is expected
+// to come out on. A vertex that touches the ray without crossing it stays
+// hidden, exactly like the rays in the naive algorithm.
+
+/// Visibility polygon of a point inside a simple polygon.
+///
+/// Returns `None` if the point isn't strictly inside the polygon. The vertices
+/// are converted to `BigRational` as the boundary is walked and the sweep is
+/// done with exact arithmetic. The result has the same points as
+/// [`naive::get_visibility_polygon`](super::naive::get_visibility_polygon)
+/// gives for the input converted with [`PolygonScalar::to_rational`].
+///
+/// # Panics
+/// Panics if the polygon has holes.
+///
+/// # Time complexity
+/// $O(n)$
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::visibility::joe_simpson::get_visibility_polygon;
+/// # use rgeometry::data::{Point, Polygon};
+/// # use num::BigRational;
+/// let polygon = Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([10, 0]),
+///   Point::new([10, 6]),
+///   Point::new([6, 6]),
+///   Point::new([6, 3]),
+///   Point::new([4, 3]),
+///   Point::new([4, 6]),
+///   Point::new([0, 6]),
+/// ])
+/// .unwrap()
+/// .map(|v| BigRational::from_integer(v.into()));
+/// let point = Point::new([2, 1]).map(|v| BigRational::from_integer(v.into()));
+/// let visible = get_visibility_polygon(&point, &polygon).unwrap();
+/// let expected = Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([10, 0]),
+///   Point::new([10, 5]),
+///   Point::new([6, 3]),
+///   Point::new([4, 3]),
+///   Point::new([4, 6]),
+///   Point::new([0, 6]),
+/// ])
+/// .unwrap()
+/// .map(|v| BigRational::from_integer(v.into()));
+/// assert!(visible.equals(&expected));
+/// ```
+pub fn get_visibility_polygon<T>(
+  point: &Point<T>,
+  polygon: &Polygon<T>,
+) -> Option<Polygon<BigRational>>
+where
+  T: PolygonScalar,
+{
+  if polygon.locate(point) != PointLocation::Inside {
+    return None;
+  }
+  // The start point and the shadows are new points which overflow fixed-width
+  // scalars.
+  let point = to_rational(point);
+  let vertices = boundary_from_start(&point, polygon);
+  let mut sweep = Sweep {
+    origin: &point,
+    stack: vec![vertices[0].clone(), vertices[1].clone()],
+    w: vertices[1].clone(),
+    i: 1,
+    vertices,
+  };
+  let mut state = State::Left;
+  while state != State::Finish {
+    state = match state {
+      State::Left => sweep.left(),
+      State::Right => sweep.right(),
+      State::ScanA => sweep.scan_a(),
+      State::ScanB => sweep.scan_b(),
+      State::ScanC => sweep.scan_c(),
+      State::ScanD => sweep.scan_d(),
+      State::Finish => State::Finish,
+    };
+    if state == State::Left {
+      state = sweep.check_wrap();
+    }
+  }
+  // The stack is star-shaped around the point by construction. Validating it
+  // would take O(n log n) time.
+  let points = into_visibility_polygon(&point, sweep.stack);
+  Some(Polygon::new_unchecked(points))
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum State {
+  Left,
+  Right,
+  ScanA,
+  ScanB,
+  ScanC,
+  ScanD,
+  Finish,
+}
+
+struct Sweep<'a, T> {
+  origin: &'a Point<T>,
+  // Boundary starting and ending at the same point.
+  vertices: Vec<Point<T>>,
+  stack: Vec<Point<T>>,
+  // Index of the current vertex.
+  i: usize,
+  // Point on the boundary before the current vertex, or the end of the window
+  // that is being scanned for.
+  w: Point<T>,
+}
+
+impl<'a, T> Sweep<'a, T>
+where
+  T: PolygonScalar,
+{
+  fn last(&self) -> usize {
+    self.vertices.len() - 1
+  }
+
+  fn top(&self) -> Point<T> {
+    self.stack[self.stack.len() - 1].clone()
+  }
+
+  fn push(&mut self, pt: Point<T>) {
+    if self.stack.last() != Some(&pt) {
+      self.stack.push(pt)
+    }
+  }
+
+  // The boundary moves counter-clockwise and the current vertex is on top of
+  // the stack.
+  fn left(&mut self) -> State {
+    if self.i >= self.last() {
+      return State::Finish;
+    }
+    let vertex = &self.vertices[self.i];
+    let next = &self.vertices[self.i + 1];
+    if !Point::orient(self.origin, vertex, next).is_cw() {
+      self.push(next.clone());
+      self.i += 1;
+      State::Left
+    } else if Point::orient(&self.stack[self.stack.len() - 2], vertex, next).is_cw() {
+      // The boundary goes behind the current vertex.
+      self.i += 1;
+      State::ScanA
+    } else {
+      // The boundary comes back in front of the stack.
+      self.w = vertex.clone();
+      self.i += 1;
+      State::Right
+    }
+  }
+
+  // The boundary may have moved past the start, behind it.
+  fn check_wrap(&mut self) -> State {
+    let start = &self.vertices[0];
+    let top = &self.stack[self.stack.len() - 1];
+    let prev = &self.stack[self.stack.len() - 2];
+    if Point::orient(self.origin, start, prev).is_cw()
+      && Point::orient(self.origin, start, top).is_ccw()
+    {
+      if let Some(crossing) = intersect(self.origin, start, prev, top, true) {
+        *self.stack.last_mut().unwrap() = crossing;
+        return State::ScanB;
+      }
+    }
+    State::Left
+  }
+
+  // The boundary moves clockwise in front of the stack. Pop the points it
+  // hides.
+  fn right(&mut self) -> State {
+    let vertex = self.vertices[self.i].clone();
+    let mut orient_prev = Point::orient(self.origin, &self.top(), &vertex);
+    let (s_j, s_prev) = loop {
+      let s_j = self.stack.pop().expect("stack underflow");
+      let orient_j = orient_prev;
+      let s_prev = self.top();
+      orient_prev = Point::orient(self.origin, &s_prev, &vertex);
+      if !orient_j.is_ccw() && !orient_prev.is_cw() {
+        break (s_j, s_prev);
+      }
+      if self.w != s_j {
+        if let Some(crossing) = intersect(&s_prev, &s_j, &self.w, &vertex, false) {
+          // The boundary goes behind the stack through a window.
+          self.w = crossing;
+          return State::ScanD;
+        }
+      }
+    };
+    let next = self.vertices[self.i + 1].clone();
+    if Point::orient(self.origin, &vertex, &next).is_cw() {
+      self.stack.push(s_j);
+      self.w = vertex;
+      self.i += 1;
+      return State::Right;
+    }
+    let shadow = intersect(self.origin, &vertex, &s_prev, &s_j, true).unwrap_or(s_j);
+    self.push(shadow);
+    // 'w' is on the edge before the vertex but may be the vertex itself.
+    if Point::orient(&self.vertices[self.i - 1], &vertex, &next).is_cw() {
+      // The boundary turns counter-clockwise in front of the stack.
+      self.push(vertex);
+      self.push(next);
+      self.i += 1;
+      State::Left
+    } else {
+      // The boundary turns counter-clockwise behind itself.
+      self.w = vertex;
+      self.i += 1;
+      State::ScanC
+    }
+  }
+
+  // The boundary went behind the top of the stack. Find where it crosses the
+  // ray through the top of the stack.
+  fn scan_a(&mut self) -> State {
+    let top = self.top();
+    let (k, crossing) = self.scan(&top, Orientation::CounterClockWise, None);
+    self.i = k + 1;
+    if Point::orient(self.origin, &self.vertices[k], &self.vertices[k + 1]).is_cw() {
+      let beyond = self.origin.cmp_distance_to(&crossing, &top) == Ordering::Greater;
+      self.w = crossing;
+      if beyond {
+        State::ScanD
+      } else {
+        State::Right
+      }
+    } else {
+      self.push(crossing);
+      self.push(self.vertices[k + 1].clone());
+      State::Left
+    }
+  }
+
+  // The boundary moved past the start. Find where it comes back in front of
+  // the start.
+  fn scan_b(&mut self) -> State {
+    if self.i >= self.last() {
+      return State::Finish;
+    }
+    let top = self.top();
+    let start = self.vertices[0].clone();
+    let (k, crossing) = self.scan(&start, Orientation::ClockWise, Some((&top, &start)));
+    if k + 1 == self.last() && crossing == start {
+      self.push(start);
+      State::Finish
+    } else {
+      self.i = k + 1;
+      self.w = crossing;
+      State::Right
+    }
+  }
+
+  // The boundary is hidden behind itself. Find where it crosses the window
+  // between the top of the stack and 'w' and continue clockwise.
+  fn scan_c(&mut self) -> State {
+    let (top, w) = (self.top(), self.w.clone());
+    let (k, crossing) = self.scan(&w, Orientation::ClockWise, Some((&top, &w)));
+    self.i = k + 1;
+    self.w = crossing;
+    State::Right
+  }
+
+  // The boundary is hidden behind the stack. Find where it crosses the window
+  // between the top of the stack and 'w' and continue counter-clockwise.
+  fn scan_d(&mut self) -> State {
+    let (top, w) = (self.top(), self.w.clone());
+    let (k, crossing) = self.scan(&w, Orientation::CounterClockWise, Some((&top, &w)));
+    self.i = k + 1;
+    self.push(crossing);
+    self.push(self.vertices[k + 1].clone());
+    State::Left
+  }
+
+  // First edge starting at or after the current vertex that blocks the ray
+  // through 'through' when it leans to the given side. If a window is given,
+  // the ray must be blocked between its ends.
+  fn scan(
+    &self,
+    through: &Point<T>,
+    lean: Orientation,
+    window: Option<(&Point<T>, &Point<T>)>,
+  ) -> (usize, Point<T>) {
+    let ray_sos = HalfLineSoS::new_through(self.origin, through);
+    for k in self.i..self.last() {
+      let edge = DirectedEdge {
+        src: &self.vertices[k],
+        dst: &self.vertices[k + 1],
+      };
+      let crossing = match ray_sos.intersect(edge) {
+        Some(IHalfLineLineSegmentSoS::Crossing(Orientation::CoLinear)) => {
+          get_intersection(ray_sos, edge)
+        }
+        Some(IHalfLineLineSegmentSoS::Crossing(side)) if side == lean => {
+          get_intersection_colinear(ray_sos, edge)
+        }
+        _ => continue,
+      };
+      let in_window = match window {
+        None => true,
+        Some((a, b)) => {
+          let (near, far) = match self.origin.cmp_distance_to(a, b) {
+            Ordering::Greater => (b, a),
+            _ => (a, b),
+          };
+          self.origin.cmp_distance_to(&crossing, near) != Ordering::Less
+            && self.origin.cmp_distance_to(&crossing, far) != Ordering::Greater
+        }
+      };
+      if in_window {
+        return (k, crossing);
+      }
+    }
+    panic!("the boundary must come out of hiding")
+  }
+}
+
+// Walk the boundary counter-clockwise starting and ending at the nearest point
+// in a direction from 'point' that has no vertices. Each vertex is converted
+// to BigRational once.
+fn boundary_from_start<T>(
+  point: &Point<BigRational>,
+  polygon: &Polygon<T>,
+) -> Vec<Point<BigRational>>
+where
+  T: PolygonScalar,
+{
+  let ring: Vec<Point<BigRational>> = polygon
+    .iter_boundary()
+    .map(|cursor| to_rational(cursor.point()))
+    .collect();
+  let n = ring.len();
+  // No vertices lie strictly between the two smallest angles around the point.
+  // They are less than half a turn apart since the point is inside.
+  let first = ring
+    .iter()
+    .min_by(|a, b| point.ccw_cmp_around(a, b))
+    .expect("polygon must have vertices");
+  let second = ring
+    .iter()
+    .filter(|pt| point.ccw_cmp_around(first, pt).is_lt())
+    .min_by(|a, b| point.ccw_cmp_around(a, b))
+    .expect("point inside polygon must be surrounded by vertices");
+  let through = first + &(second - point);
+  let ray_sos = HalfLineSoS::new_through(point, &through);
+  let mut nearest = None;
+  for k in 0..n {
+    let edge = DirectedEdge {
+      src: &ring[k],
+      dst: &ring[(k + 1) % n],
+    };
+    if ray_sos.intersect(edge).is_some() {
+      let crossing = get_intersection(ray_sos, edge);
+      let is_nearest = match &nearest {
+        None => true,
+        Some((pt, _)) => point.cmp_distance_to(&crossing, pt) == Ordering::Less,
+      };
+      if is_nearest {
+        nearest = Some((crossing, k));
+      }
+    }
+  }
+  let (start, k) = nearest.expect("point inside polygon must see the boundary");
+  let mut vertices = Vec::with_capacity(n + 2);
+  vertices.push(start.clone());
+  vertices.extend_from_slice(&ring[k + 1..]);
+  vertices.extend_from_slice(&ring[..=k]);
+  vertices.push(start);
+  vertices
+}
+
+// Turn the stack into the output of the naive algorithm: Drop the start point,
+// which is in the middle of an edge, and the points that don't change the
+// shape of the polygon. Straight vertices of the polygon are kept unless they
+// are on a ray from 'point'. Start at the first point counter-clockwise from
+// direction 0.
+fn into_visibility_polygon<T>(point: &Point<T>, mut stack: Vec<Point<T>>) -> Vec<Point<T>>
+where
+  T: PolygonScalar,
+{
+  let is_redundant = |a: &Point<T>, b: &Point<T>, c: &Point<T>| {
+    let is_between = (a < b && b < c) || (c < b && b < a);
+    Point::orient(a, b, c).is_colinear()
+      && (Point::orient(point, a, b).is_colinear() || !is_between)
+  };
+  if stack.last() == stack.first() {
+    stack.pop();
+  }
+  let mut points: Vec<Point<T>> = Vec::with_capacity(stack.len());
+  for pt in stack.into_iter().skip(1) {
+    while let [.., a, b] = points.as_slice() {
+      if !is_redundant(a, b, &pt) {
+        break;
+      }
+      points.pop();
+    }
+    if points.last() != Some(&pt) {
+      points.push(pt);
+    }
+  }
+  // The chain is closed so the points at either end may be redundant too.
+  let mut start = 0;
+  while points.len() - start >= 3 {
+    let n = points.len();
+    if is_redundant(&points[n - 2], &points[n - 1], &points[start]) {
+      points.pop();
+    } else if is_redundant(&points[n - 1], &points[start], &points[start + 1]) {
+      start += 1;
+    } else {
+      break;
+    }
+  }
+  points.drain(..start);
+  let n = points.len();
+  let mut first = (0..n)
+    .min_by(|&a, &b| point.ccw_cmp_around(&points[a], &points[b]))
+    .unwrap_or(0);
+  let before = (first + n - 1) % n;
+  if point
+    .ccw_cmp_around(&points[before], &points[first])
+    .is_eq()
+  {
+    first = before;
+  }
+  points.rotate_left(first);
+  points
+}
+
+// Intersection of the segment from 'a' to 'b' with the segment from 'p' to
+// 'q', or with the ray from 'p' through 'q'.
+fn intersect<T>(
+  p: &Point<T>,
+  q: &Point<T>,
+  a: &Point<T>,
+  b: &Point<T>,
+  ray: bool,
+) -> Option<Point<T>>
+where
+  T: PolygonScalar,
+{
+  use Orientation::*;
+  // Whether a point on the line through 'p' and 'q' is on the segment or ray.
+  let contains = |x: &Point<T>| {
+    if ray {
+      x == p || (p < q) == (p < x)
+    } else {
+      (p <= x && x <= q) || (q <= x && x <= p)
+    }
+  };
+  let (orient_a, orient_b) = (Point::orient(p, q, a), Point::orient(p, q, b));
+  match (orient_a, orient_b) {
+    (CoLinear, CoLinear) => [a, b]
+      .iter()
+      .copied()
+      .find(|x| contains(x))
+      .or_else(|| Some(p).filter(|p| (a <= *p && *p <= b) || (b <= *p && *p <= a)))
+      .cloned(),
+    (CoLinear, _) => Some(a).filter(|a| contains(a)).cloned(),
+    (_, CoLinear) => Some(b).filter(|b| contains(b)).cloned(),
+    _ if orient_a == orient_b => None,
+    _ => {
+      let (orient_p, orient_q) = (Point::orient(a, b, p), Point::orient(a, b, q));
+      if orient_p.is_colinear() {
+        Some(p.clone())
+      } else if ray && orient_p != orient_a.reverse() || !ray && orient_p == orient_q {
+        None
+      } else if !ray && orient_q.is_colinear() {
+        Some(q.clone())
+      } else {
+        Line::new_through(p, q).intersection_point(&Line::new_through(a, b))
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::algorithms::visibility::naive;
+  use crate::testing::rational;
+
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  fn check_naive(point: &Point<BigRational>, polygon: &Polygon<BigRational>) {
+    let expected = naive::get_visibility_polygon(point, polygon);
+    let actual = get_visibility_polygon(point, polygon);
+    assert_eq!(
+      expected.map(|poly| poly.points),
+      actual.map(|poly| poly.points)
+    );
+  }
+
+  #[test]
+  fn outside() {
+    let polygon = rational(
+      Polygon::new(vec![
+        Point::new([0, 0]),
+        Point::new([2, 0]),
+        Point::new([2, 2]),
+        Point::new([0, 2]),
+      ])
+      .unwrap(),
+    );
+    for pt in [[3, 1], [2, 1], [0, 0]] {
+      let point = Point::new(pt.map(|v| BigRational::from_integer(v.into())));
+      assert!(get_visibility_polygon(&point, &polygon).is_none());
+    }
+  }
+
+  #[test]
+  fn spiral() {
+    // The boundary winds behind itself as seen from most points. Many points
+    // on the grid are colinear with vertices.
+    let polygon = rational(
+      Polygon::new(vec![
+        Point::new([0, 0]),
+        Point::new([10, 0]),
+        Point::new([10, 10]),
+        Point::new([2, 10]),
+        Point::new([2, 4]),
+        Point::new([6, 4]),
+        Point::new([6, 8]),
+        Point::new([8, 8]),
+        Point::new([8, 2]),
+        Point::new([0, 2]),
+      ])
+      .unwrap(),
+    );
+    for x in 0..=20 {
+      for y in 0..=20 {
+        let point = Point::new([x, y].map(|v| BigRational::new(v.into(), 2.into())));
+        if polygon.locate(&point) == PointLocation::Inside {
+          check_naive(&point, &polygon);
+        }
+      }
+    }
+  }
+
+  #[proptest]
+  fn matches_naive(
+    #[any((3..20, ()))] polygon: Polygon<i8>,
+    #[strategy(0usize..18)] triangle: usize,
+  ) {
+    let polygon = rational(polygon);
+    let (a, b, c) = polygon
+      .triangulate()
+      .nth(triangle % (polygon.points.len() - 2))
+      .unwrap();
+    let three = BigRational::from_integer(3.into());
+    let point =
+      Point::new([0, 1].map(|i| {
+        (a.point().array[i].clone() + &b.point().array[i] + &c.point().array[i]) / &three
+      }));
+    check_naive(&point, &polygon);
+  }
+
+  // Points on the integer grid are often colinear with vertices.
+  #[proptest]
+  fn matches_naive_degenerate(#[any((3..20, ()))] polygon: Polygon<i8>, point: Point<i8>) {
+    let polygon = rational(polygon);
+    let point = point.map(|v| BigRational::from_integer(v.into()));
+    prop_assume!(polygon.locate(&point) == PointLocation::Inside);
+    check_naive(&point, &polygon);
+  }
+
+  // Fixed-width scalars give the exact result.
+  fn check_naive_i8(point: &Point<i8>, polygon: &Polygon<i8>) {
+    let expected = naive::get_visibility_polygon(&to_rational(point), &rational(polygon.clone()));
+    let actual = get_visibility_polygon(point, polygon);
+    assert_eq!(
+      expected.map(|poly| poly.points),
+      actual.map(|poly| poly.points)
+    );
+  }
+
+  #[test]
+  fn large_square_i8() {
+    let polygon = Polygon::new(vec![
+      Point::new([-120i8, -120]),
+      Point::new([120, -120]),
+      Point::new([120, 120]),
+      Point::new([-120, 120]),
+    ])
+    .unwrap();
+    let point = Point::new([-110i8, 100]);
+    check_naive_i8(&point, &polygon);
+  }
+
+  #[proptest]
+  fn matches_naive_i8(
+    #[any((3..20, ()))] polygon: Polygon<i8>,
+    #[strategy(0usize..18)] triangle: usize,
+  ) {
+    let (a, b, c) = polygon
+      .triangulate()
+      .nth(triangle % (polygon.points.len() - 2))
+      .unwrap();
+    let point = Point::new([0, 1].map(|i| {
+      let sum: i16 = [a.point(), b.point(), c.point()]
+        .iter()
+        .map(|pt| i16::from(pt.array[i]))
+        .sum();
+      (sum / 3) as i8
+    }));
+    prop_assume!(polygon.locate(&point) == PointLocation::Inside);
+    check_naive_i8(&point, &polygon);
+  }
+
+  #[proptest]
+  fn matches_naive_degenerate_i8(#[any((3..20, ()))] polygon: Polygon<i8>, point: Point<i8>) {
+    prop_assume!(polygon.locate(&point) == PointLocation::Inside);
+    check_naive_i8(&point, &polygon);
+  }
+}
@@ -1 +1,5 @@
+pub mod angular_sweep;
+pub mod graph;
+pub mod joe_simpson;
 pub mod naive;
+pub mod weak;
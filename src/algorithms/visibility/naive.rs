@@ -93,9 +93,11 @@ pub fn get_visibility_polygon<T>(point: &Point<T>, polygon: &Polygon<T>) -> Opti
 where
   T: PolygonScalar,
 {
-  // FIXME: We want to iterate over all vertices, not just boundary vertices.
-  let mut vertices: Vec<Cursor<'_, T>> = polygon.iter_boundary().collect();
-  vertices.sort_by(|a, b| point.ccw_cmp_around(a, b));
+  let mut vertices: Vec<Cursor<'_, T>> = iter_vertices(polygon).collect();
+  vertices.sort_by(|a, b| sort_around(point, a, b));
+  // Rays towards vertices in the same direction hit the same edges. Only the
+  // nearest vertex is kept so the intersections are output once.
+  vertices.dedup_by(|a, b| point.ccw_cmp_around(a, b).is_eq());
 
   let mut polygon_points = Vec::new();
   for vertex in vertices {
@@ -103,8 +105,7 @@ where
     let mut right_intersection = NearestIntersection::new(point);
     let mut left_intersection = NearestIntersection::new(point);
 
-    // FIXME: We want to iterate over all edges, not just boundary edges.
-    for edge in polygon.iter_boundary_edges() {
+    for edge in iter_edges(polygon) {
       use IHalfLineLineSegmentSoS::*;
       use Orientation::*;
       match ray_sos.intersect(edge) {
@@ -148,7 +149,38 @@ where
   Some(Polygon::new(polygon_points).expect("Polygon Creation failed"))
 }
 
-fn get_intersection_colinear<T>(sos_line: HalfLineSoS<T>, edge: DirectedEdge<'_, T>) -> Point<T>
+// Sort counter-clockwise around 'point' and then by distance.
+pub(super) fn sort_around<T>(point: &Point<T>, a: &Point<T>, b: &Point<T>) -> Ordering
+where
+  T: PolygonScalar,
+{
+  point
+    .ccw_cmp_around(a, b)
+    .then_with(|| point.cmp_distance_to(a, b))
+}
+
+// Vertices of the boundary followed by the vertices of the holes.
+pub(crate) fn iter_vertices<T>(polygon: &Polygon<T>) -> impl Iterator<Item = Cursor<'_, T>> {
+  polygon
+    .iter_boundary()
+    .chain(polygon.iter_holes().flatten())
+}
+
+// Edges of the boundary followed by the edges of the holes.
+pub(super) fn iter_edges<T>(polygon: &Polygon<T>) -> impl Iterator<Item = DirectedEdge<'_, T>>
+where
+  T: PolygonScalar,
+{
+  iter_vertices(polygon).map(|cursor| DirectedEdge {
+    src: cursor.point(),
+    dst: cursor.next().point(),
+  })
+}
+
+pub(super) fn get_intersection_colinear<T>(
+  sos_line: HalfLineSoS<T>,
+  edge: DirectedEdge<'_, T>,
+) -> Point<T>
 where
   T: PolygonScalar,
 {
@@ -160,7 +192,7 @@ where
   }
 }
 
-fn get_intersection<T>(sos_line: HalfLineSoS<T>, edge: DirectedEdge<'_, T>) -> Point<T>
+pub(super) fn get_intersection<T>(sos_line: HalfLineSoS<T>, edge: DirectedEdge<'_, T>) -> Point<T>
 where
   T: PolygonScalar,
 {
@@ -305,6 +337,44 @@ mod naive_testing {
     }
   }
 
+  //   Input            Output
+  //  /---------\     /-\     /-\
+  //  |   /-\   |     |  \   /  |
+  //  |   \-/   |     |   \ /   |
+  //  |    x    |     |    x    |
+  //  \---------/     \---------/
+  #[test]
+  fn case_3() {
+    let point = Point::new([4, 2]);
+    let input_polygon = Polygon::new_with_holes(
+      vec![
+        Point::new([0, 0]),
+        Point::new([8, 0]),
+        Point::new([8, 8]),
+        Point::new([0, 8]),
+      ],
+      vec![vec![
+        Point::new([3, 5]),
+        Point::new([3, 6]),
+        Point::new([5, 6]),
+        Point::new([5, 5]),
+      ]],
+    )
+    .unwrap();
+    let out_test_points = vec![
+      Point::new([8, 8]),
+      Point::new([6, 8]),
+      Point::new([5, 5]),
+      Point::new([3, 5]),
+      Point::new([2, 8]),
+      Point::new([0, 8]),
+      Point::new([0, 0]),
+      Point::new([8, 0]),
+    ];
+    let out_polygon = get_visibility_polygon(&point, &input_polygon).unwrap();
+    assert_eq!(out_test_points, out_polygon.points);
+  }
+
   // test with rotating square
   #[test]
   fn test_rotating_square() {
@@ -0,0 +1,493 @@
+use std::cmp::Ordering;
+
+use super::graph::Cone;
+use super::naive::{get_intersection, get_intersection_colinear};
+use crate::data::{
+  Cursor, DirectedEdge, HalfLineSoS, IHalfLineLineSegmentSoS, Line, LineSegment, Point, Polygon,
+};
+use crate::utils::exact::to_rational;
+use crate::{Intersects, Orientation, PolygonScalar};
+
+use num::BigRational;
+
+/// Region of a simple polygon that is weakly visible from a line segment.
+///
+/// A point is weakly visible if it sees at least one point of the segment. The
+/// region is the polygon minus the pockets hidden behind windows: chords that
+/// start at a reflex vertex and continue the line of sight from the last point
+/// of the segment that sees the vertex. Both endpoints of the segment are
+/// included. Lines of sight are SoS rays, as in
+/// [`naive::get_visibility_polygon`](super::naive::get_visibility_polygon), so
+/// they slip past the vertices that lie exactly on them. Returns `None` if the
+/// segment isn't inside the polygon. The ends of the windows are new points, so
+/// the region is computed and returned with exact arithmetic.
+///
+/// # Time complexity
+/// $O(n^3)$
+///
+/// Each reflex vertex finds the part of the segment it sees by checking $O(n)$
+/// candidate points, and each check walks the boundary.
+///
+/// # Panics
+///
+/// Panics if the polygon has holes.
+///
+/// # Examples
+///
+/// ```rust
+/// # use rgeometry::algorithms::visibility::weak::get_weak_visibility_polygon;
+/// # use rgeometry::data::{LineSegment, Point, Polygon};
+/// # use num::BigRational;
+/// let polygon = Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([4, 0]),
+///   Point::new([4, 2]),
+///   Point::new([2, 2]),
+///   Point::new([2, 4]),
+///   Point::new([0, 4]),
+/// ])
+/// .unwrap();
+/// let door = LineSegment::from(Point::new([3, 0])..=Point::new([4, 0]));
+/// let visible = get_weak_visibility_polygon(&door, &polygon).unwrap();
+/// let expected = Polygon::new(vec![
+///   Point::new([0, 0]),
+///   Point::new([4, 0]),
+///   Point::new([4, 2]),
+///   Point::new([2, 2]),
+///   Point::new([1, 4]),
+///   Point::new([0, 4]),
+/// ])
+/// .unwrap()
+/// .map(|v| BigRational::from_integer(v.into()));
+/// assert!(visible.equals(&expected));
+/// ```
+pub fn get_weak_visibility_polygon<T>(
+  segment: &LineSegment<T>,
+  polygon: &Polygon<T>,
+) -> Option<Polygon<BigRational>>
+where
+  T: PolygonScalar,
+{
+  assert_eq!(
+    polygon.rings.len(),
+    1,
+    "Weak visibility only supports polygons without holes."
+  );
+  // Windows end at new points which overflow fixed-width scalars.
+  let a = to_rational(segment.min.inner());
+  let b = to_rational(segment.max.inner());
+  weak_visibility(&a, &b, &polygon.clone().map(|v| v.to_rational()))
+}
+
+fn weak_visibility(
+  a: &Point<BigRational>,
+  b: &Point<BigRational>,
+  polygon: &Polygon<BigRational>,
+) -> Option<Polygon<BigRational>> {
+  if !Cone::new(polygon, a)?.sees(polygon, a, b) {
+    return None;
+  }
+
+  let closed = LineSegment::from(a.clone()..=b.clone());
+  let boundary: Vec<Cursor<'_, BigRational>> = polygon.iter_boundary().collect();
+  let mut marks = Vec::new();
+  for (idx, vertex) in boundary.iter().enumerate() {
+    marks.push(Mark {
+      idx,
+      point: vertex.point().clone(),
+      kind: MarkKind::Vertex,
+    });
+    // Only reflex vertices can hide parts of the polygon. Everything that a
+    // vertex on the segment sees is weakly visible.
+    if !vertex.orientation().is_cw() || closed.contains(vertex.point()) {
+      continue;
+    }
+    if let Some((first, last)) = visible_interval(polygon, *vertex, a, b) {
+      for (from, other) in [(&first, &last), (&last, &first)] {
+        if let Some([start, end]) = get_window(&boundary, idx, from, other) {
+          marks.push(start);
+          marks.push(end);
+        }
+      }
+    }
+  }
+  marks.sort_by(|p, q| {
+    p.idx
+      .cmp(&q.idx)
+      .then_with(|| boundary[p.idx].cmp_distance_to(&p.point, &q.point))
+      .then_with(|| p.kind.cmp(&q.kind))
+  });
+
+  // Pockets are nested or disjoint. Start the walk where no pocket is open.
+  let mut depth = 0;
+  let mut lowest = (0, 0);
+  for (nth, mark) in marks.iter().enumerate() {
+    depth += mark.kind.depth();
+    if depth < lowest.0 {
+      lowest = (depth, nth + 1);
+    }
+  }
+  marks.rotate_left(lowest.1);
+
+  let mut polygon_points = Vec::new();
+  let mut depth = 0;
+  for mark in marks {
+    let outside = match mark.kind {
+      MarkKind::Vertex => depth == 0,
+      MarkKind::Start => depth == 0,
+      MarkKind::End => depth == 1,
+    };
+    if outside {
+      polygon_points.push(mark.point);
+    }
+    depth += mark.kind.depth();
+  }
+  polygon_points.dedup();
+  if polygon_points.len() > 1 && polygon_points.first() == polygon_points.last() {
+    polygon_points.pop();
+  }
+
+  Some(Polygon::new(polygon_points).expect("Polygon Creation failed"))
+}
+
+// Point on the boundary of the polygon, on the edge that starts at the
+// 'idx'th vertex. Pockets are cut out between a start and an end mark.
+struct Mark<T> {
+  idx: usize,
+  point: Point<T>,
+  kind: MarkKind,
+}
+
+// Marks at the same point are ordered so that pockets are closed before the
+// vertex and opened after it.
+#[derive(PartialEq, Eq, PartialOrd, Ord)]
+enum MarkKind {
+  End,
+  Vertex,
+  Start,
+}
+
+impl MarkKind {
+  fn depth(&self) -> i32 {
+    match self {
+      MarkKind::End => -1,
+      MarkKind::Vertex => 0,
+      MarkKind::Start => 1,
+    }
+  }
+}
+
+// The points of segment 'ab' seen by 'vertex' form an interval. Visibility only
+// changes where the view from 'vertex' grazes another vertex so the endpoints
+// of the interval are either 'a', 'b' or such a point.
+fn visible_interval<T>(
+  polygon: &Polygon<T>,
+  vertex: Cursor<'_, T>,
+  a: &Point<T>,
+  b: &Point<T>,
+) -> Option<(Point<T>, Point<T>)>
+where
+  T: PolygonScalar,
+{
+  let ab = Line::new_through(a, b);
+  let mut candidates = vec![a.clone(), b.clone()];
+  for other in polygon.iter_boundary() {
+    let pt = other.point();
+    if pt == vertex.point() {
+      continue;
+    }
+    let side_a = Point::orient(vertex.point(), pt, a);
+    let side_b = Point::orient(vertex.point(), pt, b);
+    if side_a.is_colinear() && side_b.is_colinear() {
+      // 'ab' is on the line through 'vertex' and 'other'.
+      if a.cmp_distance_to(pt, b).is_lt() && b.cmp_distance_to(pt, a).is_lt() {
+        candidates.push(pt.clone());
+      }
+    } else if side_a == side_b.reverse() && !side_a.is_colinear() {
+      let line = Line::new_through(vertex.point(), pt);
+      candidates.push(line.intersection_point(&ab).expect("LinesMustIntersect"));
+    }
+  }
+  candidates.sort_by(|p, q| a.cmp_distance_to(p, q));
+  candidates.dedup();
+
+  // The interval may be open at either end so the pieces between the
+  // candidates are checked at their midpoints.
+  let cone = Cone::Vertex(vertex);
+  let sees = |pt: &Point<T>| cone.sees(polygon, vertex.point(), pt);
+  let two = T::from_constant(2);
+  let mut first = None;
+  let mut last = None;
+  for (nth, pt) in candidates.iter().enumerate() {
+    let piece_visible = |other: Option<&Point<T>>| match other {
+      Some(other) => sees(&Point::new([
+        (pt.x_coord().clone() + other.x_coord().clone()) / two.clone(),
+        (pt.y_coord().clone() + other.y_coord().clone()) / two.clone(),
+      ])),
+      None => false,
+    };
+    if first.is_none() && (sees(pt) || piece_visible(candidates.get(nth + 1))) {
+      first = Some(pt.clone());
+    }
+    if first.is_some() && (sees(pt) || piece_visible(nth.checked_sub(1).map(|i| &candidates[i]))) {
+      last = Some(pt.clone());
+    }
+  }
+  Some((first?, last?))
+}
+
+// Window that continues the line of sight from 'from' past the 'idx'th vertex.
+// The pocket behind the window is on the same side as the edges of the vertex
+// and it is hidden from the segment if 'other' (and so every point that sees
+// the vertex) is on that side as well. Returns the marks around the pocket.
+fn get_window<T>(
+  boundary: &[Cursor<'_, T>],
+  idx: usize,
+  from: &Point<T>,
+  other: &Point<T>,
+) -> Option<[Mark<T>; 2]>
+where
+  T: PolygonScalar,
+{
+  let vertex = boundary[idx];
+  if from == vertex.point() {
+    return None;
+  }
+  let prev = Point::orient(from, &vertex, vertex.prev().point());
+  let next = Point::orient(from, &vertex, vertex.next().point());
+  // The line of sight must continue past the vertex rather than run along one
+  // of its edges.
+  let ahead = |side: Orientation, pt: &Point<T>| {
+    side.is_colinear() && from.cmp_distance_to(pt, &vertex) == Ordering::Greater
+  };
+  if ahead(prev, vertex.prev().point()) || ahead(next, vertex.next().point()) {
+    return None;
+  }
+  let side = match (prev, next) {
+    (Orientation::CoLinear, side) | (side, Orientation::CoLinear) => side,
+    (prev, next) if prev == next => prev,
+    _ => return None,
+  };
+  if side.is_colinear() || Point::orient(from, &vertex, other) == side.reverse() {
+    return None;
+  }
+
+  // Nearest hit past the vertex by a ray that leans away from the pocket.
+  let ray_sos = HalfLineSoS::new_through(from, vertex.point());
+  let mut nearest: Option<(usize, Point<T>)> = None;
+  for (nth, cursor) in boundary.iter().enumerate() {
+    let edge = DirectedEdge {
+      src: cursor.point(),
+      dst: cursor.next().point(),
+    };
+    let intersection = match ray_sos.intersect(edge) {
+      Some(IHalfLineLineSegmentSoS::Crossing(Orientation::CoLinear)) => {
+        get_intersection(ray_sos, edge)
+      }
+      Some(IHalfLineLineSegmentSoS::Crossing(lean)) if lean == side.reverse() => {
+        get_intersection_colinear(ray_sos, edge)
+      }
+      _ => continue,
+    };
+    let is_nearer = match &nearest {
+      Some((_, pt)) => from.cmp_distance_to(&intersection, pt) == Ordering::Less,
+      None => true,
+    };
+    if from.cmp_distance_to(&intersection, &vertex) == Ordering::Greater && is_nearer {
+      let nth = if &intersection == edge.dst {
+        (nth + 1) % boundary.len()
+      } else {
+        nth
+      };
+      nearest = Some((nth, intersection));
+    }
+  }
+  let (hit_idx, hit) = nearest?;
+
+  let at_vertex = (idx, vertex.point().clone());
+  let at_hit = (hit_idx, hit);
+  // The boundary runs counter-clockwise through the pocket. A pocket on the
+  // right of the ray is entered at the vertex and left at the hit.
+  let (start, end) = if side.is_cw() {
+    (at_vertex, at_hit)
+  } else {
+    (at_hit, at_vertex)
+  };
+  Some([
+    Mark {
+      idx: start.0,
+      point: start.1,
+      kind: MarkKind::Start,
+    },
+    Mark {
+      idx: end.0,
+      point: end.1,
+      kind: MarkKind::End,
+    },
+  ])
+}
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::algorithms::visibility::graph::crosses;
+  use crate::data::PointLocation;
+  use crate::testing::rational;
+
+  use num::BigRational;
+  use test_strategy::proptest;
+
+  // Cut the segment at the vertices on it and check that the pieces are inside
+  // the polygon.
+  fn sees(polygon: &Polygon<BigRational>, p: &Point<BigRational>, q: &Point<BigRational>) -> bool {
+    let mut cuts = vec![p, q];
+    for cursor in polygon.iter_boundary() {
+      let (src, dst) = (cursor.point(), cursor.next().point());
+      if crosses(p, q, src, dst) {
+        return false;
+      }
+      if Point::orient(p, q, src).is_colinear()
+        && p.cmp_distance_to(src, q).is_lt()
+        && q.cmp_distance_to(src, p).is_lt()
+      {
+        cuts.push(src);
+      }
+    }
+    cuts.sort_by(|x, y| p.cmp_distance_to(x, y));
+    let two = BigRational::from_integer(2.into());
+    cuts.windows(2).all(|pair| {
+      let mid = Point::new([
+        (pair[0].x_coord() + pair[1].x_coord()) / &two,
+        (pair[0].y_coord() + pair[1].y_coord()) / &two,
+      ]);
+      polygon.locate(&mid) != PointLocation::Outside
+    })
+  }
+
+  // The points of 'ab' seen from 'pt' form an interval whose endpoints are 'a',
+  // 'b' or on a line through 'pt' and a vertex.
+  fn weakly_visible(
+    polygon: &Polygon<BigRational>,
+    a: &Point<BigRational>,
+    b: &Point<BigRational>,
+    pt: &Point<BigRational>,
+  ) -> bool {
+    let ab = Line::new_through(a, b);
+    let mut candidates = vec![a.clone(), b.clone()];
+    for vertex in polygon.iter_boundary() {
+      let (side_a, side_b) = (Point::orient(pt, &vertex, a), Point::orient(pt, &vertex, b));
+      if !side_a.is_colinear() && side_a == side_b.reverse() {
+        let line = Line::new_through(pt, vertex.point());
+        candidates.push(line.intersection_point(&ab).unwrap());
+      }
+    }
+    candidates.sort_by(|p, q| a.cmp_distance_to(p, q));
+    let two = BigRational::from_integer(2.into());
+    let midpoints: Vec<Point<BigRational>> = candidates
+      .windows(2)
+      .map(|pair| {
+        Point::new([
+          (pair[0].x_coord() + pair[1].x_coord()) / &two,
+          (pair[0].y_coord() + pair[1].y_coord()) / &two,
+        ])
+      })
+      .collect();
+    candidates
+      .iter()
+      .chain(midpoints.iter())
+      .any(|x| sees(polygon, pt, x))
+  }
+
+  // Points in the interior of each triangle: the centroid and the points
+  // halfway between the centroid and the corners. Vertices can be seen along
+  // the edges next to them, which the lines of sight slip past.
+  fn interior(polygon: &Polygon<BigRational>) -> Vec<Point<BigRational>> {
+    let weighted = |pts: [&Point<BigRational>; 3], ws: [i32; 3]| {
+      let total = BigRational::from_integer(ws.iter().sum::<i32>().into());
+      let coord = |f: fn(&Point<BigRational>) -> &BigRational| {
+        pts
+          .iter()
+          .zip(ws)
+          .map(|(pt, w)| f(pt) * BigRational::from_integer(w.into()))
+          .sum::<BigRational>()
+          / &total
+      };
+      Point::new([coord(|pt| pt.x_coord()), coord(|pt| pt.y_coord())])
+    };
+    let mut points = Vec::new();
+    for (p, q, r) in polygon.triangulate() {
+      let corners = [p.point(), q.point(), r.point()];
+      points.push(weighted(corners, [1, 1, 1]));
+      points.push(weighted(corners, [4, 1, 1]));
+      points.push(weighted(corners, [1, 4, 1]));
+      points.push(weighted(corners, [1, 1, 4]));
+    }
+    points
+  }
+
+  #[proptest]
+  fn matches_oracle(#[any((3..10, ()))] polygon: Polygon<i8>, a: usize, b: usize) {
+    let polygon = rational(polygon);
+    let points = interior(&polygon);
+    let mut endpoints: Vec<Point<BigRational>> = polygon
+      .iter_boundary()
+      .map(|pt| pt.point().clone())
+      .collect();
+    endpoints.extend(points.iter().cloned());
+    let (a, b) = (
+      &endpoints[a % endpoints.len()],
+      &endpoints[b % endpoints.len()],
+    );
+    let segment = LineSegment::from(a.clone()..=b.clone());
+    match get_weak_visibility_polygon(&segment, &polygon) {
+      None => assert!(!sees(&polygon, a, b)),
+      Some(visible) => {
+        for pt in &points {
+          let expected = weakly_visible(&polygon, a, b, pt);
+          let actual = visible.locate(pt) != PointLocation::Outside;
+          assert_eq!(expected, actual, "{:?}", pt);
+        }
+      }
+    }
+  }
+
+  // Fixed-width scalars give the exact result.
+  fn check_rational_i8(segment: &LineSegment<i8>, polygon: &Polygon<i8>) {
+    let exact =
+      LineSegment::from(to_rational(segment.min.inner())..=to_rational(segment.max.inner()));
+    let expected = get_weak_visibility_polygon(&exact, &rational(polygon.clone()));
+    let actual = get_weak_visibility_polygon(segment, polygon);
+    assert_eq!(
+      expected.map(|poly| poly.points),
+      actual.map(|poly| poly.points)
+    );
+  }
+
+  #[test]
+  fn large_polygon_i8() {
+    // The window past (0, 0) ends at (-109.09, 120) and the intersection
+    // overflows i8.
+    let polygon = Polygon::new(vec![
+      Point::new([-120i8, -120]),
+      Point::new([120, -120]),
+      Point::new([120, 0]),
+      Point::new([0, 0]),
+      Point::new([0, 120]),
+      Point::new([-120, 120]),
+    ])
+    .unwrap();
+    let door = LineSegment::from(Point::new([100i8, -110])..=Point::new([120, -110]));
+    check_rational_i8(&door, &polygon);
+  }
+
+  #[proptest]
+  fn matches_rational_i8(#[any((3..20, ()))] polygon: Polygon<i8>, a: usize, b: usize) {
+    let n = polygon.iter_boundary().count();
+    let (a, b) = (
+      polygon.iter_boundary().nth(a % n).unwrap().point().clone(),
+      polygon.iter_boundary().nth(b % n).unwrap().point().clone(),
+    );
+    check_rational_i8(&LineSegment::from(a..=b), &polygon);
+  }
+}
@@ -0,0 +1,265 @@
+//! Voronoi diagrams of point sets.
+//!
+//! The Voronoi diagram is computed as the dual of the [Delaunay
+//! triangulation](super::triangulation::delaunay::delaunay): The cell of a site
+//! is the intersection of the half-planes closer to the site than to each of its
+//! Delaunay neighbours. Unbounded cells are clipped to a convex region.
+//!
+//! Cell vertices are computed exactly and rounded with
+//! [`PolygonScalar::from_rational`]. Integer types are not recommended.
+use crate::data::{Point, Polygon, PolygonConvex};
+use crate::utils::exact::{signed_area_2x, to_rational};
+use crate::PolygonScalar;
+
+use num::{BigRational, Signed};
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::triangulation::delaunay::delaunay;
+
+type Rational = Point<BigRational, 2>;
+
+/// Voronoi cells clipped to a convex polygon. The result has one entry per
+/// site. Duplicate sites get identical cells and cells that do not overlap the
+/// clipping region (or only touch its boundary) are `None`.
+///
+/// Cells are counter-clockwise and convex.
+///
+/// # Time complexity
+/// $O(n^2 + n m)$ where $m$ is the number of vertices in the clipping region.
+pub fn voronoi<T>(sites: &[Point<T>], clip: &PolygonConvex<T>) -> Vec<Option<Polygon<T>>>
+where
+  T: PolygonScalar,
+{
+  let region: Vec<Rational> = clip.iter().map(to_rational).collect();
+
+  // Remove duplicates before triangulating.
+  let mut unique: Vec<&Point<T>> = Vec::new();
+  let mut first: BTreeMap<&Point<T>, usize> = BTreeMap::new();
+  let site_index: Vec<usize> = sites
+    .iter()
+    .map(|site| {
+      *first.entry(site).or_insert_with(|| {
+        unique.push(site);
+        unique.len() - 1
+      })
+    })
+    .collect();
+  let points: Vec<Point<T>> = unique.iter().map(|&pt| pt.clone()).collect();
+
+  let cells: Vec<Option<Polygon<T>>> = neighbours(&points)
+    .iter()
+    .enumerate()
+    .map(|(i, others)| {
+      let site = to_rational(&points[i]);
+      let cell = others.iter().fold(region.clone(), |cell, &j| {
+        clip_closer(&cell, &site, &to_rational(&points[j]))
+      });
+      if signed_area_2x(&cell).is_positive() {
+        let vertices = cell
+          .iter()
+          .map(|pt| {
+            Point::new([
+              T::from_rational(&pt.array[0]),
+              T::from_rational(&pt.array[1]),
+            ])
+          })
+          .collect();
+        Some(Polygon::new_unchecked(vertices))
+      } else {
+        None
+      }
+    })
+    .collect();
+  site_index.iter().map(|&i| cells[i].clone()).collect()
+}
+
+/// Voronoi cells clipped to the axis-aligned box spanned by `min` and `max`.
+/// See [`voronoi`] for details.
+///
+/// # Panics
+/// Panics if the box has no area.
+pub fn voronoi_bbox<T>(
+  sites: &[Point<T>],
+  min: &Point<T>,
+  max: &Point<T>,
+) -> Vec<Option<Polygon<T>>>
+where
+  T: PolygonScalar,
+{
+  assert!(
+    min.array[0] < max.array[0] && min.array[1] < max.array[1],
+    "bounding box must have a positive area"
+  );
+  let bbox = Polygon::new_unchecked(vec![
+    min.clone(),
+    Point::new([max.array[0].clone(), min.array[1].clone()]),
+    max.clone(),
+    Point::new([min.array[0].clone(), max.array[1].clone()]),
+  ]);
+  voronoi(sites, &PolygonConvex::new_unchecked(bbox))
+}
+
+// Voronoi neighbours of each (unique) point. These are the Delaunay neighbours
+// or, if all points are colinear, the adjacent points along the line.
+fn neighbours<T>(points: &[Point<T>]) -> Vec<BTreeSet<usize>>
+where
+  T: PolygonScalar,
+{
+  let mut neighbours = vec![BTreeSet::new(); points.len()];
+  let mut link = |a: usize, b: usize| {
+    neighbours[a].insert(b);
+    neighbours[b].insert(a);
+  };
+  let trig = delaunay(points);
+  if trig.is_empty() {
+    let mut order: Vec<usize> = (0..points.len()).collect();
+    order.sort_by(|&a, &b| points[a].cmp(&points[b]));
+    for pair in order.windows(2) {
+      link(pair[0], pair[1]);
+    }
+  } else {
+    for (a, b, c) in trig.iter() {
+      link(a.usize(), b.usize());
+      link(b.usize(), c.usize());
+      link(c.usize(), a.usize());
+    }
+  }
+  neighbours
+}
+
+// Sutherland-Hodgman: Keep the part of a convex polygon that is at least as
+// close to 'site' as to 'other'.
+fn clip_closer(cell: &[Rational], site: &Rational, other: &Rational) -> Vec<Rational> {
+  // f(p) = 2(other - site)·p - (|other|² - |site|²) is positive if 'p' is closer
+  // to 'other'.
+  let nx = (&other.array[0] - &site.array[0]) * BigRational::from_integer(2.into());
+  let ny = (&other.array[1] - &site.array[1]) * BigRational::from_integer(2.into());
+  let offset = (&other.array[0] * &other.array[0] + &other.array[1] * &other.array[1])
+    - (&site.array[0] * &site.array[0] + &site.array[1] * &site.array[1]);
+  let f = |p: &Rational| &nx * &p.array[0] + &ny * &p.array[1] - &offset;
+
+  let mut out = Vec::new();
+  for (i, p) in cell.iter().enumerate() {
+    let q = &cell[(i + 1) % cell.len()];
+    let (fp, fq) = (f(p), f(q));
+    if !fp.is_positive() {
+      out.push(p.clone());
+    }
+    if (fp.is_negative() && fq.is_positive()) || (fp.is_positive() && fq.is_negative()) {
+      let t = &fp / (&fp - &fq);
+      out.push(Point::new([
+        &p.array[0] + (&q.array[0] - &p.array[0]) * &t,
+        &p.array[1] + (&q.array[1] - &p.array[1]) * &t,
+      ]));
+    }
+  }
+  out
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  use crate::data::PointLocation;
+
+  use num::Zero;
+  use proptest::collection::vec;
+  use proptest::prelude::*;
+  use test_strategy::proptest;
+
+  fn rat(x: i32) -> BigRational {
+    BigRational::from_integer(x.into())
+  }
+
+  fn big(x: i32, y: i32) -> Point<BigRational> {
+    Point::new([rat(x), rat(y)])
+  }
+
+  fn area(poly: &Polygon<BigRational>) -> BigRational {
+    poly.signed_area_2x::<BigRational>() / rat(2)
+  }
+
+  #[test]
+  fn single_site() {
+    let cells = voronoi_bbox(&[big(1, 1)], &big(0, 0), &big(4, 4));
+    assert_eq!(area(cells[0].as_ref().unwrap()), rat(16));
+  }
+
+  #[test]
+  fn two_sites() {
+    let cells = voronoi_bbox(&[big(1, 1), big(3, 1)], &big(0, 0), &big(4, 4));
+    for cell in &cells {
+      assert_eq!(area(cell.as_ref().unwrap()), rat(8));
+    }
+    let left = cells[0].as_ref().unwrap();
+    assert_eq!(left.locate(&big(1, 3)), PointLocation::Inside);
+    assert_eq!(left.locate(&big(2, 2)), PointLocation::OnBoundary);
+    assert_eq!(left.locate(&big(3, 3)), PointLocation::Outside);
+  }
+
+  #[test]
+  fn grid() {
+    // 3x3 sites in a 6x6 box. Every cell is a 2x2 square.
+    let sites: Vec<_> = (0..9)
+      .map(|i| big(1 + 2 * (i % 3), 1 + 2 * (i / 3)))
+      .collect();
+    let cells = voronoi_bbox(&sites, &big(0, 0), &big(6, 6));
+    for (site, cell) in sites.iter().zip(&cells) {
+      let cell = cell.as_ref().unwrap();
+      assert_eq!(area(cell), rat(4));
+      assert_eq!(cell.locate(site), PointLocation::Inside);
+    }
+  }
+
+  #[test]
+  fn outside_and_duplicates() {
+    let sites = [big(1, 1), big(1, 1), big(10, 1)];
+    let cells = voronoi_bbox(&sites, &big(0, 0), &big(4, 4));
+    assert!(cells[0]
+      .as_ref()
+      .unwrap()
+      .equals(cells[1].as_ref().unwrap()));
+    assert!(cells[2].is_none());
+  }
+
+  #[test]
+  fn float_sites() {
+    let sites = [Point::new([1.0, 1.0]), Point::new([3.0, 1.0])];
+    let cells = voronoi_bbox(&sites, &Point::new([0.0, 0.0]), &Point::new([4.0, 4.0]));
+    for cell in cells {
+      assert_eq!(cell.unwrap().signed_area::<f64>(), 8.0);
+    }
+  }
+
+  #[proptest]
+  fn cells_partition_box(#[strategy(vec(any::<(i8, i8)>(), 1..10))] coords: Vec<(i8, i8)>) {
+    let sites: Vec<_> = coords
+      .iter()
+      .map(|&(x, y)| big(x.into(), y.into()))
+      .collect();
+    let cells = voronoi_bbox(&sites, &big(-128, -128), &big(128, 128));
+    let mut total = BigRational::zero();
+    let mut seen = BTreeSet::new();
+    for (site, cell) in sites.iter().zip(&cells) {
+      let cell = cell.as_ref().expect("every site is inside the box");
+      prop_assert_ne!(cell.locate(site), PointLocation::Outside);
+      // Every point in a cell is at least as close to its site as to any other.
+      for vertex in cell.iter() {
+        for other in &sites {
+          prop_assert_ne!(
+            vertex.cmp_distance_to(site, other),
+            std::cmp::Ordering::Greater
+          );
+        }
+      }
+      if seen.insert(site) {
+        total += area(cell);
+      }
+    }
+    prop_assert_eq!(total, rat(256 * 256));
+  }
+}
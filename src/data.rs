@@ -1,7 +1,9 @@
+pub mod dcel;
 mod directed_edge;
 mod intersection_set;
 mod line;
 mod line_segment;
+mod mesh;
 pub(crate) mod point;
 pub mod polygon;
 mod triangle;
@@ -11,14 +13,17 @@ pub use directed_edge::*;
 pub use intersection_set::*;
 pub use line::*;
 pub use line_segment::*;
+pub use mesh::*;
 pub use triangle::*;
 
 // pub use crate::polygon::EdgeIter as testing;
 
+#[doc(inline)]
+pub use crate::data::dcel::{Dcel, FaceId, HalfEdgeId};
 #[doc(inline)]
 pub use crate::data::polygon::{
-  Cursor, DirectedIndexEdge, IndexEdge, PointId, Polygon, PolygonConvex, Position, PositionId,
-  RingId,
+  Cursor, DirectedIndexEdge, IndexEdge, MultiPolygon, PointId, Polygon, PolygonConvex, Position,
+  PositionId, RingId,
 };
 pub use crate::transformation::Transform;
 pub use point::Point;
@@ -0,0 +1,801 @@
+//! Doubly-connected edge list.
+//!
+//! A planar subdivision made of vertices, half-edges and faces. Every edge is
+//! split into two half-edges with opposite directions and each half-edge has
+//! the face it bounds on its left. The half-edges around a face are linked in
+//! counter-clockwise order (clockwise for the boundary of a hole).
+use num::{BigRational, Signed, Zero};
+use std::collections::BTreeMap;
+
+use super::{DirectedEdge, EndPoint, LineSegment, LineSegmentView, Point, PointId, Polygon};
+use crate::algorithms::intersection::shamos_hoey;
+use crate::algorithms::triangulation::Triangulation;
+use crate::{Error, Intersects, PolygonScalar};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HalfEdgeId(usize);
+
+impl From<HalfEdgeId> for usize {
+  fn from(eid: HalfEdgeId) -> usize {
+    eid.0
+  }
+}
+
+impl HalfEdgeId {
+  pub fn usize(self) -> usize {
+    self.0
+  }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FaceId(usize);
+
+impl From<FaceId> for usize {
+  fn from(fid: FaceId) -> usize {
+    fid.0
+  }
+}
+
+impl FaceId {
+  pub fn usize(self) -> usize {
+    self.0
+  }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct HalfEdge {
+  pub(crate) origin: PointId,
+  pub(crate) next: HalfEdgeId,
+  pub(crate) prev: HalfEdgeId,
+  pub(crate) face: FaceId,
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct Face {
+  // None for the unbounded face.
+  pub(crate) outer: Option<HalfEdgeId>,
+  // One half-edge for each hole.
+  pub(crate) inner: Vec<HalfEdgeId>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Dcel<T> {
+  // Key: PointId
+  pub(crate) points: Vec<Point<T>>,
+  // Key: PointId
+  // Any half-edge leaving the vertex. None for isolated vertices.
+  pub(crate) incident: Vec<Option<HalfEdgeId>>,
+  // Key: HalfEdgeId
+  // Half-edges come in pairs: 2k and 2k+1 are twins.
+  pub(crate) half_edges: Vec<HalfEdge>,
+  // Key: FaceId
+  // Face 0 is the unbounded face.
+  pub(crate) faces: Vec<Face>,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Dcel
+
+impl<T> Dcel<T> {
+  /// Planar subdivision formed by the boundary and holes of a polygon. Vertices
+  /// keep their `PointId`s from the polygon.
+  ///
+  /// The result has a face for the inside of the polygon, a face for each hole
+  /// and the unbounded face.
+  ///
+  /// # Time complexity
+  /// $O(n \log n + h n)$ where $h$ is the number of holes.
+  pub fn from_polygon(poly: &Polygon<T>) -> Dcel<T>
+  where
+    T: PolygonScalar,
+  {
+    let edges = poly
+      .rings
+      .iter()
+      .flat_map(|ring| (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()])))
+      .collect();
+    Dcel::build(poly.points.clone(), edges)
+  }
+
+  /// Planar subdivision formed by the edges of a triangulation. Vertices keep
+  /// their `PointId`s and face `i + 1` is triangle `i`. Bounded faces that
+  /// aren't covered by any triangle, such as holes in a polygon, come after
+  /// the triangles.
+  ///
+  /// # Time complexity
+  /// $O(n \log n + h n)$ where $h$ is the number of holes.
+  pub fn from_triangulation(points: Vec<Point<T>>, trig: &Triangulation) -> Dcel<T>
+  where
+    T: PolygonScalar,
+  {
+    let mut edges: Vec<(PointId, PointId)> = trig
+      .iter()
+      .flat_map(|(a, b, c)| [(a, b), (b, c), (c, a)])
+      .map(|(a, b)| (a.min(b), a.max(b)))
+      .collect();
+    edges.sort_unstable();
+    edges.dedup();
+    let mut dcel = Dcel::build(points, edges);
+
+    // Renumber the faces so they line up with the triangles.
+    let mut order: Vec<Option<usize>> = vec![None; dcel.faces.len()];
+    order[0] = Some(0);
+    for (idx, (a, b, _)) in trig.iter().enumerate() {
+      let eid = dcel
+        .iter_outgoing(a)
+        .find(|&eid| dcel.target(eid) == b)
+        .expect("triangle edges are in the subdivision");
+      order[dcel.face(eid).0] = Some(idx + 1);
+    }
+    let mut rest = trig.len() + 1..;
+    let order: Vec<usize> = order
+      .into_iter()
+      .map(|new| new.unwrap_or_else(|| rest.next().unwrap()))
+      .collect();
+    let mut faces = vec![None; dcel.faces.len()];
+    for (old, face) in dcel.faces.drain(..).enumerate() {
+      faces[order[old]] = Some(face);
+    }
+    dcel.faces = faces.into_iter().map(Option::unwrap).collect();
+    for half_edge in dcel.half_edges.iter_mut() {
+      half_edge.face = FaceId(order[half_edge.face.0]);
+    }
+    dcel
+  }
+
+  /// Planar subdivision formed by a set of line segments. Segments may only
+  /// touch at their endpoints. Duplicate endpoints are merged into a single
+  /// vertex and zero-length segments are ignored. Whether endpoints are
+  /// inclusive or exclusive makes no difference.
+  ///
+  /// # Errors
+  /// Returns [`Error::SelfIntersections`] if two segments cross, overlap or if
+  /// the endpoint of one segment touches the interior of another.
+  ///
+  /// # Time complexity
+  /// $O(n \log n + c n)$ where $c$ is the number of connected components.
+  pub fn from_segments(segments: &[LineSegment<T>]) -> Result<Dcel<T>, Error>
+  where
+    T: PolygonScalar,
+  {
+    let dcel = Dcel::from_segments_unchecked(segments);
+    dcel.validate()?;
+    Ok(dcel)
+  }
+
+  /// Assume that the segments only touch at their endpoints.
+  ///
+  /// # Time complexity
+  /// $O(n \log n + c n)$ where $c$ is the number of connected components.
+  pub fn from_segments_unchecked(segments: &[LineSegment<T>]) -> Dcel<T>
+  where
+    T: PolygonScalar,
+  {
+    let mut points = Vec::new();
+    let mut ids: BTreeMap<&Point<T>, PointId> = BTreeMap::new();
+    let mut edges: Vec<(PointId, PointId)> = Vec::new();
+    for segment in segments {
+      let [a, b] = [segment.min.inner(), segment.max.inner()].map(|pt| {
+        *ids.entry(pt).or_insert_with(|| {
+          points.push(pt.clone());
+          PointId(points.len() - 1)
+        })
+      });
+      if a != b {
+        edges.push((a, b));
+      }
+    }
+    edges.sort_unstable();
+    edges.dedup();
+    Dcel::build(points, edges)
+  }
+
+  /// Check that no two edges intersect except at shared endpoints.
+  ///
+  /// # Time complexity
+  /// $O(n \log n)$
+  pub fn validate(&self) -> Result<(), Error>
+  where
+    T: PolygonScalar,
+  {
+    let edges: Vec<HalfEdgeId> = self.iter_half_edges().step_by(2).collect();
+    let segments: Vec<LineSegmentView<'_, T>> = edges.iter().map(|&eid| self.view(eid)).collect();
+    match shamos_hoey::sweep(&segments, |a, b| self.edges_interfere(edges[a], edges[b])) {
+      Some(_) => Err(Error::SelfIntersections),
+      None => Ok(()),
+    }
+  }
+
+  fn view(&self, eid: HalfEdgeId) -> LineSegmentView<'_, T>
+  where
+    T: PolygonScalar,
+  {
+    LineSegmentView::new(
+      EndPoint::Inclusive(self.point(self.origin(eid))),
+      EndPoint::Inclusive(self.point(self.target(eid))),
+    )
+  }
+
+  fn edges_interfere(&self, a: HalfEdgeId, b: HalfEdgeId) -> bool
+  where
+    T: PolygonScalar,
+  {
+    let (a0, a1) = (self.origin(a), self.target(a));
+    let (b0, b1) = (self.origin(b), self.target(b));
+    let shared = if a0 == b0 {
+      Some((a0, a1, b1))
+    } else if a0 == b1 {
+      Some((a0, a1, b0))
+    } else if a1 == b0 {
+      Some((a1, a0, b1))
+    } else if a1 == b1 {
+      Some((a1, a0, b0))
+    } else {
+      None
+    };
+    match shared {
+      // Edges with a common endpoint only interfere if they overlap.
+      Some((p, q, r)) => {
+        let (p, q, r) = (self.point(p), self.point(q), self.point(r));
+        Point::orient(p, q, r).is_colinear() && (p < q) == (p < r)
+      }
+      None => self.view(a).intersect(self.view(b)).is_some(),
+    }
+  }
+
+  // Link half-edges around vertices and trace faces. The edges must not
+  // intersect except at shared endpoints.
+  fn build(points: Vec<Point<T>>, edges: Vec<(PointId, PointId)>) -> Dcel<T>
+  where
+    T: PolygonScalar,
+  {
+    let mut dcel = Dcel {
+      incident: vec![None; points.len()],
+      points,
+      half_edges: Vec::with_capacity(edges.len() * 2),
+      faces: vec![Face {
+        outer: None,
+        inner: Vec::new(),
+      }],
+    };
+    let unset = HalfEdgeId(usize::MAX);
+    let mut outgoing = vec![Vec::new(); dcel.points.len()];
+    for (a, b) in edges {
+      for origin in [a, b] {
+        let eid = HalfEdgeId(dcel.half_edges.len());
+        dcel.half_edges.push(HalfEdge {
+          origin,
+          next: unset,
+          prev: unset,
+          face: FaceId(0),
+        });
+        outgoing[origin.0].push(eid);
+        dcel.incident[origin.0] = Some(eid);
+      }
+    }
+
+    // The face on the left of an incoming edge continues with the next
+    // outgoing edge in clockwise order.
+    for (vertex, around) in outgoing.iter_mut().enumerate() {
+      let origin = &dcel.points[vertex];
+      around.sort_by(|&a, &b| {
+        let (pa, pb) = (dcel.point(dcel.target(a)), dcel.point(dcel.target(b)));
+        origin.ccw_cmp_around(pa, pb)
+      });
+      for (i, &out) in around.iter().enumerate() {
+        let cw = around[(i + around.len() - 1) % around.len()];
+        let incoming = dcel.twin(out);
+        dcel.half_edges[incoming.0].next = cw;
+        dcel.half_edges[cw.0].prev = incoming;
+      }
+    }
+
+    // Every cycle is either the outer boundary of a bounded face (positive
+    // area) or the boundary of a hole in some face.
+    let mut cycles = Vec::new();
+    let mut seen = vec![false; dcel.half_edges.len()];
+    for start in 0..dcel.half_edges.len() {
+      if !seen[start] {
+        let cycle: Vec<HalfEdgeId> = dcel.iter_cycle(HalfEdgeId(start)).collect();
+        for eid in &cycle {
+          seen[eid.0] = true;
+        }
+        let area = dcel.cycle_area_2x(&cycle);
+        cycles.push((cycle, area));
+      }
+    }
+    let mut bounded: Vec<(&[HalfEdgeId], &BigRational, FaceId)> = Vec::new();
+    for (cycle, area) in &cycles {
+      if area.is_positive() {
+        let face = FaceId(dcel.faces.len());
+        dcel.faces.push(Face {
+          outer: Some(cycle[0]),
+          inner: Vec::new(),
+        });
+        dcel.set_face(cycle, face);
+        bounded.push((cycle, area, face));
+      }
+    }
+    for (cycle, area) in &cycles {
+      if !area.is_positive() {
+        // The hole is inside the smallest bounded face that surrounds it. Faces
+        // that touch the hole belong to the same component and can't contain
+        // it.
+        let vertex = dcel.origin(cycle[0]);
+        let pt = dcel.point(vertex);
+        let face = bounded
+          .iter()
+          .filter(|(other, _, _)| other.iter().all(|&eid| dcel.origin(eid) != vertex))
+          .filter(|(other, _, _)| dcel.winding_number(other, pt) != 0)
+          .min_by(|(_, a, _), (_, b, _)| a.cmp(b))
+          .map_or(FaceId(0), |&(_, _, face)| face);
+        dcel.faces[face.0].inner.push(cycle[0]);
+        dcel.set_face(cycle, face);
+      }
+    }
+    dcel
+  }
+
+  fn set_face(&mut self, cycle: &[HalfEdgeId], face: FaceId) {
+    for eid in cycle {
+      self.half_edges[eid.0].face = face;
+    }
+  }
+
+  fn cycle_area_2x(&self, cycle: &[HalfEdgeId]) -> BigRational
+  where
+    T: PolygonScalar,
+  {
+    cycle.iter().fold(BigRational::zero(), |acc, &eid| {
+      let p = self.point(self.origin(eid));
+      let q = self.point(self.target(eid));
+      acc + p.array[0].to_rational() * q.array[1].to_rational()
+        - q.array[0].to_rational() * p.array[1].to_rational()
+    })
+  }
+
+  // Winding number of a cycle around a point that isn't on the cycle.
+  fn winding_number(&self, cycle: &[HalfEdgeId], pt: &Point<T>) -> isize
+  where
+    T: PolygonScalar,
+  {
+    let mut winding = 0;
+    for &eid in cycle {
+      let p = self.point(self.origin(eid));
+      let q = self.point(self.target(eid));
+      if p.array[1] <= pt.array[1] {
+        if q.array[1] > pt.array[1] && Point::orient(p, q, pt).is_ccw() {
+          winding += 1;
+        }
+      } else if q.array[1] <= pt.array[1] && Point::orient(p, q, pt).is_cw() {
+        winding -= 1;
+      }
+    }
+    winding
+  }
+
+  pub fn point(&self, idx: PointId) -> &Point<T> {
+    &self.points[idx.0]
+  }
+
+  /// Transform the coordinates of every vertex. The topology is kept as is.
+  pub fn map<U, F>(self, f: F) -> Dcel<U>
+  where
+    T: Clone,
+    U: Clone,
+    F: Fn(T) -> U + Clone,
+  {
+    Dcel {
+      points: self.points.into_iter().map(|p| p.map(f.clone())).collect(),
+      incident: self.incident,
+      half_edges: self.half_edges,
+      faces: self.faces,
+    }
+  }
+
+  /// Number of vertices, including isolated vertices.
+  pub fn vertex_count(&self) -> usize {
+    self.points.len()
+  }
+
+  pub fn half_edge_count(&self) -> usize {
+    self.half_edges.len()
+  }
+
+  pub fn face_count(&self) -> usize {
+    self.faces.len()
+  }
+
+  pub fn iter_vertices(&self) -> impl Iterator<Item = PointId> {
+    (0..self.points.len()).map(PointId)
+  }
+
+  pub fn iter_half_edges(&self) -> impl Iterator<Item = HalfEdgeId> {
+    (0..self.half_edges.len()).map(HalfEdgeId)
+  }
+
+  pub fn iter_faces(&self) -> impl Iterator<Item = FaceId> {
+    (0..self.faces.len()).map(FaceId)
+  }
+
+  pub fn origin(&self, eid: HalfEdgeId) -> PointId {
+    self.half_edges[eid.0].origin
+  }
+
+  pub fn target(&self, eid: HalfEdgeId) -> PointId {
+    self.origin(self.twin(eid))
+  }
+
+  /// Half-edge with the opposite direction.
+  pub fn twin(&self, eid: HalfEdgeId) -> HalfEdgeId {
+    HalfEdgeId(eid.0 ^ 1)
+  }
+
+  /// Next half-edge around the face on the left.
+  pub fn next(&self, eid: HalfEdgeId) -> HalfEdgeId {
+    self.half_edges[eid.0].next
+  }
+
+  /// Previous half-edge around the face on the left.
+  pub fn prev(&self, eid: HalfEdgeId) -> HalfEdgeId {
+    self.half_edges[eid.0].prev
+  }
+
+  /// Face on the left of a half-edge.
+  pub fn face(&self, eid: HalfEdgeId) -> FaceId {
+    self.half_edges[eid.0].face
+  }
+
+  pub fn edge(&self, eid: HalfEdgeId) -> DirectedEdge<'_, T>
+  where
+    T: PolygonScalar,
+  {
+    DirectedEdge {
+      src: self.point(self.origin(eid)),
+      dst: self.point(self.target(eid)),
+    }
+  }
+
+  pub fn unbounded_face(&self) -> FaceId {
+    FaceId(0)
+  }
+
+  /// A half-edge on the outer boundary of a face. `None` for the unbounded
+  /// face.
+  pub fn outer_component(&self, fid: FaceId) -> Option<HalfEdgeId> {
+    self.faces[fid.0].outer
+  }
+
+  /// A half-edge on the boundary of each hole in a face.
+  pub fn inner_components(&self, fid: FaceId) -> &[HalfEdgeId] {
+    &self.faces[fid.0].inner
+  }
+
+  /// Half-edges on the outer boundary of a face in counter-clockwise order.
+  /// Empty for the unbounded face.
+  pub fn iter_boundary(&self, fid: FaceId) -> impl Iterator<Item = HalfEdgeId> + '_ {
+    self
+      .outer_component(fid)
+      .into_iter()
+      .flat_map(move |start| self.iter_cycle(start))
+  }
+
+  /// Follow `next` pointers until getting back to `start`.
+  pub fn iter_cycle(&self, start: HalfEdgeId) -> impl Iterator<Item = HalfEdgeId> + '_ {
+    let mut focus = Some(start);
+    std::iter::from_fn(move || {
+      let eid = focus?;
+      let next = self.next(eid);
+      focus = if next == start { None } else { Some(next) };
+      Some(eid)
+    })
+  }
+
+  /// Half-edges leaving a vertex in counter-clockwise order.
+  pub fn iter_outgoing(&self, vertex: PointId) -> impl Iterator<Item = HalfEdgeId> + '_ {
+    let mut focus = self.incident[vertex.0];
+    let start = focus;
+    std::iter::from_fn(move || {
+      let eid = focus?;
+      focus = Some(self.twin(self.prev(eid))).filter(|&n| Some(n) != start);
+      Some(eid)
+    })
+  }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::testing::square;
+
+  use proptest::collection::vec;
+  use test_strategy::proptest;
+
+  fn check_dcel<T: PolygonScalar>(dcel: &Dcel<T>) {
+    for eid in dcel.iter_half_edges() {
+      assert_eq!(dcel.twin(dcel.twin(eid)), eid);
+      assert_eq!(dcel.prev(dcel.next(eid)), eid);
+      assert_eq!(dcel.target(eid), dcel.origin(dcel.next(eid)));
+      assert_eq!(dcel.face(eid), dcel.face(dcel.next(eid)));
+    }
+    for fid in dcel.iter_faces() {
+      for eid in dcel.iter_boundary(fid) {
+        assert_eq!(dcel.face(eid), fid);
+      }
+      for &start in dcel.inner_components(fid) {
+        assert!(dcel.iter_cycle(start).all(|eid| dcel.face(eid) == fid));
+      }
+    }
+    for vertex in dcel.iter_vertices() {
+      let count = dcel.iter_outgoing(vertex).count();
+      let expected = dcel
+        .iter_half_edges()
+        .filter(|&eid| dcel.origin(eid) == vertex)
+        .count();
+      assert_eq!(count, expected);
+    }
+  }
+
+  type Segment = ((i32, i32), (i32, i32));
+
+  fn segments(coords: &[Segment]) -> Vec<LineSegment<i32>> {
+    coords
+      .iter()
+      .map(|&(a, b)| LineSegment::from(a..=b))
+      .collect()
+  }
+
+  fn boundary(poly: Polygon<i32>) -> Vec<Segment> {
+    let corner = |pt: &Point<i32>| (pt.array[0], pt.array[1]);
+    let n = poly.points.len();
+    (0..n)
+      .map(|i| (corner(&poly.points[i]), corner(&poly.points[(i + 1) % n])))
+      .collect()
+  }
+
+  #[test]
+  fn polygon_faces() {
+    let poly = Polygon::new(vec![
+      Point::new([0, 0]),
+      Point::new([2, 0]),
+      Point::new([2, 2]),
+      Point::new([0, 2]),
+    ])
+    .unwrap();
+    let dcel = Dcel::from_polygon(&poly);
+    check_dcel(&dcel);
+    assert_eq!(dcel.face_count(), 2);
+    assert_eq!(dcel.half_edge_count(), 8);
+    let inside = FaceId(1);
+    let boundary: Vec<PointId> = dcel.iter_boundary(inside).map(|e| dcel.origin(e)).collect();
+    assert_eq!(boundary.len(), 4);
+    // Same vertex ids as the polygon, in counter-clockwise order.
+    let start = boundary[0].usize();
+    for (i, pid) in boundary.iter().enumerate() {
+      assert_eq!(pid.usize(), (start + i) % 4);
+    }
+    assert_eq!(dcel.inner_components(dcel.unbounded_face()).len(), 1);
+  }
+
+  #[test]
+  fn polygon_with_hole() {
+    let poly = Polygon::new_with_holes(
+      vec![
+        Point::new([0, 0]),
+        Point::new([6, 0]),
+        Point::new([6, 6]),
+        Point::new([0, 6]),
+      ],
+      vec![vec![
+        Point::new([2, 2]),
+        Point::new([2, 4]),
+        Point::new([4, 4]),
+        Point::new([4, 2]),
+      ]],
+    )
+    .unwrap();
+    let dcel = Dcel::from_polygon(&poly);
+    check_dcel(&dcel);
+    // Unbounded face, polygon interior and the hole.
+    assert_eq!(dcel.face_count(), 3);
+    let with_hole: Vec<FaceId> = dcel
+      .iter_faces()
+      .filter(|&f| dcel.outer_component(f).is_some() && !dcel.inner_components(f).is_empty())
+      .collect();
+    assert_eq!(with_hole.len(), 1);
+    let outer = dcel.outer_component(with_hole[0]).unwrap();
+    assert_eq!(dcel.iter_cycle(outer).count(), 4);
+  }
+
+  #[test]
+  fn ring_not_starting_at_lowest_vertex() {
+    // Winding numbers around the first vertex of a ring would count the ring
+    // itself.
+    let poly = Polygon::new_with_holes(
+      vec![
+        Point::new([0, 6]),
+        Point::new([0, 0]),
+        Point::new([6, 0]),
+        Point::new([6, 6]),
+      ],
+      vec![vec![
+        Point::new([4, 4]),
+        Point::new([4, 2]),
+        Point::new([2, 2]),
+        Point::new([2, 4]),
+      ]],
+    )
+    .unwrap();
+    let dcel = Dcel::from_polygon(&poly);
+    check_dcel(&dcel);
+    assert_eq!(dcel.face_count(), 3);
+    let unbounded = dcel.inner_components(dcel.unbounded_face());
+    assert_eq!(unbounded.len(), 1);
+    assert_eq!(dcel.iter_cycle(unbounded[0]).count(), 4);
+    let inside = dcel.face(dcel.twin(unbounded[0]));
+    assert_eq!(dcel.inner_components(inside).len(), 1);
+
+    let coords = [
+      ((0, 2), (0, 0)),
+      ((0, 0), (2, 0)),
+      ((2, 0), (2, 2)),
+      ((2, 2), (0, 2)),
+    ];
+    let dcel = Dcel::from_segments(&segments(&coords)).unwrap();
+    check_dcel(&dcel);
+    assert_eq!(dcel.face_count(), 2);
+    assert_eq!(dcel.inner_components(dcel.unbounded_face()).len(), 1);
+  }
+
+  #[test]
+  fn nested_squares() {
+    let mut coords = boundary(square(0, 0, 10));
+    coords.extend(boundary(square(2, 2, 2)));
+    coords.extend(boundary(square(6, 6, 2)));
+    coords.extend(boundary(square(20, 0, 2)));
+    let dcel = Dcel::from_segments(&segments(&coords)).unwrap();
+    check_dcel(&dcel);
+    assert_eq!(dcel.face_count(), 5);
+    // Two components are directly inside the unbounded face.
+    assert_eq!(dcel.inner_components(dcel.unbounded_face()).len(), 2);
+    let big = dcel
+      .iter_faces()
+      .find(|&f| f != dcel.unbounded_face() && dcel.inner_components(f).len() == 2)
+      .expect("large square has two holes");
+    assert_eq!(dcel.iter_boundary(big).count(), 4);
+  }
+
+  #[test]
+  fn tree() {
+    // A star has a single face and every edge has the same face on both sides.
+    let coords = [
+      ((0, 0), (1, 0)),
+      ((0, 0), (0, 1)),
+      ((0, 0), (-1, 0)),
+      ((0, 0), (0, -1)),
+      ((1, 0), (2, 1)),
+    ];
+    let dcel = Dcel::from_segments(&segments(&coords)).unwrap();
+    check_dcel(&dcel);
+    assert_eq!(dcel.face_count(), 1);
+    assert_eq!(dcel.vertex_count(), 6);
+    let inner = dcel.inner_components(dcel.unbounded_face());
+    assert_eq!(inner.len(), 1);
+    assert_eq!(dcel.iter_cycle(inner[0]).count(), 10);
+    assert_eq!(dcel.iter_outgoing(PointId(0)).count(), 4);
+  }
+
+  #[test]
+  fn triangulation_faces() {
+    let points = vec![
+      Point::new([0, 0]),
+      Point::new([14, 0]),
+      Point::new([0, 1]),
+      Point::new([14, 1]),
+    ];
+    let trig = Triangulation::from_triangles(vec![
+      [PointId(1), PointId(3), PointId(2)],
+      [PointId(0), PointId(1), PointId(2)],
+    ]);
+    let dcel = Dcel::from_triangulation(points, &trig);
+    check_dcel(&dcel);
+    assert_eq!(dcel.face_count(), 3);
+    assert_eq!(dcel.inner_components(dcel.unbounded_face()).len(), 1);
+    for (idx, (a, b, c)) in trig.iter().enumerate() {
+      let boundary: Vec<PointId> = dcel
+        .iter_boundary(FaceId(idx + 1))
+        .map(|e| dcel.origin(e))
+        .collect();
+      assert_eq!(boundary.len(), 3);
+      assert!([a, b, c].iter().all(|v| boundary.contains(v)));
+    }
+  }
+
+  #[test]
+  fn outer_cycle_touching_faces() {
+    // The outer cycle shares every vertex with the two triangles. Neither
+    // triangle may be picked as the face around it, whichever vertex the cycle
+    // starts at.
+    for diagonal in [((0, 0), (4, 4)), ((4, 0), (0, 4))] {
+      for rotation in 0..4 {
+        let mut coords = boundary(square(0, 0, 4));
+        coords.rotate_left(rotation);
+        coords.push(diagonal);
+        let dcel = Dcel::from_segments(&segments(&coords)).unwrap();
+        check_dcel(&dcel);
+        assert_eq!(dcel.face_count(), 3);
+        let unbounded = dcel.unbounded_face();
+        assert_eq!(dcel.inner_components(unbounded).len(), 1);
+        for fid in dcel.iter_faces().filter(|&fid| fid != unbounded) {
+          assert!(dcel.inner_components(fid).is_empty());
+        }
+      }
+    }
+  }
+
+  #[test]
+  fn dangling_edge_in_face() {
+    let mut coords = boundary(square(0, 0, 4));
+    coords.push(((0, 0), (2, 2)));
+    coords.push(((1, 3), (2, 3)));
+    let dcel = Dcel::from_segments(&segments(&coords)).unwrap();
+    check_dcel(&dcel);
+    assert_eq!(dcel.face_count(), 2);
+    let inside = FaceId(1);
+    assert_eq!(dcel.iter_boundary(inside).count(), 6);
+    assert_eq!(dcel.inner_components(inside).len(), 1);
+  }
+
+  #[test]
+  fn invalid_segments() {
+    let crossing = segments(&[((0, 0), (2, 2)), ((0, 2), (2, 0))]);
+    assert_eq!(
+      Dcel::from_segments(&crossing).err(),
+      Some(Error::SelfIntersections)
+    );
+    let touching = segments(&[((0, 0), (2, 0)), ((1, 0), (1, 2))]);
+    assert_eq!(
+      Dcel::from_segments(&touching).err(),
+      Some(Error::SelfIntersections)
+    );
+    let overlapping = segments(&[((0, 0), (2, 0)), ((0, 0), (3, 0))]);
+    assert_eq!(
+      Dcel::from_segments(&overlapping).err(),
+      Some(Error::SelfIntersections)
+    );
+    let shared = segments(&[((0, 0), (2, 0)), ((0, 0), (-3, 0)), ((0, 0), (2, 0))]);
+    assert!(Dcel::from_segments(&shared).is_ok());
+  }
+
+  #[proptest]
+  fn validate_prop(
+    #[strategy(vec(((-4i8..4, -4i8..4), (-4i8..4, -4i8..4)), 0..12))] coords: Vec<(
+      (i8, i8),
+      (i8, i8),
+    )>,
+  ) {
+    let segments: Vec<LineSegment<i8>> = coords
+      .iter()
+      .map(|&(a, b)| LineSegment::from(a..=b))
+      .collect();
+    let dcel = Dcel::from_segments_unchecked(&segments);
+    let edges: Vec<HalfEdgeId> = dcel.iter_half_edges().step_by(2).collect();
+    let expected = edges
+      .iter()
+      .enumerate()
+      .any(|(i, &a)| edges[..i].iter().any(|&b| dcel.edges_interfere(a, b)));
+    assert_eq!(dcel.validate().is_err(), expected);
+  }
+
+  #[proptest]
+  fn polygon_prop(poly: Polygon<i8>) {
+    let dcel = Dcel::from_polygon(&poly);
+    check_dcel(&dcel);
+    assert_eq!(dcel.face_count(), 2);
+    assert!(dcel.validate().is_ok());
+  }
+}
@@ -176,6 +176,16 @@ impl<T: TotalOrd, const N: usize> From<RangeInclusive<Point<T, N>>> for LineSegm
   }
 }
 
+impl<T: TotalOrd> From<RangeInclusive<(T, T)>> for LineSegment<T> {
+  fn from(range: RangeInclusive<(T, T)>) -> LineSegment<T> {
+    let (start, end) = range.into_inner();
+    LineSegment::new(
+      EndPoint::Inclusive(start.into()),
+      EndPoint::Inclusive(end.into()),
+    )
+  }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 // LineSegmentView
 
@@ -227,6 +237,12 @@ impl<'a, T: TotalOrd> LineSegmentView<'a, T> {
   }
 }
 
+impl<'a, T: TotalOrd, const N: usize> From<&'a LineSegment<T, N>> for LineSegmentView<'a, T, N> {
+  fn from(segment: &'a LineSegment<T, N>) -> LineSegmentView<'a, T, N> {
+    segment.as_ref()
+  }
+}
+
 impl<'a, T: TotalOrd, const N: usize> From<&'a Range<Point<T, N>>> for LineSegmentView<'a, T, N> {
   fn from(range: &'a Range<Point<T, N>>) -> LineSegmentView<'a, T, N> {
     LineSegmentView::new(
@@ -0,0 +1,19 @@
+use super::Point;
+
+/// Triangle mesh in three dimensions. Faces index into the list of points and
+/// their vertices are in counter-clockwise order when seen from outside.
+#[derive(Debug, Clone)]
+pub struct TriangleMesh<T> {
+  pub points: Vec<Point<T, 3>>,
+  pub faces: Vec<[usize; 3]>,
+}
+
+impl<T> TriangleMesh<T> {
+  /// Vertices of each face.
+  pub fn triangles(&self) -> impl Iterator<Item = [&Point<T, 3>; 3]> + '_ {
+    self
+      .faces
+      .iter()
+      .map(move |&[a, b, c]| [&self.points[a], &self.points[b], &self.points[c]])
+  }
+}
@@ -300,6 +300,64 @@ impl<T: PolygonScalar> Point<T> {
     Orientation::along_perp_vector(p1, vector, p2)
   }
 
+  /// Determine whether `p4` lies inside the circle through `p1`, `p2` and `p3`.
+  /// The result is `Greater` if `p4` is inside, `Less` if it is outside and
+  /// `Equal` if all four points are cocircular. If `p1`, `p2` and `p3` are
+  /// clockwise, the result is reversed.
+  ///
+  /// For fixed-precision types (i8,i16,i32,i64,etc), this function is
+  /// guaranteed to work for any input and never cause any arithmetic overflows.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use rgeometry::data::Point;
+  /// # use std::cmp::Ordering;
+  /// let p1 = Point::new([ 1, 0 ]);
+  /// let p2 = Point::new([ 0, 1 ]);
+  /// let p3 = Point::new([ -1, 0 ]);
+  /// assert_eq!(Point::incircle(&p1, &p2, &p3, &Point::new([ 0, 0 ])), Ordering::Greater);
+  /// assert_eq!(Point::incircle(&p1, &p2, &p3, &Point::new([ 0, -1 ])), Ordering::Equal);
+  /// assert_eq!(Point::incircle(&p1, &p2, &p3, &Point::new([ 2, 2 ])), Ordering::Less);
+  /// ```
+  pub fn incircle(
+    p1: &Point<T, 2>,
+    p2: &Point<T, 2>,
+    p3: &Point<T, 2>,
+    p4: &Point<T, 2>,
+  ) -> Ordering {
+    T::incircle(p1, p2, p3, p4)
+  }
+
+  /// In-circle test with ties broken by Simulation of Simplicity. Each point is
+  /// raised on the lifting paraboloid by an infinitesimal amount and points with
+  /// lower ids are raised by (infinitely) larger amounts. The ids must be
+  /// distinct. The result is only `Equal` if all four points are colinear.
+  pub fn incircle_sos(points: [&Point<T, 2>; 4], ids: [usize; 4]) -> Ordering {
+    let [p1, p2, p3, p4] = points;
+    match Point::incircle(p1, p2, p3, p4) {
+      Ordering::Equal => {
+        // Raising a point changes the determinant by its cofactor.
+        let mut cofactors = [
+          (ids[0], Point::orient(p2, p3, p4)),
+          (ids[1], Point::orient(p1, p3, p4).reverse()),
+          (ids[2], Point::orient(p1, p2, p4)),
+          (ids[3], Point::orient(p1, p2, p3).reverse()),
+        ];
+        cofactors.sort_by_key(|&(id, _)| id);
+        cofactors
+          .iter()
+          .find_map(|&(_, orient)| match orient {
+            Orientation::CounterClockWise => Some(Ordering::Greater),
+            Orientation::ClockWise => Some(Ordering::Less),
+            Orientation::CoLinear => None,
+          })
+          .unwrap_or(Ordering::Equal)
+      }
+      ord => ord,
+    }
+  }
+
   pub fn all_colinear(pts: &[Point<T>]) -> bool {
     if pts.len() < 3 {
       return true;
@@ -324,6 +382,38 @@ impl<T: PolygonScalar> Point<T> {
   }
 }
 
+// Methods on three-dimensional points.
+impl<T: PolygonScalar> Point<T, 3> {
+  /// Determine on which side of the plane through `p1`, `p2` and `p3` the point
+  /// `p4` lies. The result is `Greater` if `p1`, `p2` and `p3` appear clockwise
+  /// when seen from `p4`, `Less` if they appear counter-clockwise and `Equal`
+  /// if all four points are coplanar.
+  ///
+  /// For fixed-precision types (i8,i16,i32,i64,etc), this function is
+  /// guaranteed to work for any input and never cause any arithmetic overflows.
+  ///
+  /// # Examples
+  ///
+  /// ```rust
+  /// # use rgeometry::data::Point;
+  /// # use std::cmp::Ordering;
+  /// let p1 = Point::new([ 0, 0, 0 ]);
+  /// let p2 = Point::new([ 1, 0, 0 ]);
+  /// let p3 = Point::new([ 0, 1, 0 ]);
+  /// assert_eq!(Point::orient3d(&p1, &p2, &p3, &Point::new([ 0, 0, -1 ])), Ordering::Greater);
+  /// assert_eq!(Point::orient3d(&p1, &p2, &p3, &Point::new([ 5, 5, 0 ])), Ordering::Equal);
+  /// assert_eq!(Point::orient3d(&p1, &p2, &p3, &Point::new([ 0, 0, 1 ])), Ordering::Less);
+  /// ```
+  pub fn orient3d(
+    p1: &Point<T, 3>,
+    p2: &Point<T, 3>,
+    p3: &Point<T, 3>,
+    p4: &Point<T, 3>,
+  ) -> Ordering {
+    T::orient3d(p1, p2, p3, p4)
+  }
+}
+
 // FIXME: Use a macro
 impl<T> Point<T, 1> {
   pub fn x_coord(&self) -> &T {
@@ -456,6 +546,123 @@ pub mod tests {
     )
   }
 
+  // i8 and BigInt use different algorithms for the in-circle test but the
+  // results should be identical.
+  #[proptest]
+  fn incircle_bigint_i8_prop(pts: [Point<i8, 2>; 4]) {
+    let [p1, p2, p3, p4] = pts.map(|pt| pt.cast::<BigInt>());
+    prop_assert_eq!(
+      Point::incircle(&pts[0], &pts[1], &pts[2], &pts[3]),
+      Point::incircle(&p1, &p2, &p3, &p4)
+    )
+  }
+
+  // i8 and f64 use different algorithms for the in-circle test but the
+  // results should be identical.
+  #[proptest]
+  fn incircle_f64_i8_prop(pts: [Point<i8, 2>; 4]) {
+    let [p1, p2, p3, p4]: [Point<OrderedFloat<f64>, 2>; 4] = pts.map(|pt| pt.to_float());
+    prop_assert_eq!(
+      Point::incircle(&pts[0], &pts[1], &pts[2], &pts[3]),
+      Point::incircle(&p1, &p2, &p3, &p4)
+    )
+  }
+
+  #[proptest]
+  fn incircle_i64_no_overflow(pts: [Point<i64, 2>; 4]) {
+    let [p1, p2, p3, p4] = pts.clone().map(|pt| pt.cast::<BigInt>());
+    prop_assert_eq!(
+      Point::incircle(&pts[0], &pts[1], &pts[2], &pts[3]),
+      Point::incircle(&p1, &p2, &p3, &p4)
+    )
+  }
+
+  // i8 and BigInt use different algorithms for the 3D orientation test but
+  // the results should be identical.
+  #[proptest]
+  fn orient3d_bigint_i8_prop(pts: [Point<i8, 3>; 4]) {
+    let [p1, p2, p3, p4] = pts.map(|pt| pt.cast::<BigInt>());
+    prop_assert_eq!(
+      Point::orient3d(&pts[0], &pts[1], &pts[2], &pts[3]),
+      Point::orient3d(&p1, &p2, &p3, &p4)
+    )
+  }
+
+  #[proptest]
+  fn orient3d_f64_i8_prop(pts: [Point<i8, 3>; 4]) {
+    let [p1, p2, p3, p4]: [Point<OrderedFloat<f64>, 3>; 4] = pts.map(|pt| pt.to_float());
+    prop_assert_eq!(
+      Point::orient3d(&pts[0], &pts[1], &pts[2], &pts[3]),
+      Point::orient3d(&p1, &p2, &p3, &p4)
+    )
+  }
+
+  #[proptest]
+  fn orient3d_i64_no_overflow(pts: [Point<i64, 3>; 4]) {
+    let [p1, p2, p3, p4] = pts.clone().map(|pt| pt.cast::<BigInt>());
+    prop_assert_eq!(
+      Point::orient3d(&pts[0], &pts[1], &pts[2], &pts[3]),
+      Point::orient3d(&p1, &p2, &p3, &p4)
+    )
+  }
+
+  #[proptest]
+  fn orient3d_swap(pts: [Point<i16, 3>; 4]) {
+    let [p1, p2, p3, p4] = &pts;
+    prop_assert_eq!(
+      Point::orient3d(p1, p2, p3, p4),
+      Point::orient3d(p2, p1, p3, p4).reverse()
+    )
+  }
+
+  #[proptest]
+  fn incircle_reverse(pts: [Point<i16, 2>; 4]) {
+    let [p1, p2, p3, p4] = &pts;
+    prop_assert_eq!(
+      Point::incircle(p1, p2, p3, p4),
+      Point::incircle(p3, p2, p1, p4).reverse()
+    )
+  }
+
+  #[test]
+  fn incircle_sos_cocircular() {
+    let square = [
+      Point::new([0, 0]),
+      Point::new([2, 0]),
+      Point::new([2, 2]),
+      Point::new([0, 2]),
+    ];
+    let [a, b, c, d] = &square;
+    assert_eq!(Point::incircle(a, b, c, d), Ordering::Equal);
+    let abc_d = Point::incircle_sos([a, b, c, d], [0, 1, 2, 3]);
+    let cda_b = Point::incircle_sos([c, d, a, b], [2, 3, 0, 1]);
+    let bcd_a = Point::incircle_sos([b, c, d, a], [1, 2, 3, 0]);
+    assert_ne!(abc_d, Ordering::Equal);
+    // Both triangles agree on whether 'ac' is a Delaunay edge and exactly one of
+    // the two diagonals is.
+    assert_eq!(abc_d, cda_b);
+    assert_eq!(abc_d, bcd_a.reverse());
+  }
+
+  #[proptest]
+  fn incircle_sos_prop(pts: [Point<i8, 2>; 4]) {
+    let [a, b, c, d] = &pts;
+    let sos = Point::incircle_sos([a, b, c, d], [0, 1, 2, 3]);
+    let exact = Point::incircle(a, b, c, d);
+    if exact != Ordering::Equal {
+      prop_assert_eq!(sos, exact);
+    }
+    if Point::all_colinear(&pts) {
+      prop_assert_eq!(sos, Ordering::Equal);
+    } else if pts
+      .iter()
+      .enumerate()
+      .all(|(i, p)| pts[..i].iter().all(|q| p != q))
+    {
+      prop_assert_ne!(sos, Ordering::Equal);
+    }
+  }
+
   #[proptest]
   fn orientation_reverse(pt1: Point<i64, 2>, pt2: Point<i64, 2>, pt3: Point<i64, 2>) {
     let abc = Point::orient(&pt1, &pt2, &pt3);
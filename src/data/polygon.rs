@@ -6,8 +6,10 @@ use std::iter::Sum;
 use std::ops::Bound::*;
 use std::ops::*;
 
+use crate::algorithms::intersection::shamos_hoey;
 use crate::data::{
-  DirectedEdge, HalfLineSoS, IHalfLineLineSegmentSoS::*, Point, PointLocation, TriangleView, Vector,
+  DirectedEdge, HalfLineSoS, IHalfLineLineSegmentSoS::*, LineSegmentView, Point, PointLocation,
+  TriangleView, Vector,
 };
 use crate::intersection::*;
 use crate::{Error, Orientation, PolygonScalar, TotalOrd};
@@ -18,6 +20,9 @@ pub use iter::*;
 mod convex;
 pub use convex::*;
 
+mod multi;
+pub use multi::*;
+
 use super::Transform;
 
 #[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
@@ -39,7 +44,7 @@ impl From<RingId> for usize {
 }
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct PointId(usize);
+pub struct PointId(pub(crate) usize);
 
 impl From<PointId> for usize {
   fn from(pid: PointId) -> usize {
@@ -141,7 +146,32 @@ impl<T> Polygon<T> {
     }
   }
 
-  /// $O(n^2)$
+  // The first ring is the boundary and must be ccw. The remaining rings are
+  // holes and must be cw.
+  pub(crate) fn new_rings_unchecked(rings: Vec<Vec<Point<T, 2>>>) -> Polygon<T> {
+    let mut points = Vec::new();
+    let mut ring_index = Vec::new();
+    let mut position_index = Vec::new();
+    let mut index_rings = Vec::new();
+    for (ring_id, ring) in rings.into_iter().enumerate() {
+      let mut index_ring = Vec::with_capacity(ring.len());
+      for (position_id, pt) in ring.into_iter().enumerate() {
+        index_ring.push(PointId(points.len()));
+        points.push(pt);
+        ring_index.push(RingId(ring_id));
+        position_index.push(PositionId(position_id));
+      }
+      index_rings.push(index_ring);
+    }
+    Polygon {
+      points,
+      ring_index,
+      position_index,
+      rings: index_rings,
+    }
+  }
+
+  /// $O(n \log n)$
   pub fn new(points: Vec<Point<T, 2>>) -> Result<Polygon<T>, Error>
   where
     T: PolygonScalar,
@@ -156,17 +186,63 @@ impl<T> Polygon<T> {
     Ok(p)
   }
 
+  /// Create a polygon with holes. Like [`Polygon::new`], the boundary is
+  /// reversed if it isn't counter-clockwise. The holes must be clockwise, lie
+  /// inside the boundary, and must not be nested inside each other. No two
+  /// rings may intersect, except that a hole may touch the boundary or another
+  /// hole at a shared vertex as long as the interior stays connected.
+  ///
+  /// # Errors
+  /// Returns [`Error::ClockWiseViolation`] if a hole isn't clockwise and
+  /// [`Error::ContainmentViolation`] if a hole isn't inside the polygon.
+  /// Other errors are the same as for [`Polygon::new`].
+  ///
+  /// # Time complexity
+  /// $O(n \log n + h n)$ where $h$ is the number of holes.
+  pub fn new_with_holes(
+    boundary: Vec<Point<T, 2>>,
+    holes: Vec<Vec<Point<T, 2>>>,
+  ) -> Result<Polygon<T>, Error>
+  where
+    T: PolygonScalar,
+  {
+    // Verify minimum length before calling 'ensure_ccw'.
+    if boundary.len() < 3 || holes.iter().any(|hole| hole.len() < 3) {
+      return Err(Error::InsufficientVertices);
+    }
+    let mut rings = vec![boundary];
+    rings.extend(holes);
+    let mut p = Self::new_rings_unchecked(rings);
+    p.ensure_ccw()?;
+    p.validate()?;
+    Ok(p)
+  }
+
   // Validate that a polygon is simple.
   // https://en.wikipedia.org/wiki/Simple_polygon
   pub fn validate(&self) -> Result<(), Error>
   where
     T: PolygonScalar,
   {
-    // Has no duplicate points.
-    let mut seen = std::collections::BTreeSet::new();
-    for pt in self.iter() {
-      if !seen.insert(pt) {
-        return Err(Error::DuplicatePoints);
+    // Has no duplicate points, except for vertices shared by different rings.
+    // If the rings that share vertices form a cycle then they enclose a part of
+    // the interior and cut it off from the rest.
+    let mut seen = std::collections::BTreeMap::new();
+    let mut parent: Vec<usize> = (0..self.rings.len()).collect();
+    fn root(parent: &mut [usize], mut ring: usize) -> usize {
+      while parent[ring] != ring {
+        parent[ring] = parent[parent[ring]];
+        ring = parent[ring];
+      }
+      ring
+    }
+    for (pt, ring) in self.points.iter().zip(&self.ring_index) {
+      if let Some(other) = seen.insert(pt, ring.0) {
+        let (a, b) = (root(&mut parent, ring.0), root(&mut parent, other));
+        if a == b {
+          return Err(Error::DuplicatePoints);
+        }
+        parent[a] = b;
       }
     }
 
@@ -189,16 +265,84 @@ impl<T> Polygon<T> {
     if self.orientation() != Orientation::CounterClockWise {
       return Err(Error::ClockWiseViolation);
     }
+    // Holes have at least three points and are clockwise.
+    for ring_id in 1..self.rings.len() {
+      if self.rings[ring_id].len() < 3 {
+        return Err(Error::InsufficientVertices);
+      }
+      if self.ring_orientation(RingId(ring_id)) != Orientation::ClockWise {
+        return Err(Error::ClockWiseViolation);
+      }
+    }
     // Has no self intersections.
     // XXX: Hm, allow overlapping (but not crossing) edges in the weakly check?
-    let edges: Vec<DirectedEdge<'_, T, 2>> = self.iter_boundary_edges().collect();
-    let mut isects = crate::algorithms::segment_intersections(&edges);
-    if isects.next().is_some() {
+    if self.find_self_intersection().is_some() {
       return Err(Error::SelfIntersections);
     }
+    // Rings don't cross so testing a single vertex of each hole is enough. The
+    // vertex must not be shared with the other ring.
+    for hole in 1..self.rings.len() {
+      for other in (0..self.rings.len()).filter(|&other| other != hole) {
+        let pt = match self.rings[hole]
+          .iter()
+          .map(|&pid| self.point(pid))
+          .find(|pt| self.rings[other].iter().all(|&pid| self.point(pid) != *pt))
+        {
+          Some(pt) => pt,
+          None => return Err(Error::DuplicatePoints),
+        };
+        let expected = if other == 0 {
+          PointLocation::Inside
+        } else {
+          PointLocation::Outside
+        };
+        if self.locate_ring(RingId(other), pt) != expected {
+          return Err(Error::ContainmentViolation);
+        }
+      }
+    }
     Ok(())
   }
 
+  /// Find two edges that intersect. Edges of holes are included. Edges are
+  /// half-open so neighbouring edges only intersect if they overlap. Edges of
+  /// different rings may touch at a vertex that both rings share.
+  ///
+  /// # Time complexity
+  /// $O(n \log n)$
+  pub fn find_self_intersection(&self) -> Option<(DirectedIndexEdge, DirectedIndexEdge)>
+  where
+    T: PolygonScalar,
+  {
+    let cursors: Vec<Cursor<'_, T>> = (0..self.rings.len())
+      .filter(|&ring_id| !self.rings[ring_id].is_empty())
+      .flat_map(|ring_id| self.iter_ring(RingId(ring_id)))
+      .collect();
+    let edges: Vec<DirectedEdge<'_, T, 2>> = cursors
+      .iter()
+      .map(|cursor| DirectedEdge {
+        src: cursor.point(),
+        dst: cursor.next().point(),
+      })
+      .collect();
+    let segments: Vec<LineSegmentView<'_, T, 2>> = edges.iter().map(Into::into).collect();
+    let (a, b) = shamos_hoey::sweep(&segments, |a, b| {
+      let (ea, eb) = (&edges[a], &edges[b]);
+      if ea.src == eb.src && cursors[a].position.ring_id != cursors[b].position.ring_id {
+        // Touching rings must not overlap.
+        Point::orient(ea.src, ea.dst, eb.dst).is_colinear()
+          && (ea.src < ea.dst) == (eb.src < eb.dst)
+      } else {
+        segments[a].intersect(segments[b]).is_some()
+      }
+    })?;
+    let index_edge = |idx: usize| DirectedIndexEdge {
+      src: cursors[idx].point_id(),
+      dst: cursors[idx].next().point_id(),
+    };
+    Some((index_edge(a), index_edge(b)))
+  }
+
   pub fn locate(&self, origin: &Point<T, 2>) -> PointLocation
   where
     T: PolygonScalar,
@@ -209,11 +353,21 @@ impl<T> Polygon<T> {
       1,
       "FIXME: Polygon::locate should support polygons with holes."
     );
+    self.locate_ring(RingId(0), origin)
+  }
 
+  // Locate a point relative to a single ring, ignoring all other rings.
+  fn locate_ring(&self, ring_id: RingId, origin: &Point<T, 2>) -> PointLocation
+  where
+    T: PolygonScalar,
+  {
     let direction = Vector::unit_right();
     let ray = HalfLineSoS::new_directed(origin, &direction);
     let mut intersections = 0;
-    for edge in self.iter_boundary_edges() {
+    let edges = EdgeIter {
+      iter: self.iter_ring(ring_id),
+    };
+    for edge in edges {
       if edge.contains(origin) {
         return PointLocation::OnBoundary;
       }
@@ -241,6 +395,20 @@ impl<T> Polygon<T> {
       .map(move |(p1, p2, p3)| (self.cursor(p1), self.cursor(p2), self.cursor(p3)))
   }
 
+  /// Constrained Delaunay triangulation. Unlike [`Polygon::triangulate`], the
+  /// triangles avoid small angles whenever the polygon allows it.
+  pub fn triangulate_delaunay(
+    &self,
+  ) -> impl Iterator<Item = (Cursor<'_, T>, Cursor<'_, T>, Cursor<'_, T>)> + '_
+  where
+    T: PolygonScalar,
+  {
+    crate::algorithms::triangulation::constrained_delaunay::constrained_delaunay(self)
+      .triangles
+      .into_iter()
+      .map(move |[p1, p2, p3]| (self.cursor(p1), self.cursor(p2), self.cursor(p3)))
+  }
+
   //
   // # Panics
   //
@@ -408,7 +576,17 @@ impl<T> Polygon<T> {
   where
     T: PolygonScalar,
   {
-    match self.iter_boundary().min_by(|a, b| a.point().cmp(b.point())) {
+    self.ring_orientation(RingId(0))
+  }
+
+  fn ring_orientation(&self, ring_id: RingId) -> Orientation
+  where
+    T: PolygonScalar,
+  {
+    match self
+      .iter_ring(ring_id)
+      .min_by(|a, b| a.point().cmp(b.point()))
+    {
       None => Orientation::CoLinear,
       Some(cursor) => cursor.orientation(),
     }
@@ -463,12 +641,22 @@ impl<T> Polygon<T> {
   }
 
   pub fn iter_boundary(&self) -> CursorIter<'_, T> {
+    self.iter_ring(RingId(0))
+  }
+
+  /// Iterate over the holes of the polygon. Each hole is a clockwise ring of
+  /// cursors.
+  pub fn iter_holes(&self) -> impl Iterator<Item = CursorIter<'_, T>> + '_ {
+    (1..self.rings.len()).map(move |ring_id| self.iter_ring(RingId(ring_id)))
+  }
+
+  fn iter_ring(&self, ring_id: RingId) -> CursorIter<'_, T> {
     let root_cursor = Cursor {
       polygon: self,
       position: Position {
-        ring_id: RingId(0),
+        ring_id,
         position_id: PositionId(0),
-        size: self.rings[0].len(),
+        size: self.rings[ring_id.0].len(),
       },
     };
     CursorIter {
@@ -849,6 +1037,21 @@ pub mod tests {
       Polygon::new_unchecked(pts).validate_weakly().ok();
     }
 
+    #[test]
+    fn find_self_intersection_prop(pts in vec((-4i8..4, -4i8..4).prop_map(|(x, y)| Point::new([x, y])), 3..8)) {
+      let poly = Polygon::new_unchecked(pts);
+      let edges: Vec<DirectedEdge<'_, i8, 2>> = poly.iter_boundary_edges().collect();
+      let expected = crate::algorithms::segment_intersections(&edges).next().is_some();
+      match poly.find_self_intersection() {
+        None => prop_assert!(!expected),
+        Some((a, b)) => {
+          let a = DirectedEdge { src: poly.point(a.src), dst: poly.point(a.dst) };
+          let b = DirectedEdge { src: poly.point(b.src), dst: poly.point(b.dst) };
+          prop_assert!(a.intersect(b).is_some());
+        }
+      }
+    }
+
     #[test]
     fn fuzz_centroid(poly in polygon_nn()) {
       poly.centroid();
@@ -879,6 +1082,14 @@ pub mod tests {
       )
     }
 
+    #[test]
+    fn new_with_holes_prop(poly: Polygon<i8>) {
+      let boundary: Vec<Point<i8>> = poly.iter_boundary().map(|cursor| *cursor.point()).collect();
+      let with_holes = Polygon::new_with_holes(boundary, vec![]).unwrap();
+      prop_assert!(with_holes.equals(&poly));
+      prop_assert_eq!(with_holes.iter_holes().count(), 0);
+    }
+
     #[test]
     fn equals_identity_prop(poly: Polygon<i8>, offset: usize) {
       let points: Vec<Point<i8>> = poly.iter_boundary().map(|cursor| cursor.point()).cloned().collect();
@@ -902,6 +1113,107 @@ pub mod tests {
   //   // prop_assert!(width == OrderedFloat(1.0) || height == OrderedFloat(1.0));
   // }
 
+  #[test]
+  fn find_self_intersection_unit() {
+    let poly: Polygon<i8> = Polygon::new_unchecked(vec![
+      Point { array: [0, 0] },
+      Point { array: [2, 0] },
+      Point { array: [0, 2] },
+      Point { array: [2, 2] },
+    ]);
+    assert_eq!(poly.validate(), Err(Error::SelfIntersections));
+    let (a, b) = poly.find_self_intersection().expect("bowtie intersects");
+    let mut found = [IndexEdge::from(a), IndexEdge::from(b)];
+    found.sort();
+    assert_eq!(
+      found,
+      [
+        IndexEdge::new(PointId(0), PointId(3)),
+        IndexEdge::new(PointId(1), PointId(2))
+      ]
+    );
+  }
+
+  // Clockwise ring of a polygon, for use as a hole.
+  fn reversed(poly: Polygon<i8>) -> Vec<Point<i8>> {
+    let mut ring = poly.points;
+    ring.reverse();
+    ring
+  }
+
+  #[test]
+  fn new_with_holes_unit() {
+    let poly = Polygon::new_with_holes(
+      reversed(square(0, 0, 10)),
+      vec![reversed(square(1, 1, 2)), reversed(square(5, 5, 2))],
+    )
+    .expect("valid polygon");
+    assert_eq!(poly.orientation(), Orientation::CounterClockWise);
+    assert_eq!(poly.validate(), Ok(()));
+    let holes: Vec<Vec<Point<i8>>> = poly
+      .iter_holes()
+      .map(|hole| hole.map(|cursor| *cursor.point()).collect())
+      .collect();
+    assert_eq!(
+      holes,
+      vec![reversed(square(1, 1, 2)), reversed(square(5, 5, 2))]
+    );
+    assert_eq!(poly.iter_boundary().len(), 4);
+  }
+
+  #[test]
+  fn new_with_holes_errors() {
+    let new = |holes| Polygon::new_with_holes(square(0, 0, 10).points, holes).err();
+    assert_eq!(
+      new(vec![square(1, 1, 2).points]),
+      Some(Error::ClockWiseViolation)
+    );
+    assert_eq!(
+      new(vec![reversed(square(20, 20, 2))]),
+      Some(Error::ContainmentViolation)
+    );
+    assert_eq!(
+      new(vec![reversed(square(1, 1, 6)), reversed(square(2, 2, 2))]),
+      Some(Error::ContainmentViolation)
+    );
+    assert_eq!(
+      new(vec![reversed(square(8, 8, 4))]),
+      Some(Error::SelfIntersections)
+    );
+    assert_eq!(
+      new(vec![reversed(square(1, 1, 4)), reversed(square(3, 3, 4))]),
+      Some(Error::SelfIntersections)
+    );
+    assert_eq!(
+      new(vec![vec![Point::new([1, 1]), Point::new([2, 2])]]),
+      Some(Error::InsufficientVertices)
+    );
+  }
+
+  #[test]
+  fn new_with_holes_touching() {
+    let new = |holes| Polygon::new_with_holes(square(0, 0, 10).points, holes).err();
+    // A hole may share a vertex with the boundary.
+    let corner = vec![Point::new([0, 0]), Point::new([2, 4]), Point::new([4, 2])];
+    assert_eq!(new(vec![corner.clone()]), None);
+    // Holes may touch each other.
+    let next = vec![Point::new([4, 2]), Point::new([6, 4]), Point::new([7, 3])];
+    assert_eq!(new(vec![corner.clone(), next]), None);
+    // But not if they cut off a part of the interior.
+    let diagonal = vec![
+      Point::new([0, 0]),
+      Point::new([2, 8]),
+      Point::new([10, 10]),
+      Point::new([8, 2]),
+    ];
+    assert_eq!(new(vec![diagonal]), Some(Error::DuplicatePoints));
+    let bottom = vec![Point::new([4, 2]), Point::new([6, 4]), Point::new([10, 0])];
+    assert_eq!(new(vec![corner, bottom]), Some(Error::DuplicatePoints));
+    // Touching rings may not overlap.
+    let side = vec![Point::new([0, 0]), Point::new([0, 4]), Point::new([3, 3])];
+    assert_eq!(new(vec![side]), Some(Error::SelfIntersections));
+  }
+
   #[test]
   #[should_panic]
   fn locate_feature_fixme() {
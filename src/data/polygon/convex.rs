@@ -6,7 +6,7 @@ use rand::seq::SliceRandom;
 use rand::Rng;
 use std::ops::*;
 
-use crate::data::{Point, PointLocation, TriangleView, Vector};
+use crate::data::{Point, PointLocation, Vector};
 use crate::{Error, Orientation, PolygonScalar, TotalOrd};
 
 use super::Polygon;
@@ -35,6 +35,9 @@ where
 
   /// Locate a point relative to a convex polygon.
   ///
+  /// The polygon is split into a fan of triangles around the first vertex and
+  /// the triangle that may contain the point is found by binary search.
+  ///
   /// # Time complexity
   /// $O(\log n)$
   ///
@@ -45,6 +48,12 @@ where
     let poly = &self.0;
     let vertices = self.boundary_slice();
     let p0 = poly.point(vertices[0]);
+    // The point has to be inside the cone spanned by the two edges at p0.
+    let first = Point::orient(p0, poly.point(vertices[1]), pt);
+    let last = Point::orient(p0, poly.point(vertices[vertices.len() - 1]), pt);
+    if first.is_cw() || last.is_ccw() {
+      return PointLocation::Outside;
+    }
     let mut lower = 1;
     let mut upper = vertices.len() - 1;
     while lower + 1 < upper {
@@ -55,10 +64,26 @@ where
         upper = middle;
       }
     }
+    // Diagonals of the fan are inside the polygon. Only the edge between p1
+    // and p2 and the edges at p0 are on the boundary.
     let p1 = poly.point(vertices[lower]);
     let p2 = poly.point(vertices[upper]);
-    let triangle = TriangleView::new_unchecked([p0, p1, p2]);
-    triangle.locate(pt)
+    match Point::orient(p1, p2, pt) {
+      Orientation::ClockWise => PointLocation::Outside,
+      Orientation::CoLinear => PointLocation::OnBoundary,
+      Orientation::CounterClockWise if first.is_colinear() || last.is_colinear() => {
+        PointLocation::OnBoundary
+      }
+      Orientation::CounterClockWise => PointLocation::Inside,
+    }
+  }
+
+  /// Check if a point is inside the polygon or on its boundary.
+  ///
+  /// # Time complexity
+  /// $O(\log n)$
+  pub fn contains(&self, pt: &Point<T, 2>) -> bool {
+    self.locate(pt) != PointLocation::Outside
   }
 
   /// Validates the following properties:
@@ -259,6 +284,32 @@ mod tests {
       prop_assert_eq!(poly.validate().err(), None)
     }
 
+    #[test]
+    fn locate_matches_polygon(poly: PolygonConvex<i8>, pt: Point<i8, 2>) {
+      let expected = poly.polygon().locate(&pt);
+      prop_assert_eq!(poly.locate(&pt), expected);
+      prop_assert_eq!(poly.contains(&pt), expected != PointLocation::Outside);
+    }
+
+    #[test]
+    fn locate_diagonals_and_edges(poly: PolygonConvex<i8>) {
+      // Scale by two so midpoints between vertices have integer coordinates.
+      let double = |pt: &Point<i8, 2>| Point::new([*pt.x_coord() as i16 * 2, *pt.y_coord() as i16 * 2]);
+      let scaled = PolygonConvex::new_unchecked(Polygon::new_unchecked(poly.iter().map(double).collect()));
+      let vertices: Vec<&Point<i8, 2>> = poly.iter().collect();
+      let n = vertices.len();
+      for i in 0..n {
+        let others = [vertices[0], vertices[(i + 1) % n]];
+        for other in others {
+          let mid = Point::new([
+            *vertices[i].x_coord() as i16 + *other.x_coord() as i16,
+            *vertices[i].y_coord() as i16 + *other.y_coord() as i16,
+          ]);
+          prop_assert_eq!(scaled.locate(&mid), scaled.polygon().locate(&mid));
+        }
+      }
+    }
+
     #[test]
     fn random_convex_prop(poly: PolygonConvex<i8>) {
       let (min, max) = poly.bounding_box();
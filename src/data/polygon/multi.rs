@@ -0,0 +1,489 @@
+use num_traits::*;
+use std::convert::TryFrom;
+use std::iter::Sum;
+use std::ops::*;
+
+use crate::algorithms::intersection::shamos_hoey;
+use crate::data::{
+  DirectedEdge, HalfLineSoS, IHalfLineLineSegmentSoS::*, LineSegmentView, Point, PointLocation,
+  Vector,
+};
+use crate::intersection::*;
+use crate::utils::exact::to_rational;
+use crate::{Error, PolygonScalar, TotalOrd};
+use num::BigRational;
+
+use super::Polygon;
+
+/// Collection of non-overlapping polygons. Components may touch at their
+/// boundaries and may be placed inside the holes of other components.
+#[derive(Debug, Clone)]
+pub struct MultiPolygon<T> {
+  pub(crate) components: Vec<Polygon<T>>,
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// MultiPolygon
+
+impl<T> MultiPolygon<T> {
+  /// Assume that the components do not overlap.
+  ///
+  /// # Time complexity
+  /// $O(1)$
+  pub fn new_unchecked(components: Vec<Polygon<T>>) -> MultiPolygon<T> {
+    MultiPolygon { components }
+  }
+
+  /// # Errors
+  /// Returns [`Error::SelfIntersections`] if the interiors of two components
+  /// overlap. Other errors are forwarded from [`Polygon::validate`].
+  pub fn new(components: Vec<Polygon<T>>) -> Result<MultiPolygon<T>, Error>
+  where
+    T: PolygonScalar,
+  {
+    let multi = MultiPolygon::new_unchecked(components);
+    multi.validate()?;
+    Ok(multi)
+  }
+
+  /// Validate each component and check that no two components overlap.
+  ///
+  /// # Time complexity
+  /// $O(n \log n + c n)$ where $c$ is the number of components.
+  pub fn validate(&self) -> Result<(), Error>
+  where
+    T: PolygonScalar,
+  {
+    for poly in self.iter() {
+      poly.validate()?;
+    }
+    // Edges of every ring with the component they belong to and the vertex
+    // before them.
+    let mut edges: Vec<(usize, &Point<T>, DirectedEdge<'_, T, 2>)> = Vec::new();
+    let mut ranges = Vec::new();
+    for (idx, poly) in self.iter().enumerate() {
+      let start = edges.len();
+      for ring in &poly.rings {
+        for (i, &pid) in ring.iter().enumerate() {
+          edges.push((
+            idx,
+            poly.point(ring[(i + ring.len() - 1) % ring.len()]),
+            DirectedEdge {
+              src: poly.point(pid),
+              dst: poly.point(ring[(i + 1) % ring.len()]),
+            },
+          ));
+        }
+      }
+      ranges.push(start..edges.len());
+    }
+    // Boundaries of different components may touch but must not cross, and
+    // where they touch the interiors must be on different sides. Every pair of
+    // edges through a touching point is compared so it is enough to look at
+    // the corners at the start of each edge.
+    let segments: Vec<LineSegmentView<'_, T, 2>> = edges.iter().map(|(_, _, e)| e.into()).collect();
+    let overlap = shamos_hoey::sweep(&segments, |a, b| {
+      let ((idx_a, prev_a, ea), (idx_b, prev_b, eb)) = (&edges[a], &edges[b]);
+      if idx_a == idx_b || segments[a].intersect(segments[b]).is_none() {
+        return false;
+      }
+      let on_b = |pt: &Point<T>| Point::orient(eb.src, eb.dst, pt).is_colinear();
+      let on_a = |pt: &Point<T>| Point::orient(ea.src, ea.dst, pt).is_colinear();
+      if ![ea.src, ea.dst].iter().any(|pt| on_b(pt)) && ![eb.src, eb.dst].iter().any(|pt| on_a(pt))
+      {
+        // The edges cross in their interiors.
+        return true;
+      }
+      let interior_a = segments[a].contains(eb.src) && ea.src != eb.src && ea.dst != eb.src;
+      let interior_b = segments[b].contains(ea.src) && eb.src != ea.src && eb.dst != ea.src;
+      (ea.src == eb.src && corners_overlap(ea.src, (prev_a, ea.dst), (prev_b, eb.dst)))
+        || (interior_a && corners_overlap(eb.src, (ea.src, ea.dst), (prev_b, eb.dst)))
+        || (interior_b && corners_overlap(ea.src, (eb.src, eb.dst), (prev_a, ea.dst)))
+    });
+    if overlap.is_some() {
+      return Err(Error::SelfIntersections);
+    }
+    // The boundaries don't cross so a component is either inside another
+    // component or outside of it. A vertex that touches another component is
+    // known to be outside of it.
+    for (idx, poly) in self.iter().enumerate() {
+      let vertex = poly.point(poly.rings[0][0]);
+      let inside = ranges.iter().enumerate().any(|(other, range)| {
+        let edges = edges[range.clone()].iter().map(|&(_, _, edge)| edge);
+        other != idx && locate_edges(edges, vertex) == PointLocation::Inside
+      });
+      if inside {
+        return Err(Error::SelfIntersections);
+      }
+    }
+    Ok(())
+  }
+
+  /// Computes the combined area of all components. Holes are subtracted.
+  ///
+  /// # Time complexity
+  /// $O(n)$
+  pub fn signed_area<F>(&self) -> F
+  where
+    T: PolygonScalar + Into<F>,
+    F: NumOps<F, F> + Sum + FromPrimitive,
+  {
+    self.signed_area_2x::<F>() / F::from_usize(2).unwrap()
+  }
+
+  /// Computes double the combined area of all components. See
+  /// [`Polygon::signed_area_2x`] for details about the return type.
+  ///
+  /// # Time complexity
+  /// $O(n)$
+  pub fn signed_area_2x<F>(&self) -> F
+  where
+    T: PolygonScalar + Into<F>,
+    F: NumOps<F, F> + Sum,
+  {
+    self
+      .iter_edges()
+      .map(|edge| {
+        let p = edge.src;
+        let q = edge.dst;
+        p.array[0].clone().into() * q.array[1].clone().into()
+          - q.array[0].clone().into() * p.array[1].clone().into()
+      })
+      .sum()
+  }
+
+  // # Panics
+  //
+  // Panics if there are no components. May panic for bounded types (i8, isize, etc).
+  // Maybe produce inaccurate results for floating point types (f32, f64).
+  pub fn centroid(&self) -> Point<T>
+  where
+    T: PolygonScalar,
+  {
+    let xs: Vector<T, 2> = self
+      .iter_edges()
+      .map(|edge| {
+        let p = edge.src.as_vec();
+        let q = edge.dst.as_vec();
+        (p + q) * (p.0[0].clone() * q.0[1].clone() - q.0[0].clone() * p.0[1].clone())
+      })
+      .sum();
+    let three = T::from_constant(3);
+    Point::from(xs / (three * self.signed_area_2x()))
+  }
+
+  /// Smallest axis-aligned box containing every component. `None` if there are
+  /// no components.
+  pub fn bounding_box(&self) -> Option<(Point<T>, Point<T>)>
+  where
+    T: PolygonScalar,
+  {
+    self
+      .iter()
+      .map(|poly| poly.bounding_box())
+      .reduce(|(a_min, a_max), (b_min, b_max)| {
+        (
+          Point::new([
+            a_min.array[0].clone().total_min(b_min.array[0].clone()),
+            a_min.array[1].clone().total_min(b_min.array[1].clone()),
+          ]),
+          Point::new([
+            a_max.array[0].clone().total_max(b_max.array[0].clone()),
+            a_max.array[1].clone().total_max(b_max.array[1].clone()),
+          ]),
+        )
+      })
+  }
+
+  /// Locate a point relative to the union of all components.
+  ///
+  /// Edges shared by two touching components are reported as
+  /// [`PointLocation::OnBoundary`] even though the union is on both sides.
+  ///
+  /// # Time complexity
+  /// $O(n)$
+  pub fn locate(&self, origin: &Point<T, 2>) -> PointLocation
+  where
+    T: PolygonScalar,
+  {
+    locate_edges(self.iter_edges(), origin)
+  }
+
+  /// Iterate over the edges of every ring of every component.
+  pub fn iter_edges(&self) -> impl Iterator<Item = DirectedEdge<'_, T, 2>> + '_
+  where
+    T: TotalOrd,
+  {
+    self.iter().flat_map(|poly| {
+      poly.rings.iter().flat_map(move |ring| {
+        (0..ring.len()).map(move |i| DirectedEdge {
+          src: poly.point(ring[i]),
+          dst: poly.point(ring[(i + 1) % ring.len()]),
+        })
+      })
+    })
+  }
+}
+
+// Even-odd test against the edges of one or more rings.
+fn locate_edges<'a, T, I>(edges: I, origin: &Point<T, 2>) -> PointLocation
+where
+  T: PolygonScalar + 'a,
+  I: Iterator<Item = DirectedEdge<'a, T, 2>>,
+{
+  let direction = Vector::unit_right();
+  let ray = HalfLineSoS::new_directed(origin, &direction);
+  let mut intersections = 0;
+  for edge in edges {
+    if edge.contains(origin) {
+      return PointLocation::OnBoundary;
+    }
+    if let Some(Crossing(lean)) = ray.intersect(edge) {
+      // Only count crossing that aren't leaning to the right.
+      if !lean.is_cw() {
+        intersections += 1;
+      }
+    }
+  }
+  if intersections % 2 == 0 {
+    PointLocation::Outside
+  } else {
+    PointLocation::Inside
+  }
+}
+
+// Whether the interiors of two corners at 'vertex' overlap. A corner is given
+// by the points before and after 'vertex' and has the interior on its left, so
+// it spans counter-clockwise from the point after to the point before. For a
+// vertex in the middle of an edge, the corner is the left side of the edge.
+fn corners_overlap<T: PolygonScalar>(
+  vertex: &Point<T>,
+  (prev_a, next_a): (&Point<T>, &Point<T>),
+  (prev_b, next_b): (&Point<T>, &Point<T>),
+) -> bool {
+  // Exact so the direction to 'prev_a' can't overflow.
+  let vertex = to_rational(vertex);
+  let [prev_a, next_a, prev_b, next_b] = [prev_a, next_a, prev_b, next_b].map(to_rational);
+  let start = &prev_a - &vertex;
+  let cmp =
+    |p: &Point<BigRational>, q: &Point<BigRational>| vertex.ccw_cmp_around_with(&start, p, q);
+  // Measured counter-clockwise from 'prev_a', the corner 'b' must lie between
+  // the angles 0 and 'next_a'.
+  !(cmp(&next_b, &prev_b).is_lt() && cmp(&prev_b, &next_a).is_le())
+}
+
+impl<T> Deref for MultiPolygon<T> {
+  type Target = [Polygon<T>];
+  fn deref(&self) -> &Self::Target {
+    &self.components
+  }
+}
+
+impl<T: PolygonScalar> TryFrom<Vec<Polygon<T>>> for MultiPolygon<T> {
+  type Error = Error;
+  fn try_from(components: Vec<Polygon<T>>) -> Result<MultiPolygon<T>, Error> {
+    MultiPolygon::new(components)
+  }
+}
+
+impl<T> From<Polygon<T>> for MultiPolygon<T> {
+  fn from(poly: Polygon<T>) -> MultiPolygon<T> {
+    MultiPolygon::new_unchecked(vec![poly])
+  }
+}
+
+impl<T> From<MultiPolygon<T>> for Vec<Polygon<T>> {
+  fn from(multi: MultiPolygon<T>) -> Vec<Polygon<T>> {
+    multi.components
+  }
+}
+
+impl<T> IntoIterator for MultiPolygon<T> {
+  type Item = Polygon<T>;
+  type IntoIter = std::vec::IntoIter<Polygon<T>>;
+  fn into_iter(self) -> Self::IntoIter {
+    self.components.into_iter()
+  }
+}
+
+impl<'a, T> IntoIterator for &'a MultiPolygon<T> {
+  type Item = &'a Polygon<T>;
+  type IntoIter = std::slice::Iter<'a, Polygon<T>>;
+  fn into_iter(self) -> Self::IntoIter {
+    self.components.iter()
+  }
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Tests
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+  use crate::algorithms::boolean;
+  use crate::algorithms::triangulation::earclip::earclip;
+  use crate::testing::{frame, rational, square};
+
+  use test_strategy::proptest;
+
+  #[test]
+  fn vec_roundtrip() {
+    let components = vec![square(0, 0, 1), square(2, 0, 1)];
+    let multi = MultiPolygon::try_from(components.clone()).unwrap();
+    assert_eq!(multi.len(), 2);
+    let back: Vec<Polygon<i32>> = multi.into();
+    assert!(back.iter().zip(&components).all(|(a, b)| a.equals(b)));
+    let overlapping = vec![square(0, 0, 2), square(1, 1, 2)];
+    assert_eq!(
+      MultiPolygon::try_from(overlapping).err(),
+      Some(Error::SelfIntersections)
+    );
+  }
+
+  #[test]
+  fn validate_disjoint() {
+    assert!(MultiPolygon::new(vec![square(0, 0, 2), square(3, 0, 2)]).is_ok());
+  }
+
+  #[test]
+  fn validate_touching() {
+    assert!(MultiPolygon::new(vec![square(0, 0, 2), square(2, 0, 2)]).is_ok());
+    assert!(MultiPolygon::new(vec![square(0, 0, 2), square(2, 2, 2)]).is_ok());
+  }
+
+  #[test]
+  fn validate_overlapping() {
+    assert_eq!(
+      MultiPolygon::new(vec![square(0, 0, 2), square(1, 1, 2)]).err(),
+      Some(Error::SelfIntersections)
+    );
+    assert_eq!(
+      MultiPolygon::new(vec![square(0, 0, 6), square(2, 2, 2)]).err(),
+      Some(Error::SelfIntersections)
+    );
+  }
+
+  #[test]
+  fn validate_touching_edge_interior() {
+    // Vertices of one square are in the middle of an edge of the other.
+    assert!(MultiPolygon::new(vec![square(0, 0, 4), square(4, 1, 2)]).is_ok());
+    let triangle = Polygon::new(vec![
+      Point::new([2, 4]),
+      Point::new([3, 6]),
+      Point::new([1, 6]),
+    ])
+    .unwrap();
+    assert!(MultiPolygon::new(vec![square(0, 0, 4), triangle]).is_ok());
+  }
+
+  #[test]
+  fn validate_overlapping_without_crossing() {
+    // The boundaries only touch but the triangle is on the inside of the
+    // square's top edge.
+    let triangle = Polygon::new(vec![
+      Point::new([0, 0]),
+      Point::new([4, 4]),
+      Point::new([-2, 4]),
+    ])
+    .unwrap();
+    assert_eq!(
+      MultiPolygon::new(vec![square(0, 0, 4), triangle]).err(),
+      Some(Error::SelfIntersections)
+    );
+    // A vertical edge goes through the square from the middle of the bottom
+    // edge to the middle of the top edge.
+    let quad = Polygon::new(vec![
+      Point::new([2, 0]),
+      Point::new([6, -2]),
+      Point::new([6, 6]),
+      Point::new([2, 4]),
+    ])
+    .unwrap();
+    assert_eq!(
+      MultiPolygon::new(vec![square(0, 0, 4), quad]).err(),
+      Some(Error::SelfIntersections)
+    );
+  }
+
+  #[test]
+  fn validate_inside_hole() {
+    assert!(MultiPolygon::new(vec![frame(), square(4, 4, 4)]).is_ok());
+    assert!(MultiPolygon::new(vec![frame(), square(5, 5, 2)]).is_ok());
+    assert!(MultiPolygon::new(vec![frame(), square(5, 5, 4)]).is_err());
+    assert!(MultiPolygon::new(vec![frame(), square(1, 1, 2)]).is_err());
+  }
+
+  #[proptest]
+  fn validate_matches_boolean(
+    #[any((3..10, ()))] a: Polygon<i8>,
+    #[any((3..10, ()))] b: Polygon<i8>,
+  ) {
+    let (a, b) = (rational(a), rational(b));
+    let disjoint = boolean::intersection(&a, &b).is_empty();
+    assert_eq!(MultiPolygon::new(vec![a, b]).is_ok(), disjoint);
+  }
+
+  #[proptest]
+  fn validate_triangulation(#[any((3..20, ()))] poly: Polygon<i8>) {
+    // The triangles touch along their edges and at vertices in the middle of
+    // other edges.
+    let trigs: Vec<Polygon<i8>> = earclip(&poly)
+      .map(|(a, b, c)| Polygon::new(vec![*poly.point(a), *poly.point(b), *poly.point(c)]).unwrap())
+      .collect();
+    assert_eq!(MultiPolygon::new(trigs.clone()).err(), None);
+    let mut overlapping = trigs;
+    overlapping.push(poly);
+    assert_eq!(
+      MultiPolygon::new(overlapping).err(),
+      Some(Error::SelfIntersections)
+    );
+  }
+
+  #[test]
+  fn area_and_centroid() {
+    let multi = MultiPolygon::new_unchecked(vec![frame(), square(13, 0, 2)]);
+    assert_eq!(multi.signed_area::<i32>(), 128 + 4);
+    let multi = MultiPolygon::new_unchecked(vec![square(0, 0, 2), square(4, 0, 2)]);
+    assert_eq!(multi.centroid(), Point::new([3, 1]));
+    assert_eq!(MultiPolygon::from(frame()).centroid(), Point::new([6, 6]));
+  }
+
+  #[test]
+  fn bounding_box() {
+    let multi = MultiPolygon::new_unchecked(vec![square(0, 0, 2), square(4, -3, 2)]);
+    assert_eq!(
+      multi.bounding_box(),
+      Some((Point::new([0, -3]), Point::new([6, 2])))
+    );
+    assert_eq!(
+      MultiPolygon::<i32>::new_unchecked(vec![]).bounding_box(),
+      None
+    );
+  }
+
+  #[test]
+  fn locate() {
+    let multi = MultiPolygon::new_unchecked(vec![frame(), square(13, 0, 2)]);
+    assert_eq!(multi.locate(&Point::new([1, 1])), PointLocation::Inside);
+    assert_eq!(multi.locate(&Point::new([6, 6])), PointLocation::Outside);
+    assert_eq!(multi.locate(&Point::new([4, 6])), PointLocation::OnBoundary);
+    assert_eq!(multi.locate(&Point::new([14, 1])), PointLocation::Inside);
+    assert_eq!(multi.locate(&Point::new([16, 1])), PointLocation::Outside);
+    // The shared edge of touching components is still a boundary.
+    let multi = MultiPolygon::new_unchecked(vec![square(0, 0, 2), square(2, 0, 2)]);
+    assert_eq!(multi.locate(&Point::new([2, 1])), PointLocation::OnBoundary);
+  }
+
+  #[proptest]
+  fn locate_matches_polygon(poly: Polygon<i8>, origin: Point<i8>) {
+    let expected = poly.locate(&origin);
+    assert_eq!(MultiPolygon::from(poly).locate(&origin), expected);
+  }
+
+  #[proptest]
+  fn area_matches_polygon(poly: Polygon<i8>) {
+    let expected = poly.signed_area_2x::<i64>();
+    assert_eq!(MultiPolygon::from(poly).signed_area_2x::<i64>(), expected);
+  }
+}
@@ -30,6 +30,8 @@ pub enum Error {
   ConvexViolation,
   ClockWiseViolation,
   CoLinearViolation,
+  /// A hole is either outside the boundary or inside another hole.
+  ContainmentViolation,
 }
 
 impl std::fmt::Display for Error {
@@ -44,6 +46,7 @@ impl std::fmt::Display for Error {
         f,
         "Two or more points are colinear and no valid solution exists"
       ),
+      Error::ContainmentViolation => write!(f, "Containment violation"),
     }
   }
 }
@@ -103,10 +106,62 @@ pub trait PolygonScalar:
   fn cmp_slope(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2]) -> std::cmp::Ordering;
   fn cmp_vector_slope(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2]) -> std::cmp::Ordering;
   fn cmp_perp_vector_slope(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2]) -> std::cmp::Ordering;
+  /// Sign of the cross product of `q - p` and `s - r`. The result is `Greater`
+  /// if `s - r` points to the left of `q - p`, `Less` if it points to the right
+  /// and `Equal` if they are parallel.
+  ///
+  /// The default implementation evaluates the cross product with
+  /// [`to_rational`](PolygonScalar::to_rational).
+  fn cmp_cross(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2], s: &[Self; 2]) -> std::cmp::Ordering {
+    let lift = |pt: &[Self; 2]| [pt[0].to_rational(), pt[1].to_rational()];
+    cross_det(lift(p), lift(q), lift(r), lift(s))
+  }
+  /// Sign of the in-circle determinant. If `p`, `q` and `r` are counter-clockwise,
+  /// the result is `Greater` if `s` is inside the circle through `p`, `q` and
+  /// `r`, `Less` if it is outside and `Equal` if it is on the circle. The result
+  /// is reversed if `p`, `q` and `r` are clockwise.
+  ///
+  /// The default implementation evaluates the determinant with
+  /// [`to_rational`](PolygonScalar::to_rational).
+  fn incircle(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2], s: &[Self; 2]) -> std::cmp::Ordering {
+    let lift = |pt: &[Self; 2]| [pt[0].to_rational(), pt[1].to_rational()];
+    incircle_det(lift(p), lift(q), lift(r), lift(s))
+  }
+  /// Sign of the 3D orientation determinant. The result is `Greater` if `s` is
+  /// below the plane through `p`, `q` and `r`, `Less` if it is above and
+  /// `Equal` if the four points are coplanar. Below is the side from which `p`,
+  /// `q` and `r` appear in clockwise order.
+  ///
+  /// The default implementation evaluates the determinant with
+  /// [`to_rational`](PolygonScalar::to_rational).
+  fn orient3d(p: &[Self; 3], q: &[Self; 3], r: &[Self; 3], s: &[Self; 3]) -> std::cmp::Ordering {
+    let lift = |pt: &[Self; 3]| {
+      [
+        pt[0].to_rational(),
+        pt[1].to_rational(),
+        pt[2].to_rational(),
+      ]
+    };
+    orient3d_det(lift(p), lift(q), lift(r), lift(s))
+  }
+  /// Exact conversion to an arbitrary precision rational. Used by algorithms that
+  /// need predicates of higher degree than the ones offered by this trait.
+  fn to_rational(&self) -> num::BigRational;
+  /// Conversion from an arbitrary precision rational. Exact if the value can be
+  /// represented, otherwise rounded to the nearest representable value.
+  ///
+  /// Algorithms that create new points, such as intersections, compute them
+  /// exactly and convert the result with this method. Their output is only
+  /// exact if `T` can represent the new coordinates (eg. `BigRational`).
+  ///
+  /// # Panics
+  /// Fixed-width integers panic if the rounded value is out of range.
+  fn from_rational(val: &num::BigRational) -> Self;
 }
 
+// $wide must be able to hold fourth degree polynomials of the coordinates.
 macro_rules! fixed_precision {
-  ( $ty:ty, $uty:ty, $long:ty, $ulong: ty ) => {
+  ( $ty:ty, $uty:ty, $long:ty, $ulong: ty, $wide: ty ) => {
     impl TotalOrd for $ty {
       fn total_cmp(&self, other: &Self) -> Ordering {
         self.cmp(other)
@@ -117,6 +172,14 @@ macro_rules! fixed_precision {
       fn from_constant(val: i8) -> Self {
         val as $ty
       }
+      fn to_rational(&self) -> num::BigRational {
+        num::BigRational::from_integer(num::BigInt::from(*self))
+      }
+      fn from_rational(val: &num::BigRational) -> Self {
+        let int = val.round().to_integer();
+        <$ty as std::convert::TryFrom<&num::BigInt>>::try_from(&int)
+          .unwrap_or_else(|_| panic!("{} is out of range for {}", int, stringify!($ty)))
+      }
       fn cmp_dist(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2]) -> std::cmp::Ordering {
         fn diff(a: $ty, b: $ty) -> $ulong {
           if b > a {
@@ -230,6 +293,48 @@ macro_rules! fixed_precision {
           (false, false) => (ux * vy).cmp(&(uy * vx)),
         }
       }
+
+      fn cmp_cross(
+        p: &[Self; 2],
+        q: &[Self; 2],
+        r: &[Self; 2],
+        s: &[Self; 2],
+      ) -> std::cmp::Ordering {
+        cross_det(
+          p.map(<$wide as From<$ty>>::from),
+          q.map(<$wide as From<$ty>>::from),
+          r.map(<$wide as From<$ty>>::from),
+          s.map(<$wide as From<$ty>>::from),
+        )
+      }
+
+      fn incircle(
+        p: &[Self; 2],
+        q: &[Self; 2],
+        r: &[Self; 2],
+        s: &[Self; 2],
+      ) -> std::cmp::Ordering {
+        incircle_det(
+          p.map(<$wide as From<$ty>>::from),
+          q.map(<$wide as From<$ty>>::from),
+          r.map(<$wide as From<$ty>>::from),
+          s.map(<$wide as From<$ty>>::from),
+        )
+      }
+
+      fn orient3d(
+        p: &[Self; 3],
+        q: &[Self; 3],
+        r: &[Self; 3],
+        s: &[Self; 3],
+      ) -> std::cmp::Ordering {
+        orient3d_det(
+          p.map(<$wide as From<$ty>>::from),
+          q.map(<$wide as From<$ty>>::from),
+          r.map(<$wide as From<$ty>>::from),
+          s.map(<$wide as From<$ty>>::from),
+        )
+      }
     }
   };
 }
@@ -247,6 +352,12 @@ macro_rules! arbitrary_precision {
       fn from_constant(val: i8) -> Self {
         <$ty>::from_i8(val).unwrap()
       }
+      fn to_rational(&self) -> num::BigRational {
+        num::BigRational::from(self.clone())
+      }
+      fn from_rational(val: &num::BigRational) -> Self {
+        FromBigRational::from_big_rational(val)
+      }
       fn cmp_dist(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2]) -> std::cmp::Ordering {
         let pq_x = &p[0] - &q[0];
         let pq_y = &p[1] - &q[1];
@@ -276,10 +387,36 @@ macro_rules! arbitrary_precision {
           q
         )
       }
+      fn cmp_cross(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2], s: &[Self; 2]) -> std::cmp::Ordering {
+        cross_det(p.clone(), q.clone(), r.clone(), s.clone())
+      }
+      fn incircle(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2], s: &[Self; 2]) -> std::cmp::Ordering {
+        incircle_det(p.clone(), q.clone(), r.clone(), s.clone())
+      }
+      fn orient3d(p: &[Self; 3], q: &[Self; 3], r: &[Self; 3], s: &[Self; 3]) -> std::cmp::Ordering {
+        orient3d_det(p.clone(), q.clone(), r.clone(), s.clone())
+      }
     })*
   };
 }
 
+// Rounding conversion for the arbitrary precision types.
+trait FromBigRational {
+  fn from_big_rational(val: &num::BigRational) -> Self;
+}
+
+impl FromBigRational for num::BigInt {
+  fn from_big_rational(val: &num::BigRational) -> Self {
+    val.round().to_integer()
+  }
+}
+
+impl FromBigRational for num::BigRational {
+  fn from_big_rational(val: &num::BigRational) -> Self {
+    val.clone()
+  }
+}
+
 macro_rules! wrapped_floating_precision {
   ( $( $ty:ty ),* ) => {
     $(
@@ -293,6 +430,12 @@ macro_rules! wrapped_floating_precision {
       fn from_constant(val: i8) -> Self {
         <$ty>::from_i8(val).unwrap()
       }
+      fn to_rational(&self) -> num::BigRational {
+        float_to_rational(self.into_inner())
+      }
+      fn from_rational(val: &num::BigRational) -> Self {
+        <$ty>::from_f64(val.to_f64().unwrap()).unwrap()
+      }
       // FIXME: Use `geometry_predicates` to speed up calculation. Right now we're
       // roughly 100x slower than necessary.
       fn cmp_dist(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2]) -> std::cmp::Ordering {
@@ -338,6 +481,38 @@ macro_rules! wrapped_floating_precision {
           &[float_to_rational(q[0].into_inner()), float_to_rational(q[1].into_inner())],
         )
       }
+      // The in-circle test from `geometry_predicates` is exact.
+      fn incircle(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2], s: &[Self; 2]) -> std::cmp::Ordering {
+        let det = geometry_predicates::predicates::incircle(
+          [p[0].into_inner() as f64, p[1].into_inner() as f64],
+          [q[0].into_inner() as f64, q[1].into_inner() as f64],
+          [r[0].into_inner() as f64, r[1].into_inner() as f64],
+          [s[0].into_inner() as f64, s[1].into_inner() as f64],
+        );
+        if det > 0.0 {
+          Ordering::Greater
+        } else if det < 0.0 {
+          Ordering::Less
+        } else {
+          Ordering::Equal
+        }
+      }
+      // The 3D orientation test from `geometry_predicates` is exact.
+      fn orient3d(p: &[Self; 3], q: &[Self; 3], r: &[Self; 3], s: &[Self; 3]) -> std::cmp::Ordering {
+        let det = geometry_predicates::predicates::orient3d(
+          [p[0].into_inner() as f64, p[1].into_inner() as f64, p[2].into_inner() as f64],
+          [q[0].into_inner() as f64, q[1].into_inner() as f64, q[2].into_inner() as f64],
+          [r[0].into_inner() as f64, r[1].into_inner() as f64, r[2].into_inner() as f64],
+          [s[0].into_inner() as f64, s[1].into_inner() as f64, s[2].into_inner() as f64],
+        );
+        if det > 0.0 {
+          Ordering::Greater
+        } else if det < 0.0 {
+          Ordering::Less
+        } else {
+          Ordering::Equal
+        }
+      }
     })*
   };
 }
@@ -355,6 +530,12 @@ macro_rules! floating_precision {
       fn from_constant(val: i8) -> Self {
         <$ty>::from_i8(val).unwrap()
       }
+      fn to_rational(&self) -> num::BigRational {
+        float_to_rational(*self)
+      }
+      fn from_rational(val: &num::BigRational) -> Self {
+        <$ty>::from_f64(val.to_f64().unwrap()).unwrap()
+      }
       // FIXME: Use `geometry_predicates` to speed up calculation. Right now we're
       // roughly 100x slower than necessary.
       fn cmp_dist(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2]) -> std::cmp::Ordering {
@@ -400,15 +581,47 @@ macro_rules! floating_precision {
           &[float_to_rational(q[0]), float_to_rational(q[1])],
         )
       }
+      // The in-circle test from `geometry_predicates` is exact.
+      fn incircle(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2], s: &[Self; 2]) -> std::cmp::Ordering {
+        let det = geometry_predicates::predicates::incircle(
+          [p[0] as f64, p[1] as f64],
+          [q[0] as f64, q[1] as f64],
+          [r[0] as f64, r[1] as f64],
+          [s[0] as f64, s[1] as f64],
+        );
+        if det > 0.0 {
+          Ordering::Greater
+        } else if det < 0.0 {
+          Ordering::Less
+        } else {
+          Ordering::Equal
+        }
+      }
+      // The 3D orientation test from `geometry_predicates` is exact.
+      fn orient3d(p: &[Self; 3], q: &[Self; 3], r: &[Self; 3], s: &[Self; 3]) -> std::cmp::Ordering {
+        let det = geometry_predicates::predicates::orient3d(
+          [p[0] as f64, p[1] as f64, p[2] as f64],
+          [q[0] as f64, q[1] as f64, q[2] as f64],
+          [r[0] as f64, r[1] as f64, r[2] as f64],
+          [s[0] as f64, s[1] as f64, s[2] as f64],
+        );
+        if det > 0.0 {
+          Ordering::Greater
+        } else if det < 0.0 {
+          Ordering::Less
+        } else {
+          Ordering::Equal
+        }
+      }
     })*
   };
 }
 
-fixed_precision!(i8, u8, i16, u16);
-fixed_precision!(i16, u16, i32, u32);
-fixed_precision!(i32, u32, i64, u64);
-fixed_precision!(i64, u64, i128, u128);
-fixed_precision!(isize, usize, i128, u128);
+fixed_precision!(i8, u8, i16, u16, i64);
+fixed_precision!(i16, u16, i32, u32, i128);
+fixed_precision!(i32, u32, i64, u64, num_bigint::BigInt);
+fixed_precision!(i64, u64, i128, u128, num_bigint::BigInt);
+fixed_precision!(isize, usize, i128, u128, num_bigint::BigInt);
 arbitrary_precision!(num_bigint::BigInt);
 arbitrary_precision!(num_rational::BigRational);
 wrapped_floating_precision!(ordered_float::OrderedFloat<f32>);
@@ -430,6 +643,24 @@ impl PolygonScalar for rug::Integer {
   fn from_constant(val: i8) -> Self {
     rug::Integer::from(val)
   }
+  fn to_rational(&self) -> num::BigRational {
+    let sign = match self.cmp0() {
+      Ordering::Less => num_bigint::Sign::Minus,
+      Ordering::Equal => num_bigint::Sign::NoSign,
+      Ordering::Greater => num_bigint::Sign::Plus,
+    };
+    let digits = self.to_digits::<u32>(rug::integer::Order::Lsf);
+    num::BigRational::from_integer(num::BigInt::new(sign, digits))
+  }
+  fn from_rational(val: &num::BigRational) -> Self {
+    let (sign, digits) = val.round().to_integer().to_u32_digits();
+    let abs = rug::Integer::from_digits(&digits, rug::integer::Order::Lsf);
+    if sign == num_bigint::Sign::Minus {
+      -abs
+    } else {
+      abs
+    }
+  }
   fn cmp_dist(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2]) -> std::cmp::Ordering {
     let [qx, qy] = q.clone();
     let [px, py] = p.clone();
@@ -465,11 +696,124 @@ impl PolygonScalar for rug::Integer {
     let new_y = rug::Integer::from(&p[1] + &vector[0]);
     PolygonScalar::cmp_slope(p, &[new_x, new_y], q)
   }
+  fn cmp_cross(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2], s: &[Self; 2]) -> std::cmp::Ordering {
+    cross_det(p.clone(), q.clone(), r.clone(), s.clone())
+  }
+  fn incircle(p: &[Self; 2], q: &[Self; 2], r: &[Self; 2], s: &[Self; 2]) -> std::cmp::Ordering {
+    incircle_det(p.clone(), q.clone(), r.clone(), s.clone())
+  }
+  fn orient3d(p: &[Self; 3], q: &[Self; 3], r: &[Self; 3], s: &[Self; 3]) -> std::cmp::Ordering {
+    orient3d_det(p.clone(), q.clone(), r.clone(), s.clone())
+  }
 }
 
 fn float_to_rational(f: impl num::traits::float::FloatCore) -> num::BigRational {
   num::BigRational::from_float(f).expect("cannot convert NaN or infinite to exact precision number")
 }
 
+// Sign of the cross product of 'q - p' and 's - r'. Exact if 'T' doesn't
+// overflow.
+fn cross_det<T>(p: [T; 2], q: [T; 2], r: [T; 2], s: [T; 2]) -> Ordering
+where
+  T: Ord + Sub<Output = T> + Mul<Output = T>,
+{
+  let [px, py] = p;
+  let [qx, qy] = q;
+  let [rx, ry] = r;
+  let [sx, sy] = s;
+  ((qx - px) * (sy - ry)).cmp(&((qy - py) * (sx - rx)))
+}
+
+// Sign of the in-circle determinant. Exact if 'T' doesn't overflow.
+fn incircle_det<T>(p: [T; 2], q: [T; 2], r: [T; 2], s: [T; 2]) -> Ordering
+where
+  T: Clone + Ord + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+  let [sx, sy] = s;
+  let lift = |[x, y]: [T; 2]| {
+    let x = x - sx.clone();
+    let y = y - sy.clone();
+    let z = x.clone() * x.clone() + y.clone() * y.clone();
+    (x, y, z)
+  };
+  let (px, py, pz) = lift(p);
+  let (qx, qy, qz) = lift(q);
+  let (rx, ry, rz) = lift(r);
+  // The determinant is 'pos - neg'. Comparing the two sides directly means we
+  // don't need a zero constant.
+  let pos = pz.clone() * qx.clone() * ry.clone()
+    + qz.clone() * rx.clone() * py.clone()
+    + rz.clone() * px.clone() * qy.clone();
+  let neg = pz * rx * qy + qz * px * ry + rz * qx * py;
+  pos.cmp(&neg)
+}
+
+// Sign of the 3D orientation determinant. Exact if 'T' doesn't overflow.
+fn orient3d_det<T>(p: [T; 3], q: [T; 3], r: [T; 3], s: [T; 3]) -> Ordering
+where
+  T: Clone + Ord + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+  let [sx, sy, sz] = s;
+  let translate = |[x, y, z]: [T; 3]| (x - sx.clone(), y - sy.clone(), z - sz.clone());
+  let (px, py, pz) = translate(p);
+  let (qx, qy, qz) = translate(q);
+  let (rx, ry, rz) = translate(r);
+  // Same trick as in 'incircle_det': compare the positive and negative terms.
+  let pos = px.clone() * qy.clone() * rz.clone()
+    + qx.clone() * ry.clone() * pz.clone()
+    + rx.clone() * py.clone() * qz.clone();
+  let neg = px * ry * qz + qx * py * rz + rx * qy * pz;
+  pos.cmp(&neg)
+}
+
 #[cfg(test)]
 pub mod testing;
+
+#[cfg(test)]
+#[cfg(not(tarpaulin_include))]
+mod tests {
+  use super::*;
+
+  use num::{BigInt, BigRational};
+  use test_strategy::proptest;
+
+  fn ratio(numer: i64, denom: i64) -> BigRational {
+    BigRational::new(numer.into(), denom.into())
+  }
+
+  #[test]
+  fn from_rational_rounds() {
+    assert_eq!(i8::from_rational(&ratio(7, 2)), 4);
+    assert_eq!(i8::from_rational(&ratio(-7, 2)), -4);
+    assert_eq!(i8::from_rational(&ratio(-256, 2)), -128);
+    assert_eq!(i64::from_rational(&ratio(i64::MAX, 1)), i64::MAX);
+  }
+
+  #[test]
+  #[should_panic(expected = "200 is out of range for i8")]
+  fn from_rational_out_of_range() {
+    i8::from_rational(&ratio(200, 1));
+  }
+
+  fn big<T: Into<BigInt> + Clone>(pt: &[T; 2]) -> [BigInt; 2] {
+    [pt[0].clone().into(), pt[1].clone().into()]
+  }
+
+  #[proptest]
+  fn cmp_cross_i8_prop(p: [i8; 2], q: [i8; 2], r: [i8; 2], s: [i8; 2]) {
+    let expected = BigInt::cmp_cross(&big(&p), &big(&q), &big(&r), &big(&s));
+    assert_eq!(i8::cmp_cross(&p, &q, &r, &s), expected);
+    let float = |pt: [i8; 2]| pt.map(|v| v as f64);
+    assert_eq!(
+      f64::cmp_cross(&float(p), &float(q), &float(r), &float(s)),
+      expected
+    );
+  }
+
+  // Differences of i64 coordinates overflow i64.
+  #[proptest]
+  fn cmp_cross_i64_no_overflow(p: [i64; 2], q: [i64; 2], r: [i64; 2], s: [i64; 2]) {
+    let expected = BigInt::cmp_cross(&big(&p), &big(&q), &big(&r), &big(&s));
+    assert_eq!(i64::cmp_cross(&p, &q, &r, &s), expected);
+  }
+}
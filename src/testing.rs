@@ -310,6 +310,127 @@ pub fn polygon_big() -> impl Strategy<Value = Polygon<BigRational>> {
   )
 }
 
+///////////////////////////////////////////////////////////////////////////////
+// Fixtures
+
+pub fn rational<T: PolygonScalar>(poly: Polygon<T>) -> Polygon<BigRational> {
+  poly.map(|v| v.to_rational())
+}
+
+// Counter-clockwise square with its lower left corner at (x, y).
+pub fn square<T: PolygonScalar>(x: T, y: T, size: T) -> Polygon<T> {
+  let (x2, y2) = (x.clone() + size.clone(), y.clone() + size);
+  Polygon::new(vec![
+    Point::new([x.clone(), y.clone()]),
+    Point::new([x2.clone(), y]),
+    Point::new([x2, y2.clone()]),
+    Point::new([x, y2]),
+  ])
+  .unwrap()
+}
+
+// Square of size 12 with a square hole of size 4 in the middle.
+pub fn frame() -> Polygon<i32> {
+  Polygon::new_with_holes(
+    square(0, 0, 12).points,
+    vec![vec![
+      Point::new([4, 4]),
+      Point::new([4, 8]),
+      Point::new([8, 8]),
+      Point::new([8, 4]),
+    ]],
+  )
+  .unwrap()
+}
+
+///////////////////////////////////////////////////////////////////////////////
+// Polygons with holes
+
+// Place the polygons side by side as holes inside a rectangular boundary.
+pub fn polygon_with_holes(holes: &[Polygon<i8>]) -> Polygon<BigRational> {
+  let mut rings = vec![];
+  let mut offset = 1;
+  let mut height = 0;
+  for hole in holes {
+    let (min, max) = hole.bounding_box();
+    let mut ring: Vec<Point<BigRational>> = hole
+      .iter_boundary()
+      .map(|pt| {
+        Point::new([
+          BigRational::from_integer((*pt.x_coord() as i64 - *min.x_coord() as i64 + offset).into()),
+          BigRational::from_integer((*pt.y_coord() as i64 - *min.y_coord() as i64 + 1).into()),
+        ])
+      })
+      .collect();
+    ring.reverse();
+    rings.push(ring);
+    offset += *max.x_coord() as i64 - *min.x_coord() as i64 + 1;
+    height = height.max(*max.y_coord() as i64 - *min.y_coord() as i64);
+  }
+  let (width, height) = (offset, height + 2);
+  let corner = |x: i64, y: i64| Point::new([x, y].map(|v| BigRational::from_integer(v.into())));
+  let boundary = vec![
+    corner(0, 0),
+    corner(width, 0),
+    corner(width, height),
+    corner(0, height),
+  ];
+  Polygon::new_with_holes(boundary, rings).unwrap()
+}
+
+// Chain the polygons as holes that touch at single vertices: The first hole
+// touches a reflex vertex of the boundary and every other hole touches the one
+// before it. The shared points are separate vertices of each ring.
+pub fn polygon_with_touching_holes(holes: &[Polygon<i8>]) -> Polygon<BigRational> {
+  let mut rings: Vec<Vec<Point<i64>>> = vec![];
+  let mut touch = Point::new([0, 0]);
+  for hole in holes {
+    let ring: Vec<Point<i64>> = hole
+      .iter_boundary()
+      .map(|pt| Point::new([*pt.x_coord() as i64, *pt.y_coord() as i64]))
+      .collect();
+    // The left-most vertex is the highest of the left-most vertices and the
+    // right-most vertex is the lowest of the right-most vertices. Holes then
+    // only share the touching vertex, even if they have vertical edges.
+    let key = |pt: &Point<i64>| (pt.array[0], -pt.array[1]);
+    let left = *ring.iter().min_by_key(|pt| key(pt)).unwrap();
+    let right = *ring.iter().max_by_key(|pt| key(pt)).unwrap();
+    let (dx, dy) = (
+      touch.array[0] - left.array[0],
+      touch.array[1] - left.array[1],
+    );
+    let mut ring: Vec<Point<i64>> = ring
+      .into_iter()
+      .map(|pt| Point::new([pt.array[0] + dx, pt.array[1] + dy]))
+      .collect();
+    ring.reverse();
+    rings.push(ring);
+    touch = Point::new([right.array[0] + dx, right.array[1] + dy]);
+  }
+  let ys = || rings.iter().flatten().map(|pt| pt.array[1]);
+  let (bottom, top) = (ys().min().unwrap_or(0) - 1, ys().max().unwrap_or(0) + 1);
+  let right = touch.array[0] + 1;
+  // The boundary has a notch on the left with its tip at the origin.
+  let boundary = vec![
+    Point::new([-1, bottom]),
+    Point::new([right, bottom]),
+    Point::new([right, top]),
+    Point::new([-1, top]),
+    Point::new([0, 0]),
+  ];
+  let rational = |ring: Vec<Point<i64>>| {
+    ring
+      .into_iter()
+      .map(|pt| pt.map(|v| BigRational::from_integer(v.into())))
+      .collect()
+  };
+  Polygon::new_with_holes(
+    rational(boundary),
+    rings.into_iter().map(rational).collect(),
+  )
+  .unwrap()
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 // Arbitrary convex polygons
 
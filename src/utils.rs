@@ -1,6 +1,10 @@
+use rand::rngs::SmallRng;
 use rand::Rng;
+use rand::SeedableRng;
 use std::ops::{Index, IndexMut};
 
+pub mod exact;
+
 pub type SparseIndex = usize;
 pub struct SparseVec<T> {
   dense: DenseCollection,
@@ -113,6 +117,170 @@ impl DenseCollection {
   }
 }
 
+///////////////////////////////////////////////////////////////////////////////
+// Treap
+
+// Balanced binary tree without keys. Elements are kept in the order they were
+// inserted and the position of an element is given by a monotone predicate
+// (eg. "is this line segment below the sweep point?"). This is exactly what
+// sweep-line algorithms need: The ordering of the elements changes as the sweep
+// progresses but remains consistent for the elements that are in the tree.
+//
+// All operations take O(log n) expected time (plus the size of the output).
+const NIL: usize = usize::MAX;
+
+struct TreapNode<T> {
+  value: T,
+  priority: u64,
+  left: usize,
+  right: usize,
+}
+
+pub struct Treap<T> {
+  nodes: Vec<TreapNode<T>>,
+  free: Vec<usize>,
+  root: usize,
+  rng: SmallRng,
+}
+
+impl<T: Copy> Treap<T> {
+  pub fn new() -> Treap<T> {
+    Treap {
+      nodes: Vec::new(),
+      free: Vec::new(),
+      root: NIL,
+      rng: SmallRng::seed_from_u64(0xDEADBEEF),
+    }
+  }
+
+  /// Remove and return all elements that are neither below nor above the pivot.
+  /// Elements are returned in tree order.
+  pub fn remove_range<B, A>(&mut self, is_below: B, is_above: A) -> Vec<T>
+  where
+    B: Fn(&T) -> bool,
+    A: Fn(&T) -> bool,
+  {
+    let (below, rest) = self.split(self.root, &|v| is_below(v));
+    let (middle, above) = self.split(rest, &|v| !is_above(v));
+    let mut out = Vec::new();
+    self.drain(middle, &mut out);
+    self.root = self.merge(below, above);
+    out
+  }
+
+  /// Insert a sorted list of elements right after the last element that is below
+  /// the pivot. Returns the elements immediately below and above the inserted
+  /// elements.
+  pub fn insert_range<B>(&mut self, is_below: B, values: &[T]) -> (Option<T>, Option<T>)
+  where
+    B: Fn(&T) -> bool,
+  {
+    let (below, above) = self.split(self.root, &|v| is_below(v));
+    let lower = self.last(below);
+    let upper = self.first(above);
+    let mut middle = NIL;
+    for &value in values {
+      let node = self.alloc(value);
+      middle = self.merge(middle, node);
+    }
+    let below = self.merge(below, middle);
+    self.root = self.merge(below, above);
+    (lower, upper)
+  }
+
+  /// Return the first element in tree order.
+  pub fn min(&self) -> Option<T> {
+    self.first(self.root)
+  }
+
+  fn alloc(&mut self, value: T) -> usize {
+    let node = TreapNode {
+      value,
+      priority: self.rng.gen(),
+      left: NIL,
+      right: NIL,
+    };
+    match self.free.pop() {
+      Some(idx) => {
+        self.nodes[idx] = node;
+        idx
+      }
+      None => {
+        self.nodes.push(node);
+        self.nodes.len() - 1
+      }
+    }
+  }
+
+  // Move every element of the tree to 'out' and free the nodes.
+  fn drain(&mut self, node: usize, out: &mut Vec<T>) {
+    if node != NIL {
+      let (left, right) = (self.nodes[node].left, self.nodes[node].right);
+      self.drain(left, out);
+      out.push(self.nodes[node].value);
+      self.free.push(node);
+      self.drain(right, out);
+    }
+  }
+
+  // Split a tree in two. Elements satisfying 'pred' go in the left tree.
+  // The predicate must be monotone: true for a prefix of the elements and false
+  // for the rest.
+  fn split(&mut self, node: usize, pred: &dyn Fn(&T) -> bool) -> (usize, usize) {
+    if node == NIL {
+      return (NIL, NIL);
+    }
+    if pred(&self.nodes[node].value) {
+      let (left, right) = self.split(self.nodes[node].right, pred);
+      self.nodes[node].right = left;
+      (node, right)
+    } else {
+      let (left, right) = self.split(self.nodes[node].left, pred);
+      self.nodes[node].left = right;
+      (left, node)
+    }
+  }
+
+  // Concatenate two trees. All elements in 'a' go before the elements in 'b'.
+  fn merge(&mut self, a: usize, b: usize) -> usize {
+    if a == NIL {
+      return b;
+    }
+    if b == NIL {
+      return a;
+    }
+    if self.nodes[a].priority > self.nodes[b].priority {
+      let right = self.merge(self.nodes[a].right, b);
+      self.nodes[a].right = right;
+      a
+    } else {
+      let left = self.merge(a, self.nodes[b].left);
+      self.nodes[b].left = left;
+      b
+    }
+  }
+
+  fn first(&self, mut node: usize) -> Option<T> {
+    if node == NIL {
+      return None;
+    }
+    while self.nodes[node].left != NIL {
+      node = self.nodes[node].left;
+    }
+    Some(self.nodes[node].value)
+  }
+
+  fn last(&self, mut node: usize) -> Option<T> {
+    if node == NIL {
+      return None;
+    }
+    while self.nodes[node].right != NIL {
+      node = self.nodes[node].right;
+    }
+    Some(self.nodes[node].value)
+  }
+}
+
 ///////////////////////////////////////////////////////////////////////////////
 // Iterator permutations
 
@@ -0,0 +1,47 @@
+// Exact arithmetic on points with BigRational coordinates. Algorithms that
+// create new points convert their input with 'to_rational' so intersections
+// can't overflow or drift.
+use num::BigRational;
+use num_traits::Zero;
+
+use crate::data::Point;
+use crate::PolygonScalar;
+
+pub type Vec2 = [BigRational; 2];
+
+pub fn to_rational<T: PolygonScalar>(pt: &Point<T, 2>) -> Point<BigRational, 2> {
+  Point::new([pt.array[0].to_rational(), pt.array[1].to_rational()])
+}
+
+pub fn sub(p: &Point<BigRational, 2>, q: &Point<BigRational, 2>) -> Vec2 {
+  [&p.array[0] - &q.array[0], &p.array[1] - &q.array[1]]
+}
+
+pub fn cross(a: &Vec2, b: &Vec2) -> BigRational {
+  &a[0] * &b[1] - &a[1] * &b[0]
+}
+
+pub fn dot(a: &Vec2, b: &Vec2) -> BigRational {
+  &a[0] * &b[0] + &a[1] * &b[1]
+}
+
+// Intersection of the line through 'a' and 'b' with the line through 'c' and
+// 'd'. The lines must not be parallel.
+pub fn line_intersection(
+  a: &Point<BigRational, 2>,
+  b: &Point<BigRational, 2>,
+  c: &Point<BigRational, 2>,
+  d: &Point<BigRational, 2>,
+) -> Point<BigRational, 2> {
+  let (da, dc) = (sub(b, a), sub(d, c));
+  let t = cross(&sub(c, a), &dc) / cross(&da, &dc);
+  Point::new([&a.array[0] + &t * &da[0], &a.array[1] + t * &da[1]])
+}
+
+// Twice the signed area of a ring. Positive if the ring is counter-clockwise.
+pub fn signed_area_2x(ring: &[Point<BigRational, 2>]) -> BigRational {
+  (0..ring.len()).fold(BigRational::zero(), |acc, i| {
+    let (p, q) = (&ring[i], &ring[(i + 1) % ring.len()]);
+    acc + &p.array[0] * &q.array[1] - &q.array[0] * &p.array[1]
+  })
+}